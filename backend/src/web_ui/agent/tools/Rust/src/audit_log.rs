@@ -0,0 +1,103 @@
+use std::{
+    fs::OpenOptions,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use tokio::{io::AsyncWriteExt, sync::mpsc, task::JoinHandle};
+
+use crate::error::{ServiceError, ServiceResult};
+
+/// A single audit log record, written as one line of newline-delimited JSON.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry<'a> {
+    pub timestamp: String,
+    pub tool: &'a str,
+    pub paths: Vec<String>,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Appends a record of every tool invocation to a newline-delimited JSON file.
+/// Records are handed to a background task, so logging never blocks a tool call. A record that
+/// can't be written is reported on stderr, and the writer carries on with the next one.
+pub struct AuditLog {
+    /// Taken by `close`, after which records are refused.
+    sender: Mutex<Option<mpsc::UnboundedSender<String>>>,
+    writer: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl AuditLog {
+    /// Opens the audit log for appending, creating it if it doesn't exist, and starts the
+    /// background writer. Must be called from within a tokio runtime.
+    pub fn open(path: &Path) -> ServiceResult<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut file = tokio::fs::File::from_std(file);
+
+        let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+        let writer = tokio::spawn(async move {
+            while let Some(line) = receiver.recv().await {
+                let written = async {
+                    file.write_all(line.as_bytes()).await?;
+                    file.flush().await
+                }
+                .await;
+                if let Err(err) = written {
+                    eprintln!("Failed to write the audit log: {}", err);
+                }
+            }
+        });
+
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            writer: Mutex::new(Some(writer)),
+        })
+    }
+
+    /// Records a tool call along with the validated paths it operated on.
+    /// `error` is `None` when the call succeeded.
+    pub fn record(
+        &self,
+        tool: &str,
+        paths: &[PathBuf],
+        error: Option<String>,
+    ) -> ServiceResult<()> {
+        let entry = AuditEntry {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            tool,
+            paths: paths.iter().map(|p| p.display().to_string()).collect(),
+            success: error.is_none(),
+            error,
+        };
+
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        let sender = self.sender.lock().ok();
+        sender
+            .as_ref()
+            .and_then(|sender| sender.as_ref())
+            .ok_or_else(|| ServiceError::FromString("Audit log is closed".to_string()))?
+            .send(line)
+            .map_err(|_| {
+                ServiceError::FromString("Audit log writer is no longer running".to_string())
+            })
+    }
+
+    /// Waits until all pending records are written and closes the log. Later records are
+    /// refused; closing again does nothing.
+    pub async fn close(&self) -> ServiceResult<()> {
+        if let Ok(mut sender) = self.sender.lock() {
+            sender.take();
+        }
+        let writer = self.writer.lock().ok().and_then(|mut writer| writer.take());
+        match writer {
+            Some(writer) => writer
+                .await
+                .map_err(|err| ServiceError::FromString(err.to_string())),
+            None => Ok(()),
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use clap::{arg, command, Parser};
+use std::path::Path;
+
+use clap::Parser;
+use serde::Deserialize;
+
+use crate::error::{ServiceError, ServiceResult};
+
+/// Environment variable listing allowed directories, used when none are given as arguments.
+pub const ALLOWED_DIRS_ENV: &str = "MCP_FS_ALLOWED_DIRS";
 
 #[derive(Parser, Debug)]
 #[command(name =  env!("CARGO_PKG_NAME"))]
@@ -6,16 +14,193 @@ use clap::{arg, command, Parser};
 #[command(about = "A lightning-fast, asynchronous, and lightweight MCP server designed for efficient handling of various filesystem operations", 
 long_about = None)]
 pub struct CommandArguments {
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Loads allowed directories and options from a TOML file. Command-line flags take precedence over the file, and directories and deny entries from both are combined."
+    )]
+    pub config: Option<String>,
     #[arg(
         short = 'w',
         long,
+        overrides_with = "no_allow_write",
         help = "Enables read/write mode for the app, allowing both reading and writing."
     )]
     pub allow_write: bool,
     #[arg(
-        help = "List of directories that are permitted for the operation.",
-        long_help = concat!("Provide a space-separated list of directories that are permitted for the operation.\nThis list allows multiple directories to be provided.\n\nExample:  ", env!("CARGO_PKG_NAME"), " /path/to/dir1 /path/to/dir2 /path/to/dir3"),
-        required = true
+        long,
+        overrides_with = "allow_write",
+        help = "Disables read/write mode, even if the config file enables it."
+    )]
+    pub no_allow_write: bool,
+    #[arg(
+        long,
+        overrides_with = "no_backup",
+        help = "Saves the previous content of a file to '<path>.bak' before it is overwritten by write_file or edit_file."
+    )]
+    pub backup: bool,
+    #[arg(
+        long,
+        overrides_with = "backup",
+        help = "Disables backups, even if the config file enables them."
+    )]
+    pub no_backup: bool,
+    #[arg(
+        long,
+        value_name = "PATH_OR_GLOB",
+        help = "Denies access to a path or glob pattern (e.g. '**/.git') and everything below it, even inside an allowed directory. Can be repeated."
+    )]
+    pub deny: Vec<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Appends a newline-delimited JSON record of every tool call (tool, paths, timestamp, outcome) to the given file."
+    )]
+    pub audit_log: Option<String>,
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Moves items removed by the delete tool into this directory instead of deleting them permanently. Must be inside an allowed directory."
+    )]
+    pub trash_dir: Option<String>,
+    #[arg(
+        long,
+        value_name = "BYTES",
+        help = "Rejects unzip_file when the total uncompressed size declared by the archive exceeds this many bytes."
+    )]
+    pub max_extract_size: Option<u64>,
+    #[arg(
+        long,
+        value_name = "N",
+        help = "Runs at most this many tool calls at the same time when requests are dispatched concurrently; further calls wait until one finishes. The built-in stdio server handles one request at a time, so there the limit has no effect."
+    )]
+    pub max_concurrent_operations: Option<usize>,
+    #[arg(
+        long,
+        value_name = "OPS_PER_SEC",
+        help = "Limits tool calls to this many per second on average, allowing bursts of up to one second's worth. Calls over the limit fail with a retriable error."
+    )]
+    pub rate_limit: Option<f64>,
+    #[arg(
+        long,
+        value_name = "TOOL",
+        help = "Exposes only the given tools (e.g. 'read_file'); all others are hidden and rejected. Can be repeated."
+    )]
+    pub enable: Vec<String>,
+    #[arg(
+        long,
+        value_name = "TOOL",
+        help = "Hides and rejects the given tool (e.g. 'delete'), even if it is listed with --enable. Can be repeated."
+    )]
+    pub disable: Vec<String>,
+    /// Raw value of `MCP_FS_ALLOWED_DIRS`, split by `with_env_allowed_directories`.
+    #[arg(long, env = ALLOWED_DIRS_ENV, hide = true)]
+    pub allowed_directories_env: Option<String>,
+    #[arg(
+        help = "List of directories that are permitted for the operation. Glob patterns (e.g. /srv/projects/*/data) are expanded at startup. Append ':ro' or ':rw' to set per-directory access.",
+        long_help = concat!("Provide a space-separated list of directories that are permitted for the operation.\nThis list allows multiple directories to be provided.\nGlob patterns are expanded at startup to all matching directories; quote them to prevent shell expansion.\nAppend ':ro' or ':rw' to a directory to make it read-only or writable regardless of --allow-write.\nWhen no directories are given, they are read from the MCP_FS_ALLOWED_DIRS environment variable as a ':'-separated list (';' on Windows).\n\nExample:  ", env!("CARGO_PKG_NAME"), " /path/to/dir1 /path/to/dir2:ro /path/to/out:rw '/srv/projects/*/data'"),
+        required_unless_present_any = ["config", "allowed_directories_env"]
     )]
     pub allowed_directories: Vec<String>,
 }
+
+/// Options read from a `--config` TOML file. Keys mirror the long command-line flags.
+#[derive(Deserialize, Debug, Default)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ConfigFile {
+    pub allowed_directories: Vec<String>,
+    pub allow_write: bool,
+    pub backup: bool,
+    pub deny: Vec<String>,
+    pub audit_log: Option<String>,
+    pub trash_dir: Option<String>,
+    pub max_extract_size: Option<u64>,
+    pub max_concurrent_operations: Option<usize>,
+    pub rate_limit: Option<f64>,
+    pub enable: Vec<String>,
+    pub disable: Vec<String>,
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> ServiceResult<Self> {
+        let content = std::fs::read_to_string(path).map_err(|err| {
+            ServiceError::FromString(format!(
+                "Error: failed to read config file '{}': {}",
+                path.display(),
+                err
+            ))
+        })?;
+        toml::from_str(&content).map_err(|err| {
+            ServiceError::FromString(format!(
+                "Error: invalid config file '{}': {}",
+                path.display(),
+                err
+            ))
+        })
+    }
+}
+
+impl CommandArguments {
+    /// Uses the directories listed in `MCP_FS_ALLOWED_DIRS` when none were given as arguments.
+    /// Directories on the command line always take precedence over the environment variable.
+    pub fn with_env_allowed_directories(mut self) -> Self {
+        if let Some(env_dirs) = self.allowed_directories_env.take() {
+            if self.allowed_directories.is_empty() {
+                self.allowed_directories = split_allowed_directories(&env_dirs);
+            }
+        }
+        self
+    }
+
+    /// Merges the options of the `--config` file, if any, into the command-line arguments.
+    pub fn with_config_file(mut self) -> ServiceResult<Self> {
+        let Some(config_path) = &self.config else {
+            return Ok(self);
+        };
+        let config = ConfigFile::load(Path::new(config_path))?;
+
+        self.allowed_directories = config
+            .allowed_directories
+            .into_iter()
+            .chain(self.allowed_directories)
+            .collect();
+        self.deny = config.deny.into_iter().chain(self.deny).collect();
+        self.enable = config.enable.into_iter().chain(self.enable).collect();
+        self.disable = config.disable.into_iter().chain(self.disable).collect();
+        self.allow_write = !self.no_allow_write && (self.allow_write || config.allow_write);
+        self.backup = !self.no_backup && (self.backup || config.backup);
+        self.audit_log = self.audit_log.or(config.audit_log);
+        self.trash_dir = self.trash_dir.or(config.trash_dir);
+        self.max_extract_size = self.max_extract_size.or(config.max_extract_size);
+        self.max_concurrent_operations = self
+            .max_concurrent_operations
+            .or(config.max_concurrent_operations);
+        self.rate_limit = self.rate_limit.or(config.rate_limit);
+
+        if self.allowed_directories.is_empty() {
+            return Err(ServiceError::FromString(format!(
+                "Error: no allowed directories given on the command line or in '{}'",
+                config_path
+            )));
+        }
+        Ok(self)
+    }
+}
+
+/// Splits an `MCP_FS_ALLOWED_DIRS` value into directories. Entries are separated by ':' (';' on
+/// Windows); on Unix, a trailing `:ro` or `:rw` stays attached to its directory.
+pub fn split_allowed_directories(value: &str) -> Vec<String> {
+    let separator = if cfg!(windows) { ';' } else { ':' };
+    let mut dirs: Vec<String> = vec![];
+    for part in value.split(separator) {
+        match dirs.last_mut() {
+            Some(last) if separator == ':' && (part == "ro" || part == "rw") => {
+                last.push(':');
+                last.push_str(part);
+            }
+            _ if part.is_empty() => {}
+            _ => dirs.push(part.to_string()),
+        }
+    }
+    dirs
+}
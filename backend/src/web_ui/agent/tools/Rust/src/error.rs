@@ -12,6 +12,20 @@ pub type ServiceResult<T> = core::result::Result<T, ServiceError>;
 pub enum ServiceError {
     #[error("Service is running in read-only mode. To enable write access, please run with the --allow-write flag.")]
     NoWriteAccess,
+    #[error("Access denied - {0}")]
+    AccessDenied(String),
+    #[error("Access denied - path is outside allowed directories: {path} not in {allowed}")]
+    OutsideAllowedDirectories { path: String, allowed: String },
+    #[error("Access denied - a symlink target path is outside allowed directories: {path} not in {allowed}")]
+    SymlinkOutsideAllowedDirectories { path: String, allowed: String },
+    #[error("No such file or directory: {0}")]
+    NotFound(String),
+    #[error("Access denied - {0} is in a read-only directory")]
+    ReadOnlyDirectory(String),
+    #[error("Tool '{0}' is disabled on this server.")]
+    ToolDisabled(String),
+    #[error("Rate limit exceeded, please retry in {retry_after_ms} ms.")]
+    RateLimited { retry_after_ms: u64 },
     #[error("{0}")]
     FromString(String),
     #[error("{0}")]
@@ -30,4 +44,66 @@ pub enum ServiceError {
     ZipError(#[from] ZipError),
     #[error("{0}")]
     GlobPatternError(#[from] PatternError),
+    #[error("Invalid base64 content: {0}")]
+    Base64DecodeError(#[from] base64::DecodeError),
+    #[error("Invalid regex pattern: {0}")]
+    RegexError(#[from] regex::Error),
+    #[error("Write conflict for '{path}': expected sha256 {expected}, but the file {actual}.")]
+    WriteConflict {
+        path: String,
+        expected: String,
+        actual: String,
+    },
+    #[error("Archive would extract to {size} bytes, which exceeds the maximum extract size of {limit} bytes.")]
+    ExtractSizeExceeded { size: u64, limit: u64 },
+    #[error("Archive entry '{entry}' expands from {compressed} to {uncompressed} bytes, which exceeds the maximum compression ratio of {limit}:1.")]
+    CompressionRatioExceeded {
+        entry: String,
+        compressed: u64,
+        uncompressed: u64,
+        limit: u64,
+    },
+    #[error("Files to archive total {size} bytes, which exceeds the in-memory archive limit of {limit} bytes. Write the archive to a file instead.")]
+    InMemoryZipSizeExceeded { size: u64, limit: u64 },
+    /// An error that was reduced to its message and code, e.g. to move it across tasks.
+    #[error("{message}")]
+    WithCode { code: &'static str, message: String },
+}
+
+impl ServiceError {
+    /// A stable, machine-readable code for the kind of error, such as `ACCESS_DENIED` or
+    /// `NOT_FOUND`, that clients can branch on instead of matching the message.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServiceError::NoWriteAccess | ServiceError::ReadOnlyDirectory(_) => "READ_ONLY",
+            ServiceError::AccessDenied(_)
+            | ServiceError::OutsideAllowedDirectories { .. }
+            | ServiceError::SymlinkOutsideAllowedDirectories { .. } => "ACCESS_DENIED",
+            ServiceError::NotFound(_) => "NOT_FOUND",
+            ServiceError::ToolDisabled(_) => "TOOL_DISABLED",
+            ServiceError::RateLimited { .. } => "RATE_LIMITED",
+            ServiceError::WriteConflict { .. } => "WRITE_CONFLICT",
+            ServiceError::ExtractSizeExceeded { .. }
+            | ServiceError::CompressionRatioExceeded { .. }
+            | ServiceError::InMemoryZipSizeExceeded { .. } => "TOO_LARGE",
+            ServiceError::Base64DecodeError(_) => "INVALID_ENCODING",
+            ServiceError::GlobPatternError(_) | ServiceError::RegexError(_) => "INVALID_PATTERN",
+            ServiceError::SerdeJsonError(_) => "INVALID_ARGUMENT",
+            ServiceError::IoError(err) => match err.kind() {
+                io::ErrorKind::NotFound => "NOT_FOUND",
+                io::ErrorKind::PermissionDenied => "ACCESS_DENIED",
+                io::ErrorKind::AlreadyExists => "ALREADY_EXISTS",
+                io::ErrorKind::InvalidInput => "INVALID_ARGUMENT",
+                io::ErrorKind::InvalidData => "INVALID_ENCODING",
+                _ => "IO_ERROR",
+            },
+            ServiceError::ZipError(_) => "ARCHIVE_ERROR",
+            ServiceError::FromString(_) => "OPERATION_FAILED",
+            ServiceError::WithCode { code, .. } => code,
+            ServiceError::TransportError(_)
+            | ServiceError::SdkError(_)
+            | ServiceError::RpcError(_)
+            | ServiceError::McpSdkError(_) => "INTERNAL_ERROR",
+        }
+    }
 }
@@ -1,63 +1,518 @@
 pub mod file_info;
+pub mod indentation;
+pub mod line_endings;
 pub mod utils;
+pub mod word_count;
 
 use file_info::FileInfo;
+use indentation::IndentationReport;
+use line_endings::{restore_line_endings, LineEnding, LineEndingCounts};
+use word_count::WordCount;
 
 use std::{
+    cmp::Ordering,
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
     env,
     fs::{self},
+    io::SeekFrom,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
 };
 
-use async_zip::tokio::{read::seek::ZipFileReader, write::ZipFileWriter};
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+use async_zip::{
+    tokio::{read::seek::ZipFileReader, write::ZipFileWriter},
+    StoredZipEntry, ZipEntry, ZipEntryBuilder,
+};
+use filetime::FileTime;
+use flate2::{read::GzDecoder, write::GzEncoder};
 use glob::Pattern;
+use regex::Regex;
 use rust_mcp_schema::RpcError;
 use similar::TextDiff;
 use tokio::{
     fs::File,
-    io::{AsyncWriteExt, BufReader},
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader},
+};
+use tokio_util::{
+    compat::{FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt},
+    sync::CancellationToken,
 };
-use tokio_util::compat::{FuturesAsyncReadCompatExt, TokioAsyncReadCompatExt};
 use utils::{
-    contains_symlink, expand_home, format_bytes, normalize_line_endings, normalize_path,
-    write_zip_entry,
+    check_edit_span, check_in_memory_zip_size, closest_line_window, closest_match_report,
+    compute_digest, contains_symlink, copy_preserving_metadata, decode_bytes, delete_lines,
+    detect_charset, detect_mime_type, expand_braces, expand_home, file_identity, format_bytes,
+    format_hex_dump, format_permissions, glob_set, insert_lines, io_error_at, is_glob_pattern,
+    is_hidden, is_probably_binary, is_same_file, line_span, merge_conflicts, merge_directories,
+    normalize_line_endings, normalize_path, parse_rfc3339, relative_slash_path, rename_or_copy,
+    render_template_content, sanitize_filename, temp_sibling_path, unique_path, write_atomic,
+    write_zip_directory_entries, write_zip_entry, zip_entry_path, zip_file_entries,
+    zip_root_prefix,
 };
 use walkdir::WalkDir;
 
 use crate::{
     error::{ServiceError, ServiceResult},
-    tools::EditOperation,
+    tools::{DeleteLinesOperation, EditOperation, InsertOperation},
 };
 
+/// Receives `(processed, total)` counts as a long-running operation makes progress.
+pub type ProgressCallback = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Largest uncompressed-to-compressed size ratio accepted for an archive entry by `unzip_file`.
+pub const MAX_COMPRESSION_RATIO: u64 = 100;
+/// Entries up to this uncompressed size are exempt from the compression ratio check, so that
+/// small, highly repetitive files can still be extracted.
+const MAX_COMPRESSION_RATIO_GRACE_SIZE: u64 = 1024 * 1024;
+/// Largest total size of the input files accepted when building a ZIP archive in memory.
+pub const MAX_IN_MEMORY_ZIP_SIZE: u64 = 8 * 1024 * 1024;
+/// Size cap of a `bundle_directory` bundle when none is given.
+pub const DEFAULT_BUNDLE_MAX_SIZE: u64 = 1024 * 1024;
+/// Number of bytes `hex_dump` reads when no length is given.
+pub const DEFAULT_HEX_DUMP_LENGTH: u64 = 256;
+/// Most bytes `hex_dump` reads; longer requests are cut to this length.
+pub const MAX_HEX_DUMP_LENGTH: u64 = 64 * 1024;
+
+/// Access granted to an allowed directory, configured with a `:ro` or `:rw` suffix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLevel {
+    ReadOnly,
+    ReadWrite,
+}
+
+impl AccessLevel {
+    /// Splits a trailing `:ro` or `:rw` access suffix off an allowed directory entry.
+    pub fn split_suffix(dir: &str) -> (&str, Option<AccessLevel>) {
+        if let Some(path) = dir.strip_suffix(":ro") {
+            (path, Some(AccessLevel::ReadOnly))
+        } else if let Some(path) = dir.strip_suffix(":rw") {
+            (path, Some(AccessLevel::ReadWrite))
+        } else {
+            (dir, None)
+        }
+    }
+}
+
 pub struct FileSystemService {
     allowed_path: Vec<PathBuf>,
+    /// Allowed directories with an explicit access level, overriding `default_access`.
+    directory_access: Vec<(PathBuf, AccessLevel)>,
+    default_access: AccessLevel,
+    denied_patterns: Vec<Pattern>,
+    /// Deleted items are moved here instead of being removed, when set.
+    trash_dir: Option<PathBuf>,
+    /// Upper bound on the total uncompressed size of an archive extracted by `unzip_file`.
+    max_extract_size: Option<u64>,
+    backup: bool,
+}
+
+/// Collects the paths under `root` (down to `max_depth` levels) that are not excluded by
+/// `.gitignore` or `.ignore` files, including those in parent directories of `root`.
+/// Hidden files are kept; only ignore rules filter entries.
+fn not_ignored_paths(root: &Path, max_depth: Option<usize>) -> HashSet<PathBuf> {
+    ignore::WalkBuilder::new(root)
+        .standard_filters(false)
+        .git_ignore(true)
+        .git_exclude(true)
+        .ignore(true)
+        .parents(true)
+        .require_git(false)
+        .follow_links(true)
+        .max_depth(max_depth)
+        .build()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+/// Turns an entry type filter (`file` or `dir`) into whether files and directories are wanted.
+fn parse_entry_type(entry_type: Option<&str>) -> ServiceResult<(bool, bool)> {
+    match entry_type {
+        None => Ok((true, true)),
+        Some("file") => Ok((true, false)),
+        Some("dir") | Some("directory") => Ok((false, true)),
+        Some(other) => Err(ServiceError::FromString(format!(
+            "Invalid type '{}'. Expected 'file' or 'dir'",
+            other
+        ))),
+    }
+}
+
+/// Ordering and paging options for `FileSystemService::list_directory`.
+#[derive(Debug, Default, Clone)]
+pub struct ListDirectoryOptions {
+    /// Number of entries to skip.
+    pub offset: Option<usize>,
+    /// Maximum number of entries to return.
+    pub limit: Option<usize>,
+    /// Sort key: `name`, `size` or `modified`.
+    pub sort_by: Option<String>,
+    /// Reverse the sort order.
+    pub reverse: bool,
+    /// List directories before files.
+    pub directories_first: bool,
+    /// Fetch each entry's metadata during the listing.
+    pub with_metadata: bool,
+    /// Leave out entries matched by `.gitignore` (and `.ignore`) files.
+    pub respect_gitignore: bool,
+    /// Leave out hidden entries (see `utils::is_hidden`).
+    pub skip_hidden: bool,
+}
+
+/// A directory entry, with its metadata when it was requested or needed for sorting.
+pub struct DirectoryEntry {
+    pub entry: tokio::fs::DirEntry,
+    pub metadata: Option<fs::Metadata>,
+}
+
+impl DirectoryEntry {
+    pub fn file_name(&self) -> std::ffi::OsString {
+        self.entry.file_name()
+    }
+
+    pub fn path(&self) -> PathBuf {
+        self.entry.path()
+    }
+}
+
+/// A page of directory entries returned by `FileSystemService::list_directory`.
+pub struct DirectoryPage {
+    pub entries: Vec<DirectoryEntry>,
+    /// Offset of the next page, or `None` when there are no more entries.
+    pub next_offset: Option<usize>,
+}
+
+/// File content returned by `FileSystemService::read_file_decoded`.
+#[derive(Debug, Clone)]
+pub struct DecodedFile {
+    pub content: String,
+    /// Name of the charset detected in `auto` mode, e.g. `UTF-16LE` or `windows-1252`.
+    pub detected_encoding: Option<&'static str>,
+    /// Digest of the raw file bytes, when one was requested.
+    pub digest: Option<String>,
+}
+
+/// What kind of entry a path refers to, returned by `FileSystemService::path_status`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct PathStatus {
+    pub exists: bool,
+    pub is_file: bool,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+/// Capacity of the filesystem holding a path, returned by `FileSystemService::disk_space`.
+#[derive(Debug, Clone, Copy)]
+pub struct DiskSpace {
+    pub total: u64,
+    pub used: u64,
+    /// Space available to the server's user, which may be less than `total - used`.
+    pub available: u64,
+}
+
+/// A byte range of a file returned by `FileSystemService::read_file_chunk`.
+#[derive(Debug, Clone)]
+pub struct FileChunk {
+    pub offset: u64,
+    pub data: Vec<u8>,
+    /// Size of the whole file at the time of the read.
+    pub total_size: u64,
+    /// True when the chunk reaches the end of the file.
+    pub eof: bool,
+}
+
+/// What happened to one pair of a `FileSystemService::batch_move`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveStatus {
+    Moved,
+    Failed(String),
+    /// The move succeeded but was undone because a later move failed.
+    RolledBack,
+    /// The move succeeded, but undoing it after a later failure did not.
+    RollbackFailed(String),
+    /// Not attempted because an earlier move failed.
+    Skipped,
+}
+
+impl std::fmt::Display for MoveStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveStatus::Moved => write!(f, "moved"),
+            MoveStatus::Failed(err) => write!(f, "failed: {}", err),
+            MoveStatus::RolledBack => write!(f, "rolled back"),
+            MoveStatus::RollbackFailed(err) => write!(f, "rollback failed: {}", err),
+            MoveStatus::Skipped => write!(f, "skipped"),
+        }
+    }
+}
+
+/// Outcome of one `(source, destination)` pair of a `FileSystemService::batch_move`.
+#[derive(Debug, Clone)]
+pub struct MoveOutcome {
+    pub source: PathBuf,
+    pub destination: PathBuf,
+    pub status: MoveStatus,
+}
+
+/// Filtering and matching options for `FileSystemService::search_files`.
+#[derive(Debug, Default, Clone)]
+pub struct SearchOptions {
+    /// Glob patterns matched against paths relative to the root; matching entries and their
+    /// contents are skipped. Patterns without `*` match anywhere in the path.
+    pub exclude_patterns: Vec<String>,
+    /// Skip paths excluded by `.gitignore` (and `.ignore`) files.
+    pub respect_gitignore: bool,
+    /// Match the search and exclude patterns case-sensitively.
+    pub case_sensitive: bool,
+    /// Maximum depth to descend below the root; 1 searches only the root's direct entries.
+    pub max_depth: Option<usize>,
+    /// Only return entries of this type: `file` or `dir`.
+    pub type_filter: Option<String>,
+    /// Skip hidden entries (see `utils::is_hidden`) and the contents of hidden directories.
+    pub skip_hidden: bool,
+}
+
+/// File selection and matching options for `FileSystemService::replace_in_files`.
+#[derive(Debug, Default, Clone)]
+pub struct ReplaceInFilesOptions {
+    /// Glob patterns matched against paths relative to the root; all files when empty.
+    pub include_patterns: Vec<String>,
+    /// Glob patterns for files and directories to skip, matched like `include_patterns`.
+    pub exclude_patterns: Vec<String>,
+    /// Treat the search text as a regular expression whose captures `new_text` may reference.
+    pub is_regex: bool,
+    /// Report the changes without writing them.
+    pub dry_run: bool,
+}
+
+/// File selection options for `FileSystemService::bundle_directory`.
+#[derive(Debug, Default, Clone)]
+pub struct BundleDirectoryOptions {
+    /// Glob patterns matched against paths relative to the root; all files when empty.
+    pub include_patterns: Vec<String>,
+    /// Glob patterns for files and directories to skip, matched like `include_patterns`.
+    pub exclude_patterns: Vec<String>,
+    /// Maximum size of the bundle in bytes, `DEFAULT_BUNDLE_MAX_SIZE` when not set.
+    pub max_size: Option<u64>,
+}
+
+/// File selection and conflict options for `FileSystemService::copy_directory`.
+#[derive(Debug, Default, Clone)]
+pub struct CopyDirectoryOptions {
+    /// Glob patterns matched against paths relative to the source; all files when empty.
+    pub include_patterns: Vec<String>,
+    /// Glob patterns for files and directories to skip, matched like `include_patterns`.
+    pub exclude_patterns: Vec<String>,
+    /// Replace files that already exist at the destination instead of skipping them.
+    pub overwrite: bool,
+    /// Carry over the permissions and access/modification times of the copied files.
+    pub preserve_metadata: bool,
+}
+
+/// A single change applied by `FileSystemService::apply_edits`.
+#[derive(Debug, Clone)]
+pub enum FileEdit {
+    /// Replaces matched text.
+    Replace(EditOperation),
+    /// Inserts text before a line.
+    Insert(InsertOperation),
+    /// Removes a range of lines.
+    DeleteLines(DeleteLinesOperation),
+}
+
+impl From<EditOperation> for FileEdit {
+    fn from(edit: EditOperation) -> Self {
+        FileEdit::Replace(edit)
+    }
+}
+
+impl From<InsertOperation> for FileEdit {
+    fn from(insert: InsertOperation) -> Self {
+        FileEdit::Insert(insert)
+    }
+}
+
+impl From<DeleteLinesOperation> for FileEdit {
+    fn from(delete: DeleteLinesOperation) -> Self {
+        FileEdit::DeleteLines(delete)
+    }
+}
+
+/// Optional behaviors for `FileSystemService::apply_file_edits`.
+#[derive(Debug, Default, Clone)]
+pub struct EditOptions {
+    /// Skip edits that have no match instead of failing, and report them in the result.
+    pub skip_unmatched: bool,
+    /// Save the previous content to `<path>.bak` before writing. Falls back to the server default when `None`.
+    pub backup: Option<bool>,
+    /// Number of unchanged lines shown around each change in the returned diff. Defaults to 4.
+    pub context_lines: Option<usize>,
+    /// When an edit matches neither exactly nor up to whitespace, apply it to the most similar
+    /// block of lines if its similarity (0.0-1.0) is at least this value.
+    pub fuzzy_threshold: Option<f32>,
+    /// Fail, writing nothing, if an edit changes lines outside the region it matched, e.g.
+    /// when the line-based matching alters the end of the file.
+    pub strict_span: bool,
 }
 
 impl FileSystemService {
     pub fn try_new(allowed_directories: &[String]) -> ServiceResult<Self> {
-        let normalized_dirs: Vec<PathBuf> = allowed_directories
-            .iter()
-            .map_while(|dir| {
-                let expand_result = expand_home(dir.into());
-                if !expand_result.is_dir() {
-                    panic!("{}", format!("Error: {} is not a directory", dir));
+        let mut normalized_dirs: Vec<PathBuf> = Vec::new();
+        let mut directory_access: Vec<(PathBuf, AccessLevel)> = Vec::new();
+        let mut invalid_dirs: Vec<String> = Vec::new();
+        for entry in allowed_directories {
+            let (dir, access) = AccessLevel::split_suffix(entry);
+            let resolved_start = normalized_dirs.len();
+            let expand_result = expand_home(dir.into());
+            if is_glob_pattern(dir) {
+                // Patterns such as `/srv/projects/*/data` expand to every matching directory
+                let matches: Vec<PathBuf> = match glob::glob(&expand_result.to_string_lossy()) {
+                    Ok(paths) => paths
+                        .filter_map(Result::ok)
+                        .filter(|path| path.is_dir())
+                        .collect(),
+                    Err(err) => {
+                        invalid_dirs.push(format!("{} is not a valid pattern: {}", dir, err));
+                        continue;
+                    }
+                };
+                if matches.is_empty() {
+                    invalid_dirs.push(format!("no directories match the pattern {}", dir));
                 }
-                Some(expand_result)
-            })
-            .collect();
+                normalized_dirs.extend(matches);
+            } else if expand_result.is_dir() {
+                normalized_dirs.push(dunce::simplified(&expand_result).to_path_buf());
+            } else {
+                invalid_dirs.push(format!("{} is not a directory", dir));
+            }
+
+            if let Some(access) = access {
+                directory_access.extend(
+                    normalized_dirs[resolved_start..]
+                        .iter()
+                        .map(|path| (path.clone(), access)),
+                );
+            }
+        }
+
+        if !invalid_dirs.is_empty() {
+            return Err(ServiceError::FromString(format!(
+                "Error: invalid allowed directories:\n{}",
+                invalid_dirs
+                    .iter()
+                    .map(|reason| format!("- {}", reason))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )));
+        }
 
         Ok(Self {
             allowed_path: normalized_dirs,
+            directory_access,
+            default_access: AccessLevel::ReadWrite,
+            denied_patterns: vec![],
+            trash_dir: None,
+            max_extract_size: None,
+            backup: false,
         })
     }
 
+    /// Enables saving a `.bak` copy of files before they are overwritten, unless a call opts out.
+    pub fn with_backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+
+    /// Moves deleted items into `trash_dir` instead of removing them permanently, unless a call
+    /// opts out. The directory must be inside an allowed directory and is created if missing.
+    pub fn with_trash_dir(mut self, trash_dir: Option<&str>) -> ServiceResult<Self> {
+        if let Some(trash_dir) = trash_dir {
+            let valid_path = self.validate_write_path(Path::new(trash_dir))?;
+            fs::create_dir_all(&valid_path)?;
+            self.trash_dir = Some(valid_path);
+        }
+        Ok(self)
+    }
+
+    /// Limits the total uncompressed size of archives extracted by `unzip_file`.
+    pub fn with_max_extract_size(mut self, max_extract_size: Option<u64>) -> Self {
+        self.max_extract_size = max_extract_size;
+        self
+    }
+
+    /// Sets the access level of allowed directories that have no explicit `:ro` or `:rw` suffix.
+    pub fn with_default_access(mut self, access: AccessLevel) -> Self {
+        self.default_access = access;
+        self
+    }
+
+    /// Returns `true` if any allowed directory accepts writes.
+    pub fn has_writable_directories(&self) -> bool {
+        self.default_access == AccessLevel::ReadWrite
+            || self
+                .directory_access
+                .iter()
+                .any(|(_, access)| *access == AccessLevel::ReadWrite)
+    }
+
+    /// Denies access to the given paths or glob patterns and everything below them, even when
+    /// they are inside an allowed directory. Denials take precedence over allowed directories.
+    pub fn with_denied(mut self, denied: &[String]) -> ServiceResult<Self> {
+        self.denied_patterns = denied
+            .iter()
+            .map(|entry| {
+                let expanded = expand_home(entry.into());
+                if is_glob_pattern(entry) {
+                    Pattern::new(&expanded.to_string_lossy())
+                } else {
+                    Pattern::new(&Pattern::escape(
+                        &normalize_path(&expanded).to_string_lossy(),
+                    ))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self)
+    }
+
     pub fn allowed_directories(&self) -> &Vec<PathBuf> {
         &self.allowed_path
     }
+
+    /// Returns the configured deny list as glob patterns.
+    pub fn denied_patterns(&self) -> Vec<String> {
+        self.denied_patterns
+            .iter()
+            .map(|pattern| pattern.as_str().to_string())
+            .collect()
+    }
+
+    pub fn trash_dir(&self) -> Option<&PathBuf> {
+        self.trash_dir.as_ref()
+    }
+
+    pub fn max_extract_size(&self) -> Option<u64> {
+        self.max_extract_size
+    }
+
+    /// Returns `true` if overwritten files are backed up by default.
+    pub fn backup_enabled(&self) -> bool {
+        self.backup
+    }
 }
 
 impl FileSystemService {
     pub fn validate_path(&self, requested_path: &Path) -> ServiceResult<PathBuf> {
+        if requested_path.as_os_str().to_string_lossy().contains('\0') {
+            return Err(ServiceError::FromString(format!(
+                "Invalid path - contains a null byte: {}",
+                requested_path.display()
+            )));
+        }
+
         // Expand ~ to home directory
         let expanded_path = expand_home(requested_path.to_path_buf());
 
@@ -77,31 +532,95 @@ impl FileSystemService {
             normalized_requested.starts_with(dir)
                 || normalized_requested.starts_with(normalize_path(dir))
         }) {
-            let symlink_target = if contains_symlink(&absolute_path)? {
-                "a symlink target path"
+            let path = absolute_path.display().to_string();
+            let allowed = self
+                .allowed_path
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(",\n");
+            return Err(if contains_symlink(&absolute_path)? {
+                ServiceError::SymlinkOutsideAllowedDirectories { path, allowed }
             } else {
-                "path"
-            };
-            return Err(ServiceError::FromString(format!(
-                "Access denied - {} is outside allowed directories: {} not in {}",
-                symlink_target,
+                ServiceError::OutsideAllowedDirectories { path, allowed }
+            });
+        }
+
+        // Denied subtrees take precedence over allowed directories
+        if let Some(denied) = self.denied_patterns.iter().find(|pattern| {
+            normalized_requested
+                .ancestors()
+                .any(|ancestor| pattern.matches_path(ancestor))
+        }) {
+            return Err(ServiceError::AccessDenied(format!(
+                "path is within a denied location: {} matches {}",
                 absolute_path.display(),
-                self.allowed_path
-                    .iter()
-                    .map(|p| p.display().to_string())
-                    .collect::<Vec<_>>()
-                    .join(",\n"),
+                denied.as_str()
             )));
         }
 
         Ok(absolute_path)
     }
 
+    /// Resolves an existing path to its canonical absolute form, following every symlink and
+    /// collapsing `.` and `..`, and confirms the final target is still within the allowed
+    /// directories.
+    pub async fn real_path(&self, requested_path: &Path) -> ServiceResult<PathBuf> {
+        let valid_path = self.validate_path(requested_path)?;
+        let canonical = tokio::fs::canonicalize(&valid_path).await?;
+        self.validate_path(dunce::simplified(&canonical))
+    }
+
+    /// Returns the access level of a path, taken from the most specific allowed directory
+    /// containing it.
+    pub fn access_level(&self, requested_path: &Path) -> ServiceResult<AccessLevel> {
+        let valid_path = self.validate_path(requested_path)?;
+        let normalized_path = normalize_path(&valid_path);
+        let access = self
+            .directory_access
+            .iter()
+            .filter(|(dir, _)| {
+                normalized_path.starts_with(dir) || normalized_path.starts_with(normalize_path(dir))
+            })
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map_or(self.default_access, |(_, access)| *access);
+        Ok(access)
+    }
+
+    /// Validates a path that is about to be modified, rejecting paths in read-only directories.
+    pub fn validate_write_path(&self, requested_path: &Path) -> ServiceResult<PathBuf> {
+        let valid_path = self.validate_path(requested_path)?;
+        if self.access_level(&valid_path)? == AccessLevel::ReadOnly {
+            return Err(ServiceError::ReadOnlyDirectory(
+                valid_path.display().to_string(),
+            ));
+        }
+        Ok(valid_path)
+    }
+
     // Get file stats
-    pub async fn get_file_stats(&self, file_path: &Path) -> ServiceResult<FileInfo> {
+    /// Returns the metadata of a file or directory. Symlinks are followed unless
+    /// `follow_symlinks` is `Some(false)`, in which case the link itself is described.
+    pub async fn get_file_stats(
+        &self,
+        file_path: &Path,
+        detect_mime: Option<bool>,
+        follow_symlinks: Option<bool>,
+    ) -> ServiceResult<FileInfo> {
         let valid_path = self.validate_path(file_path)?;
 
-        let metadata = fs::metadata(valid_path)?;
+        let link_metadata = fs::symlink_metadata(&valid_path).map_err(io_error_at(&valid_path))?;
+        let is_symlink = link_metadata.file_type().is_symlink();
+        let symlink_target = if is_symlink {
+            fs::read_link(&valid_path).ok()
+        } else {
+            None
+        };
+        let metadata = if follow_symlinks.unwrap_or(true) {
+            fs::metadata(&valid_path)?
+        } else {
+            link_metadata
+        };
 
         let size = metadata.len();
         let created = metadata.created().ok();
@@ -109,6 +628,11 @@ impl FileSystemService {
         let accessed = metadata.accessed().ok();
         let is_directory = metadata.is_dir();
         let is_file = metadata.is_file();
+        let mime_type = if is_file && detect_mime.unwrap_or(false) {
+            detect_mime_type(&valid_path)
+        } else {
+            None
+        };
 
         Ok(FileInfo {
             size,
@@ -117,10 +641,343 @@ impl FileSystemService {
             accessed,
             is_directory,
             is_file,
+            is_symlink,
+            symlink_target,
+            mime_type,
+            line_ending: None,
+            encoding: None,
+            #[cfg(unix)]
+            uid: metadata.uid(),
+            #[cfg(unix)]
+            gid: metadata.gid(),
             metadata,
         })
     }
 
+    /// Detects the charset of a text file and the line ending style of its decoded content;
+    /// see `detect_charset` and `LineEndingCounts::style`. Reads the whole file.
+    pub async fn detect_text_format(
+        &self,
+        file_path: &Path,
+    ) -> ServiceResult<(&'static str, Option<LineEnding>)> {
+        let valid_path = self.validate_path(file_path)?;
+        let bytes = tokio::fs::read(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        let charset = detect_charset(&bytes);
+        let (content, _, _) = charset.decode(&bytes);
+        Ok((charset.name(), LineEndingCounts::count(&content).style()))
+    }
+
+    /// Truncates or extends an existing file to exactly `size` bytes. Extended regions are
+    /// filled with zeros. Returns the previous size of the file.
+    pub async fn truncate_file(&self, file_path: &Path, size: u64) -> ServiceResult<u64> {
+        let valid_path = self.validate_write_path(file_path)?;
+        let file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&valid_path)
+            .await?;
+        let previous_size = file.metadata().await?.len();
+        file.set_len(size).await?;
+        file.sync_all().await?;
+        Ok(previous_size)
+    }
+
+    /// Creates an empty file if it doesn't exist, otherwise updates its access and modification
+    /// times. Uses the current time unless an RFC 3339 `timestamp` is given.
+    /// Returns `true` when a new file was created.
+    pub async fn touch(&self, file_path: &Path, timestamp: Option<&str>) -> ServiceResult<bool> {
+        let valid_path = self.validate_write_path(file_path)?;
+        let time = match timestamp {
+            Some(timestamp) => FileTime::from_system_time(parse_rfc3339(timestamp)?),
+            None => FileTime::now(),
+        };
+
+        let created = !valid_path.exists();
+        if created {
+            File::create(&valid_path).await?;
+        }
+        filetime::set_file_times(&valid_path, time, time)?;
+        Ok(created)
+    }
+
+    /// Writes the contents of `input_files`, in order, to `target_file`, with `separator` between
+    /// consecutive files. Inputs are streamed rather than read into memory. Returns the number of
+    /// bytes written.
+    pub async fn concat_files(
+        &self,
+        input_files: &[String],
+        target_file: &Path,
+        separator: Option<&str>,
+        overwrite: bool,
+    ) -> ServiceResult<u64> {
+        if input_files.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "No file(s) to concatenate. The input files array is empty.",
+            )
+            .into());
+        }
+        let target_path = self.validate_write_path(target_file)?;
+        let source_paths = input_files
+            .iter()
+            .map(|p| self.validate_path(Path::new(p)))
+            .collect::<Result<Vec<_>, _>>()?;
+        if source_paths
+            .iter()
+            .any(|source| is_same_file(source, &target_path))
+        {
+            return Err(ServiceError::FromString(format!(
+                "'{}' cannot be both an input and the target file",
+                target_path.display()
+            )));
+        }
+        if target_path.exists() && !overwrite {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("'{}' already exists!", target_path.display()),
+            )
+            .into());
+        }
+
+        let mut target = tokio::io::BufWriter::new(File::create(&target_path).await?);
+        let mut written = 0;
+        for (index, source_path) in source_paths.iter().enumerate() {
+            if let (true, Some(separator)) = (index > 0, separator) {
+                target.write_all(separator.as_bytes()).await?;
+                written += separator.len() as u64;
+            }
+            let mut source = File::open(source_path).await?;
+            written += tokio::io::copy(&mut source, &mut target).await?;
+        }
+        target.flush().await?;
+        Ok(written)
+    }
+
+    /// Splits a file into numbered parts (`<name>.part0001`, `<name>.part0002`, ...) in
+    /// `output_dir`, each holding `chunk_size` bytes or `lines` lines except possibly the last.
+    /// The file is streamed rather than read into memory. Returns the paths of the parts in order.
+    pub async fn split_file(
+        &self,
+        file_path: &Path,
+        output_dir: &Path,
+        chunk_size: Option<u64>,
+        lines: Option<u64>,
+    ) -> ServiceResult<Vec<PathBuf>> {
+        let (chunk_size, lines) = match (chunk_size, lines) {
+            (Some(0), _) | (_, Some(0)) => {
+                return Err(ServiceError::FromString(
+                    "chunk_size and lines must be greater than 0".to_string(),
+                ))
+            }
+            (Some(chunk_size), None) => (chunk_size, None),
+            (None, Some(lines)) => (u64::MAX, Some(lines)),
+            _ => {
+                return Err(ServiceError::FromString(
+                    "Exactly one of chunk_size or lines must be provided".to_string(),
+                ))
+            }
+        };
+        let valid_path = self.validate_path(file_path)?;
+        let valid_dir = self.validate_write_path(output_dir)?;
+        let file_name = valid_path
+            .file_name()
+            .ok_or_else(|| ServiceError::FromString("Invalid file name".to_string()))?
+            .to_string_lossy()
+            .to_string();
+        tokio::fs::create_dir_all(&valid_dir).await?;
+
+        let mut source = BufReader::new(File::open(&valid_path).await?);
+        let mut parts: Vec<PathBuf> = vec![];
+        let mut line = Vec::new();
+        while !source.fill_buf().await?.is_empty() {
+            let part_path = valid_dir.join(format!("{}.part{:04}", file_name, parts.len() + 1));
+            let part_file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&part_path)
+                .await?;
+            let mut part = tokio::io::BufWriter::new(part_file);
+            match lines {
+                Some(lines) => {
+                    for _ in 0..lines {
+                        line.clear();
+                        if source.read_until(b'\n', &mut line).await? == 0 {
+                            break;
+                        }
+                        part.write_all(&line).await?;
+                    }
+                }
+                None => {
+                    tokio::io::copy(&mut (&mut source).take(chunk_size), &mut part).await?;
+                }
+            }
+            part.flush().await?;
+            parts.push(part_path);
+        }
+        Ok(parts)
+    }
+
+    /// Creates a new, uniquely named empty file and returns its path. The file is created in
+    /// `dir`, or in the first writable allowed directory when no directory is given.
+    pub async fn create_temp_file(
+        &self,
+        dir: Option<&Path>,
+        prefix: Option<&str>,
+        suffix: Option<&str>,
+    ) -> ServiceResult<PathBuf> {
+        let valid_dir = match dir {
+            Some(dir) => self.validate_write_path(dir)?,
+            None => self
+                .allowed_path
+                .iter()
+                .find(|dir| matches!(self.access_level(dir), Ok(AccessLevel::ReadWrite)))
+                .cloned()
+                .ok_or(ServiceError::NoWriteAccess)?,
+        };
+        let prefix = prefix.unwrap_or("tmp");
+        let suffix = suffix.unwrap_or_default();
+        if [prefix, suffix]
+            .iter()
+            .any(|part| part.contains(['/', '\\']) || part.contains(".."))
+        {
+            return Err(ServiceError::FromString(
+                "Prefix and suffix must not contain path separators or '..'".to_string(),
+            ));
+        }
+
+        let temp_path = tempfile::Builder::new()
+            .prefix(prefix)
+            .suffix(suffix)
+            .tempfile_in(&valid_dir)?
+            .into_temp_path()
+            .keep()
+            .map_err(std::io::Error::from)?;
+        // Make sure the created file is still within the allowed directories
+        self.validate_write_path(&temp_path)
+    }
+
+    /// Changes the permissions of a file or directory and returns the resulting permissions.
+    /// On Unix, `mode` is an octal string such as `0644`; `readonly` alone toggles the write bits.
+    /// On Windows, only `readonly` is supported.
+    pub async fn set_permissions(
+        &self,
+        file_path: &Path,
+        mode: Option<&str>,
+        readonly: Option<bool>,
+    ) -> ServiceResult<String> {
+        let valid_path = self.validate_write_path(file_path)?;
+        let mut permissions = fs::metadata(&valid_path)?.permissions();
+
+        #[cfg(unix)]
+        {
+            let new_mode = match (mode, readonly) {
+                (Some(mode), _) => u32::from_str_radix(mode.trim_start_matches("0o"), 8)
+                    .ok()
+                    .filter(|mode| *mode <= 0o7777)
+                    .ok_or(ServiceError::FromString(format!(
+                        "Invalid mode '{}'. Expected an octal value such as 0644",
+                        mode
+                    )))?,
+                (None, Some(true)) => permissions.mode() & !0o222,
+                (None, Some(false)) => permissions.mode() | 0o200,
+                (None, None) => {
+                    return Err(ServiceError::FromString(
+                        "Either 'mode' or 'readonly' must be provided".to_string(),
+                    ))
+                }
+            };
+            permissions.set_mode(new_mode);
+        }
+
+        #[cfg(windows)]
+        {
+            if mode.is_some() {
+                return Err(ServiceError::FromString(
+                    "Octal modes are not supported on Windows, use 'readonly' instead".to_string(),
+                ));
+            }
+            let readonly = readonly.ok_or(ServiceError::FromString(
+                "'readonly' must be provided".to_string(),
+            ))?;
+            permissions.set_readonly(readonly);
+        }
+
+        tokio::fs::set_permissions(&valid_path, permissions).await?;
+        Ok(format_permissions(&fs::metadata(&valid_path)?))
+    }
+
+    pub async fn indentation_report(&self, file_path: &Path) -> ServiceResult<IndentationReport> {
+        let valid_path = self.validate_path(file_path)?;
+        let content = tokio::fs::read_to_string(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        Ok(IndentationReport::analyze(&content))
+    }
+
+    /// Checks whether a path exists and what it is, without failing when it is missing.
+    /// `is_file` and `is_dir` describe the symlink target; a dangling symlink still exists.
+    pub async fn path_status(&self, path: &Path) -> ServiceResult<PathStatus> {
+        let valid_path = self.validate_path(path)?;
+        let Ok(link_metadata) = tokio::fs::symlink_metadata(&valid_path).await else {
+            return Ok(PathStatus::default());
+        };
+        let metadata = tokio::fs::metadata(&valid_path).await.ok();
+        Ok(PathStatus {
+            exists: true,
+            is_file: metadata.as_ref().is_some_and(|metadata| metadata.is_file()),
+            is_dir: metadata.as_ref().is_some_and(|metadata| metadata.is_dir()),
+            is_symlink: link_metadata.file_type().is_symlink(),
+        })
+    }
+
+    /// Returns the total, used and available space of the filesystem that holds `path`.
+    pub async fn disk_space(&self, path: &Path) -> ServiceResult<DiskSpace> {
+        let valid_path = self.validate_path(path)?;
+        let space = tokio::task::spawn_blocking(move || -> std::io::Result<DiskSpace> {
+            let total = fs2::total_space(&valid_path)?;
+            let free = fs2::free_space(&valid_path)?;
+            Ok(DiskSpace {
+                total,
+                used: total.saturating_sub(free),
+                available: fs2::available_space(&valid_path)?,
+            })
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+        Ok(space)
+    }
+
+    /// Counts lines, words and bytes of a file by streaming it in fixed-size chunks.
+    pub async fn word_count(&self, file_path: &Path) -> ServiceResult<WordCount> {
+        let valid_path = self.validate_path(file_path)?;
+        let mut file = File::open(valid_path).await?;
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut count = WordCount::default();
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            count.update(&buffer[..read]);
+        }
+        Ok(count.finish())
+    }
+
+    /// Reads up to `length` bytes (at most `MAX_HEX_DUMP_LENGTH`) from the start of a file and
+    /// formats them as a hex dump.
+    pub async fn hex_dump(&self, file_path: &Path, length: u64) -> ServiceResult<String> {
+        let valid_path = self.validate_path(file_path)?;
+        let file = File::open(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        let mut bytes = vec![];
+        file.take(length.min(MAX_HEX_DUMP_LENGTH))
+            .read_to_end(&mut bytes)
+            .await?;
+        Ok(format_hex_dump(&bytes))
+    }
+
     fn detect_line_ending(&self, text: &str) -> &str {
         if text.contains("\r\n") {
             "\r\n"
@@ -136,18 +993,13 @@ impl FileSystemService {
         input_dir: String,
         pattern: String,
         target_zip_file: String,
+        root_prefix: Option<&str>,
+        progress: Option<ProgressCallback>,
     ) -> ServiceResult<String> {
         let valid_dir_path = self.validate_path(Path::new(&input_dir))?;
+        let root_prefix = root_prefix.map(zip_root_prefix).transpose()?;
 
-        let input_dir_str = &valid_dir_path
-            .as_os_str()
-            .to_str()
-            .ok_or(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Invalid UTF-8 in file name",
-            ))?;
-
-        let target_path = self.validate_path(Path::new(&target_zip_file))?;
+        let target_path = self.validate_write_path(Path::new(&target_zip_file))?;
 
         if target_path.exists() {
             return Err(std::io::Error::new(
@@ -157,55 +1009,18 @@ impl FileSystemService {
             .into());
         }
 
-        let updated_pattern = if pattern.contains('*') {
-            pattern.to_lowercase()
-        } else {
-            format!("*{}*", &pattern.to_lowercase())
-        };
-
-        let glob_pattern = Pattern::new(&updated_pattern)?;
-
-        let entries: Vec<_> = WalkDir::new(&valid_dir_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_map(|entry| entry.ok())
-            .filter_map(|entry| {
-                let full_path = entry.path();
-
-                self.validate_path(full_path).ok().and_then(|path| {
-                    if path != valid_dir_path && glob_pattern.matches(&path.display().to_string()) {
-                        Some(path)
-                    } else {
-                        None
-                    }
-                })
-            })
-            .collect();
+        let entries = self.zip_directory_entries(&valid_dir_path, &pattern)?;
 
         let zip_file = File::create(&target_path).await?;
         let mut zip_writer = ZipFileWriter::new(zip_file.compat());
-
-        for entry_path_buf in &entries {
-            if entry_path_buf.is_dir() {
-                continue;
-            }
-            let entry_path = entry_path_buf.as_path();
-            let entry_str = entry_path.as_os_str().to_str().ok_or(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Invalid UTF-8 in file name",
-            ))?;
-
-            if !entry_str.starts_with(input_dir_str) {
-                return Err(std::io::Error::new(
-                    std::io::ErrorKind::InvalidInput,
-                    "Entry file path does not start with base input directory path.",
-                )
-                .into());
-            }
-
-            let entry_str = &entry_str[input_dir_str.len() + 1..];
-            write_zip_entry(entry_str, entry_path, &mut zip_writer).await?;
-        }
+        write_zip_directory_entries(
+            &entries,
+            &valid_dir_path,
+            root_prefix.as_deref(),
+            &mut zip_writer,
+            progress,
+        )
+        .await?;
 
         let z_file = zip_writer.close().await?;
         let zip_file_size = if let Ok(meta_data) = z_file.into_inner().metadata().await {
@@ -222,24 +1037,90 @@ impl FileSystemService {
         Ok(result_message)
     }
 
-    pub async fn zip_files(
+    /// Builds a ZIP archive of a directory like `zip_directory`, but in memory, and returns its
+    /// bytes. Fails if the files to archive total more than `MAX_IN_MEMORY_ZIP_SIZE` bytes.
+    pub async fn zip_directory_in_memory(
         &self,
-        input_files: Vec<String>,
-        target_zip_file: String,
-    ) -> ServiceResult<String> {
-        let file_count = input_files.len();
+        input_dir: &str,
+        pattern: &str,
+        root_prefix: Option<&str>,
+        progress: Option<ProgressCallback>,
+    ) -> ServiceResult<Vec<u8>> {
+        let valid_dir_path = self.validate_path(Path::new(input_dir))?;
+        let root_prefix = root_prefix.map(zip_root_prefix).transpose()?;
+        let entries = self.zip_directory_entries(&valid_dir_path, pattern)?;
+        check_in_memory_zip_size(&entries)?;
+
+        let mut zip_writer = ZipFileWriter::with_tokio(Vec::new());
+        write_zip_directory_entries(
+            &entries,
+            &valid_dir_path,
+            root_prefix.as_deref(),
+            &mut zip_writer,
+            progress,
+        )
+        .await?;
+        Ok(zip_writer.close().await?.into_inner())
+    }
 
-        if file_count == 0 {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "No file(s) to zip. The input files array is empty.",
-            )
-            .into());
-        }
+    /// Collects the paths under `valid_dir_path` to archive whose path matches `pattern`.
+    /// A pattern without `*` matches any path containing it.
+    fn zip_directory_entries(
+        &self,
+        valid_dir_path: &Path,
+        pattern: &str,
+    ) -> ServiceResult<Vec<PathBuf>> {
+        let glob_patterns = expand_braces(&pattern.to_lowercase())
+            .into_iter()
+            .map(|pattern| {
+                let updated_pattern = if pattern.contains('*') {
+                    pattern
+                } else {
+                    format!("*{}*", pattern)
+                };
+                Pattern::new(&updated_pattern)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let target_path = self.validate_path(Path::new(&target_zip_file))?;
+        Ok(WalkDir::new(valid_dir_path)
+            .follow_links(true)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let full_path = entry.path();
 
-        if target_path.exists() {
+                self.validate_path(full_path).ok().and_then(|path| {
+                    let path_str = path.display().to_string();
+                    if path != valid_dir_path
+                        && glob_patterns.iter().any(|glob| glob.matches(&path_str))
+                    {
+                        Some(path)
+                    } else {
+                        None
+                    }
+                })
+            })
+            .collect())
+    }
+
+    /// Creates a ZIP archive from the given files. With `append`, the files are added to an
+    /// existing archive instead: since entries can't be appended in place, the archive is rebuilt
+    /// from its existing entries plus the new ones and then atomically replaced. Files whose name
+    /// already exists in the archive are skipped when `skip_duplicates` is set, otherwise they
+    /// cause an error. `progress` is called after each new file is written.
+    pub async fn zip_files(
+        &self,
+        input_files: Vec<String>,
+        target_zip_file: String,
+        append: bool,
+        skip_duplicates: bool,
+        progress: Option<ProgressCallback>,
+    ) -> ServiceResult<String> {
+        let source_paths = self.validate_zip_input_files(&input_files)?;
+        let target_path = self.validate_write_path(Path::new(&target_zip_file))?;
+        let append = append && target_path.exists();
+
+        if target_path.exists() && !append {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::AlreadyExists,
                 format!("'{}' already exists!", target_zip_file),
@@ -247,47 +1128,326 @@ impl FileSystemService {
             .into());
         }
 
-        let source_paths = input_files
-            .iter()
-            .map(|p| self.validate_path(Path::new(p)))
-            .collect::<Result<Vec<_>, _>>()?;
+        // Entries of the archive being appended to, with their uncompressed content
+        let mut existing_entries: Vec<(ZipEntryBuilder, Vec<u8>)> = vec![];
+        let mut entry_names: Vec<String> = vec![];
+        if append {
+            let file = BufReader::new(File::open(&target_path).await?);
+            let mut zip = ZipFileReader::with_tokio(file).await?;
+            for index in 0..zip.file().entries().len() {
+                let entry = ZipEntry::clone(&zip.file().entries()[index]);
+                entry_names.push(entry.filename().as_str()?.to_string());
+                let mut content = vec![];
+                zip.reader_with_entry(index)
+                    .await?
+                    .read_to_end_checked(&mut content)
+                    .await?;
+                existing_entries.push((entry.into(), content));
+            }
+        }
 
-        let zip_file = File::create(&target_path).await?;
-        let mut zip_writer = ZipFileWriter::new(zip_file.compat());
-        for path in source_paths {
-            let filename = path.file_name().ok_or(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Invalid path!",
-            ))?;
+        let (new_entries, skipped) =
+            zip_file_entries(source_paths, &mut entry_names, skip_duplicates)?;
 
-            let filename = filename.to_str().ok_or(std::io::Error::new(
-                std::io::ErrorKind::InvalidInput,
-                "Invalid UTF-8 in file name",
-            ))?;
+        let output_path = if append {
+            temp_sibling_path(&target_path)?
+        } else {
+            target_path.clone()
+        };
 
-            write_zip_entry(filename, &path, &mut zip_writer).await?;
+        let write_result: ServiceResult<File> = async {
+            let zip_file = File::create(&output_path).await?;
+            let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+            for (builder, content) in existing_entries {
+                zip_writer.write_entry_whole(builder, &content).await?;
+            }
+            for (index, (filename, path)) in new_entries.iter().enumerate() {
+                write_zip_entry(filename, path, &mut zip_writer).await?;
+                if let Some(progress) = &progress {
+                    progress(index + 1, new_entries.len());
+                }
+            }
+            Ok(zip_writer.close().await?.into_inner())
         }
-        let z_file = zip_writer.close().await?;
+        .await;
 
-        let zip_file_size = if let Ok(meta_data) = z_file.into_inner().metadata().await {
+        let z_file = match write_result {
+            Ok(z_file) => z_file,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(&output_path).await;
+                return Err(err);
+            }
+        };
+        if append {
+            z_file.sync_all().await?;
+            tokio::fs::rename(&output_path, &target_path).await?;
+        }
+
+        let zip_file_size = if let Ok(meta_data) = z_file.metadata().await {
             format_bytes(meta_data.len())
         } else {
             "unknown".to_string()
         };
 
-        let result_message = format!(
-            "Successfully compressed {} {} into '{}' ({}).",
-            file_count,
-            if file_count == 1 { "file" } else { "files" },
+        let added_count = new_entries.len();
+        let mut result_message = format!(
+            "Successfully {} {} {} into '{}' ({}).",
+            if append { "appended" } else { "compressed" },
+            added_count,
+            if added_count == 1 { "file" } else { "files" },
             target_path.display(),
             zip_file_size
         );
+        if !skipped.is_empty() {
+            result_message.push_str(&format!(
+                "\nSkipped {} already in the archive: {}",
+                if skipped.len() == 1 { "file" } else { "files" },
+                skipped.join(", ")
+            ));
+        }
         Ok(result_message)
     }
 
-    pub async fn unzip_file(&self, zip_file: &str, target_dir: &str) -> ServiceResult<String> {
+    /// Builds a ZIP archive of the given files like `zip_files`, but in memory, and returns its
+    /// bytes. Fails if the files total more than `MAX_IN_MEMORY_ZIP_SIZE` bytes.
+    pub async fn zip_files_in_memory(
+        &self,
+        input_files: &[String],
+        skip_duplicates: bool,
+        progress: Option<ProgressCallback>,
+    ) -> ServiceResult<Vec<u8>> {
+        let source_paths = self.validate_zip_input_files(input_files)?;
+        let (entries, _) = zip_file_entries(source_paths, &mut vec![], skip_duplicates)?;
+        let paths: Vec<PathBuf> = entries.iter().map(|(_, path)| path.clone()).collect();
+        check_in_memory_zip_size(&paths)?;
+
+        let mut zip_writer = ZipFileWriter::with_tokio(Vec::new());
+        for (index, (filename, path)) in entries.iter().enumerate() {
+            write_zip_entry(filename, path, &mut zip_writer).await?;
+            if let Some(progress) = &progress {
+                progress(index + 1, entries.len());
+            }
+        }
+        Ok(zip_writer.close().await?.into_inner())
+    }
+
+    fn validate_zip_input_files(&self, input_files: &[String]) -> ServiceResult<Vec<PathBuf>> {
+        if input_files.is_empty() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "No file(s) to zip. The input files array is empty.",
+            )
+            .into());
+        }
+        input_files
+            .iter()
+            .map(|p| self.validate_path(Path::new(p)))
+            .collect()
+    }
+
+    /// Compresses a single file into a gzip (`.gz`) file, streaming it through the encoder.
+    pub async fn gzip_file(
+        &self,
+        input_file: &str,
+        target_file: &str,
+        overwrite: bool,
+    ) -> ServiceResult<String> {
+        let (input_path, target_path) =
+            self.validate_single_file_transform(input_file, target_file, overwrite)?;
+
+        let input_size = tokio::fs::metadata(&input_path).await?.len();
+        let task_target = target_path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut reader = std::io::BufReader::new(fs::File::open(&input_path)?);
+            let writer = std::io::BufWriter::new(fs::File::create(&task_target)?);
+            let mut encoder = GzEncoder::new(writer, flate2::Compression::default());
+            std::io::copy(&mut reader, &mut encoder)?;
+            encoder.finish()?.into_inner()?.sync_all()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let output_size = tokio::fs::metadata(&target_path).await?.len();
+        Ok(format!(
+            "Successfully compressed '{}' ({}) into '{}' ({}).",
+            input_file,
+            format_bytes(input_size),
+            target_path.display(),
+            format_bytes(output_size)
+        ))
+    }
+
+    /// Decompresses a gzip (`.gz`) file, streaming it through the decoder.
+    pub async fn gunzip_file(
+        &self,
+        input_file: &str,
+        target_file: &str,
+        overwrite: bool,
+    ) -> ServiceResult<String> {
+        let (input_path, target_path) =
+            self.validate_single_file_transform(input_file, target_file, overwrite)?;
+
+        let task_target = target_path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let reader = std::io::BufReader::new(fs::File::open(&input_path)?);
+            let mut decoder = GzDecoder::new(reader);
+            let mut writer = std::io::BufWriter::new(fs::File::create(&task_target)?);
+            std::io::copy(&mut decoder, &mut writer)?;
+            writer.into_inner()?.sync_all()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let output_size = tokio::fs::metadata(&target_path).await?.len();
+        Ok(format!(
+            "Successfully decompressed '{}' into '{}' ({}).",
+            input_file,
+            target_path.display(),
+            format_bytes(output_size)
+        ))
+    }
+
+    /// Compresses a single file with zstd at the given level (defaults to 3), streaming it
+    /// through the encoder.
+    pub async fn zstd_compress_file(
+        &self,
+        input_file: &str,
+        target_file: &str,
+        level: Option<i32>,
+        overwrite: bool,
+    ) -> ServiceResult<String> {
+        let level = level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL);
+        let level_range = zstd::compression_level_range();
+        if !level_range.contains(&level) {
+            return Err(ServiceError::FromString(format!(
+                "Invalid zstd compression level {}. Expected a value between {} and {}",
+                level,
+                level_range.start(),
+                level_range.end()
+            )));
+        }
+
+        let (input_path, target_path) =
+            self.validate_single_file_transform(input_file, target_file, overwrite)?;
+
+        let input_size = tokio::fs::metadata(&input_path).await?.len();
+        let task_target = target_path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let mut reader = std::io::BufReader::new(fs::File::open(&input_path)?);
+            let writer = std::io::BufWriter::new(fs::File::create(&task_target)?);
+            let mut encoder = zstd::stream::Encoder::new(writer, level)?;
+            std::io::copy(&mut reader, &mut encoder)?;
+            encoder.finish()?.into_inner()?.sync_all()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let output_size = tokio::fs::metadata(&target_path).await?.len();
+        Ok(format!(
+            "Successfully compressed '{}' ({}) into '{}' ({}) with zstd level {}.",
+            input_file,
+            format_bytes(input_size),
+            target_path.display(),
+            format_bytes(output_size),
+            level
+        ))
+    }
+
+    /// Decompresses a zstd (`.zst`) file, streaming it through the decoder.
+    pub async fn zstd_decompress_file(
+        &self,
+        input_file: &str,
+        target_file: &str,
+        overwrite: bool,
+    ) -> ServiceResult<String> {
+        let (input_path, target_path) =
+            self.validate_single_file_transform(input_file, target_file, overwrite)?;
+
+        let task_target = target_path.clone();
+        tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+            let reader = std::io::BufReader::new(fs::File::open(&input_path)?);
+            let mut decoder = zstd::stream::Decoder::with_buffer(reader)?;
+            let mut writer = std::io::BufWriter::new(fs::File::create(&task_target)?);
+            std::io::copy(&mut decoder, &mut writer)?;
+            writer.into_inner()?.sync_all()
+        })
+        .await
+        .map_err(std::io::Error::other)??;
+
+        let output_size = tokio::fs::metadata(&target_path).await?.len();
+        Ok(format!(
+            "Successfully decompressed '{}' into '{}' ({}).",
+            input_file,
+            target_path.display(),
+            format_bytes(output_size)
+        ))
+    }
+
+    /// Validates the input and target of a file-to-file transformation (e.g. gzip), refusing to
+    /// replace an existing target unless `overwrite` is set.
+    fn validate_single_file_transform(
+        &self,
+        input_file: &str,
+        target_file: &str,
+        overwrite: bool,
+    ) -> ServiceResult<(PathBuf, PathBuf)> {
+        let input_path = self.validate_path(Path::new(input_file))?;
+        let target_path = self.validate_write_path(Path::new(target_file))?;
+
+        if !input_path.is_file() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("File '{}' does not exist!", input_file),
+            )
+            .into());
+        }
+        if target_path.exists() && !overwrite {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("'{}' already exists!", target_file),
+            )
+            .into());
+        }
+        Ok((input_path, target_path))
+    }
+
+    /// Guards against zip bombs using the sizes declared by the archive entries, before
+    /// anything is extracted.
+    fn check_extract_limits(&self, entries: &[StoredZipEntry]) -> ServiceResult<()> {
+        let mut total_size: u64 = 0;
+        for entry in entries {
+            let compressed = entry.compressed_size();
+            let uncompressed = entry.uncompressed_size();
+            if uncompressed > MAX_COMPRESSION_RATIO_GRACE_SIZE
+                && uncompressed > compressed.saturating_mul(MAX_COMPRESSION_RATIO)
+            {
+                return Err(ServiceError::CompressionRatioExceeded {
+                    entry: entry.filename().as_str()?.to_string(),
+                    compressed,
+                    uncompressed,
+                    limit: MAX_COMPRESSION_RATIO,
+                });
+            }
+            total_size = total_size.saturating_add(uncompressed);
+        }
+
+        match self.max_extract_size {
+            Some(limit) if total_size > limit => Err(ServiceError::ExtractSizeExceeded {
+                size: total_size,
+                limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    pub async fn unzip_file(
+        &self,
+        zip_file: &str,
+        target_dir: &str,
+        progress: Option<ProgressCallback>,
+    ) -> ServiceResult<String> {
         let zip_file = self.validate_path(Path::new(&zip_file))?;
-        let target_dir_path = self.validate_path(Path::new(target_dir))?;
+        let target_dir_path = self.validate_write_path(Path::new(target_dir))?;
         if !zip_file.exists() {
             return Err(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -296,133 +1456,955 @@ impl FileSystemService {
             .into());
         }
 
-        if target_dir_path.exists() {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::AlreadyExists,
-                format!("'{}' directory already exists!", target_dir),
-            )
-            .into());
+        if target_dir_path.exists() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("'{}' directory already exists!", target_dir),
+            )
+            .into());
+        }
+
+        let file = BufReader::new(File::open(zip_file).await?);
+        let mut zip = ZipFileReader::with_tokio(file).await?;
+
+        let file_count = zip.file().entries().len();
+        self.check_extract_limits(zip.file().entries())?;
+        // Every entry is checked before anything is written
+        let entry_paths = zip
+            .file()
+            .entries()
+            .iter()
+            .map(|entry| zip_entry_path(&target_dir_path, entry.filename().as_str()?))
+            .collect::<ServiceResult<Vec<_>>>()?;
+
+        // Entries may decompress to more than their recorded size, so the bytes actually written
+        // are capped too. The archive may not write more than it claims, nor more than the limit.
+        let claimed_size: u64 = zip
+            .file()
+            .entries()
+            .iter()
+            .map(|entry| entry.uncompressed_size())
+            .fold(0, u64::saturating_add);
+        let size_limit = self
+            .max_extract_size
+            .map_or(claimed_size, |limit| limit.min(claimed_size));
+        let result = self
+            .extract_zip_entries(&mut zip, entry_paths, size_limit, progress)
+            .await;
+        if result.is_err() {
+            // Don't leave a partial extraction behind; the target didn't exist before
+            let _ = tokio::fs::remove_dir_all(&target_dir_path).await;
+        }
+        result?;
+
+        let result_message = format!(
+            "Successfully extracted {} {} into '{}'.",
+            file_count,
+            if file_count == 1 { "file" } else { "files" },
+            target_dir_path.display()
+        );
+
+        Ok(result_message)
+    }
+
+    /// Extracts each entry of `zip` to its path in `entry_paths`, failing with
+    /// `ExtractSizeExceeded` as soon as an entry writes more than its recorded uncompressed size
+    /// or all entries together write more than `size_limit` bytes.
+    async fn extract_zip_entries(
+        &self,
+        zip: &mut ZipFileReader<BufReader<File>>,
+        entry_paths: Vec<PathBuf>,
+        size_limit: u64,
+        progress: Option<ProgressCallback>,
+    ) -> ServiceResult<()> {
+        let file_count = entry_paths.len();
+        let mut extracted_size: u64 = 0;
+        for (index, entry_path) in entry_paths.into_iter().enumerate() {
+            let entry = zip.file().entries().get(index).unwrap();
+            let is_dir = entry.dir()?;
+            let unix_mode = entry.unix_permissions();
+            let modified = entry.last_modification_date().as_chrono();
+            let claimed_size = entry.uncompressed_size();
+
+            if is_dir {
+                // Recreate directories stored in the archive, including empty ones
+                tokio::fs::create_dir_all(&entry_path).await?;
+            } else {
+                // Ensure the parent directory exists
+                if let Some(parent) = entry_path.parent() {
+                    tokio::fs::create_dir_all(parent).await?;
+                }
+
+                // Extract the file, reading at most one byte more than the entry claims
+                let reader = zip.reader_without_entry(index).await?;
+                let mut compat_reader = reader.compat().take(claimed_size.saturating_add(1));
+                let mut output_file = File::create(&entry_path).await?;
+
+                let written = tokio::io::copy(&mut compat_reader, &mut output_file).await?;
+                output_file.flush().await?;
+                extracted_size = extracted_size.saturating_add(written);
+                if written > claimed_size || extracted_size > size_limit {
+                    return Err(ServiceError::ExtractSizeExceeded {
+                        size: extracted_size,
+                        limit: size_limit,
+                    });
+                }
+            }
+
+            // Restore the permissions and modification time recorded in the archive
+            #[cfg(unix)]
+            if let Some(mode) = unix_mode {
+                let mode = u32::from(mode) & 0o7777;
+                if mode != 0 {
+                    tokio::fs::set_permissions(&entry_path, fs::Permissions::from_mode(mode))
+                        .await?;
+                }
+            }
+            if let chrono::LocalResult::Single(modified) = modified {
+                filetime::set_file_mtime(&entry_path, FileTime::from_system_time(modified.into()))?;
+            }
+
+            if let Some(progress) = &progress {
+                progress(index + 1, file_count);
+            }
+        }
+        Ok(())
+    }
+
+    /// Follows a file like `tail -f`: lines appended after the call are passed to `on_line` in
+    /// order until `cancel` is triggered, and the number of lines delivered is returned. The file
+    /// is checked every `poll_interval`; it is reopened from the start when it is replaced (its
+    /// inode changes, on Unix) or truncated, as happens with log rotation. A trailing partial
+    /// line is held back until it is completed.
+    pub async fn tail_follow(
+        &self,
+        file_path: &Path,
+        poll_interval: Duration,
+        cancel: CancellationToken,
+        mut on_line: impl FnMut(String) + Send,
+    ) -> ServiceResult<u64> {
+        let valid_path = self.validate_path(file_path)?;
+        let mut file = File::open(&valid_path).await?;
+        let mut file_id = file_identity(&file.metadata().await?);
+        let mut position = file.seek(SeekFrom::End(0)).await?;
+        let mut pending: Vec<u8> = vec![];
+        let mut line_count = 0;
+
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => return Ok(line_count),
+                _ = tokio::time::sleep(poll_interval) => {}
+            }
+
+            // A missing file is expected briefly while a log is being rotated
+            let Ok(metadata) = fs::metadata(&valid_path) else {
+                continue;
+            };
+            if file_identity(&metadata) != file_id || metadata.len() < position {
+                file = File::open(&valid_path).await?;
+                file_id = file_identity(&file.metadata().await?);
+                position = 0;
+                pending.clear();
+            }
+
+            file.seek(SeekFrom::Start(position)).await?;
+            let read = file.read_to_end(&mut pending).await?;
+            position += read as u64;
+
+            let mut start = 0;
+            while let Some(offset) = pending[start..].iter().position(|byte| *byte == b'\n') {
+                let line = &pending[start..start + offset];
+                let line = line.strip_suffix(b"\r").unwrap_or(line);
+                on_line(String::from_utf8_lossy(line).into_owned());
+                line_count += 1;
+                start += offset + 1;
+            }
+            pending.drain(..start);
+        }
+    }
+
+    /// Returns `true` if the file looks like binary data; see `is_probably_binary`.
+    pub fn is_binary_file(&self, file_path: &Path) -> ServiceResult<bool> {
+        let valid_path = self.validate_path(file_path)?;
+        Ok(is_probably_binary(&valid_path)?)
+    }
+
+    pub async fn read_file(&self, file_path: &Path) -> ServiceResult<String> {
+        let valid_path = self.validate_path(file_path)?;
+        let content = tokio::fs::read_to_string(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        Ok(content)
+    }
+
+    /// Reads up to `chunk_size` bytes starting at `offset`. Reading at or past the end of the file
+    /// returns an empty chunk with `eof` set.
+    pub async fn read_file_chunk(
+        &self,
+        file_path: &Path,
+        offset: u64,
+        chunk_size: usize,
+    ) -> ServiceResult<FileChunk> {
+        let valid_path = self.validate_path(file_path)?;
+        let mut file = File::open(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        let total_size = file.metadata().await?.len();
+        let start = offset.min(total_size);
+        file.seek(SeekFrom::Start(start)).await?;
+
+        let mut data = Vec::with_capacity(chunk_size.min((total_size - start) as usize));
+        file.take(chunk_size as u64).read_to_end(&mut data).await?;
+        let eof = start + data.len() as u64 >= total_size;
+        Ok(FileChunk {
+            offset: start,
+            data,
+            total_size,
+            eof,
+        })
+    }
+
+    /// Reads a file and decodes it with `decode_bytes`, also returning the charset detected in
+    /// `auto` mode and, when `hash_algorithm` is set, the digest of the raw bytes (see
+    /// `compute_digest`) computed from the same read.
+    pub async fn read_file_decoded(
+        &self,
+        file_path: &Path,
+        encoding: Option<&str>,
+        hash_algorithm: Option<&str>,
+    ) -> ServiceResult<DecodedFile> {
+        // Reject an unknown algorithm before reading the file
+        if let Some(algorithm) = hash_algorithm {
+            compute_digest(algorithm, &[])?;
+        }
+        let valid_path = self.validate_path(file_path)?;
+        let bytes = tokio::fs::read(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        let digest = hash_algorithm
+            .map(|algorithm| compute_digest(algorithm, &bytes))
+            .transpose()?;
+        let (content, detected_encoding) = decode_bytes(bytes, encoding.unwrap_or("utf-8"))?;
+        Ok(DecodedFile {
+            content,
+            detected_encoding,
+            digest,
+        })
+    }
+
+    /// Reads a file using the given encoding mode: `utf-8` (default, fails on invalid UTF-8),
+    /// `lossy` (invalid sequences are replaced with U+FFFD), `base64` (raw bytes, base64-encoded)
+    /// or `auto` (the charset is detected and decoded to UTF-8).
+    pub async fn read_file_with_encoding(
+        &self,
+        file_path: &Path,
+        encoding: Option<&str>,
+    ) -> ServiceResult<String> {
+        let decoded = self.read_file_decoded(file_path, encoding, None).await?;
+        Ok(decoded.content)
+    }
+
+    /// Reads lines `start_line..=end_line` (1-based) of a file decoded with `encoding`, joined
+    /// with the file's own line ending. Either bound may be omitted; a range running past the
+    /// end of the file returns the lines that exist.
+    pub async fn read_file_lines(
+        &self,
+        file_path: &Path,
+        encoding: Option<&str>,
+        start_line: Option<usize>,
+        end_line: Option<usize>,
+    ) -> ServiceResult<String> {
+        let start_line = start_line.unwrap_or(1);
+        if start_line == 0 || end_line.is_some_and(|end_line| end_line < start_line) {
+            return Err(ServiceError::FromString(format!(
+                "Invalid line range {}..{}: lines are 1-based and start_line must not exceed end_line",
+                start_line,
+                end_line.map(|end_line| end_line.to_string()).unwrap_or_default()
+            )));
+        }
+
+        let content = self.read_file_with_encoding(file_path, encoding).await?;
+        let line_ending = self.detect_line_ending(&content);
+        let content = content.strip_suffix(line_ending).unwrap_or(&content);
+        let lines: Vec<&str> = content
+            .split(line_ending)
+            .skip(start_line - 1)
+            .take(end_line.map_or(usize::MAX, |end_line| end_line - start_line + 1))
+            .collect();
+        Ok(lines.join(line_ending))
+    }
+
+    pub async fn create_directory(&self, file_path: &Path) -> ServiceResult<()> {
+        let valid_path = self.validate_write_path(file_path)?;
+        tokio::fs::create_dir_all(valid_path).await?;
+        Ok(())
+    }
+
+    /// Returns the directories `create_directory` would create for `dir_path`, i.e. the path and
+    /// its missing ancestors, outermost first, without creating anything. Fails if the closest
+    /// existing ancestor is not a directory.
+    pub async fn missing_directories(&self, dir_path: &Path) -> ServiceResult<Vec<PathBuf>> {
+        let valid_path = self.validate_write_path(dir_path)?;
+        let mut missing = vec![];
+        for ancestor in valid_path.ancestors() {
+            match tokio::fs::metadata(ancestor).await {
+                Ok(metadata) if metadata.is_dir() => break,
+                Ok(_) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::AlreadyExists,
+                        format!("'{}' exists and is not a directory", ancestor.display()),
+                    )
+                    .into())
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+                    missing.push(ancestor.to_path_buf())
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+        missing.reverse();
+        Ok(missing)
+    }
+
+    /// Moves or renames a file or directory and returns the final destination.
+    /// `on_conflict` decides what happens when the destination already exists: `error` (default)
+    /// fails, `overwrite` replaces it and `rename` appends ` (1)`, ` (2)`, ... to the destination name.
+    /// With `dry_run`, both paths are validated and the source is checked to exist, but nothing is moved.
+    /// Moves across filesystems copy the item, keeping its permissions and timestamps, then remove the source.
+    pub async fn move_file(
+        &self,
+        src_path: &Path,
+        dest_path: &Path,
+        dry_run: Option<bool>,
+        on_conflict: Option<&str>,
+    ) -> ServiceResult<PathBuf> {
+        let valid_src_path = self.validate_write_path(src_path)?;
+        let mut valid_dest_path = self.validate_write_path(dest_path)?;
+
+        if !valid_src_path.exists() {
+            return Err(ServiceError::NotFound(valid_src_path.display().to_string()));
+        }
+        if valid_src_path.starts_with(&valid_dest_path) {
+            return Err(ServiceError::FromString(format!(
+                "Cannot move {} onto {}: the destination contains the source",
+                valid_src_path.display(),
+                valid_dest_path.display()
+            )));
+        }
+
+        // An existing directory replaced by `overwrite` is set aside until the move succeeds
+        let mut replaced_dir = None;
+        let dest_exists = tokio::fs::symlink_metadata(&valid_dest_path).await.is_ok();
+        match on_conflict.unwrap_or("error") {
+            "error" if dest_exists => {
+                return Err(ServiceError::FromString(format!(
+                    "Destination already exists: {}",
+                    valid_dest_path.display()
+                )));
+            }
+            "rename" if dest_exists => {
+                valid_dest_path = self.validate_write_path(&unique_path(&valid_dest_path))?;
+            }
+            "overwrite" if dest_exists && !dry_run.unwrap_or(false) => {
+                // `rename` replaces files but not directories, so clear the way first
+                if valid_dest_path.is_dir() && !valid_dest_path.is_symlink() {
+                    let aside_path = temp_sibling_path(&valid_dest_path)?;
+                    tokio::fs::rename(&valid_dest_path, &aside_path).await?;
+                    replaced_dir = Some(aside_path);
+                }
+            }
+            "error" | "rename" | "overwrite" => {}
+            other => {
+                return Err(ServiceError::FromString(format!(
+                    "Invalid on_conflict value '{}'. Expected one of: error, overwrite, rename",
+                    other
+                )));
+            }
+        }
+
+        if !dry_run.unwrap_or(false) {
+            if let Err(err) = rename_or_copy(&valid_src_path, &valid_dest_path).await {
+                if let Some(aside_path) = &replaced_dir {
+                    tokio::fs::rename(aside_path, &valid_dest_path).await?;
+                }
+                return Err(err.into());
+            }
+            if let Some(aside_path) = &replaced_dir {
+                tokio::fs::remove_dir_all(aside_path).await?;
+            }
+        }
+        Ok(valid_dest_path)
+    }
+
+    /// Moves `src_path` like `move_file`, except that when both it and `dest_path` are existing
+    /// directories, the source is merged into the destination: subdirectories present in both
+    /// are merged recursively and other entries are moved in. Entries that already exist are
+    /// handled per `on_conflict`: `error` (default) fails before anything is moved, `overwrite`
+    /// replaces them and `rename` moves the new entry under a free name like ` (1)`.
+    pub async fn merge_move(
+        &self,
+        src_path: &Path,
+        dest_path: &Path,
+        dry_run: Option<bool>,
+        on_conflict: Option<&str>,
+    ) -> ServiceResult<PathBuf> {
+        let valid_src_path = self.validate_write_path(src_path)?;
+        let valid_dest_path = self.validate_write_path(dest_path)?;
+        let is_dir = |path: &Path| fs::symlink_metadata(path).is_ok_and(|meta| meta.is_dir());
+        if !is_dir(&valid_src_path) || !is_dir(&valid_dest_path) {
+            return self
+                .move_file(src_path, dest_path, dry_run, on_conflict)
+                .await;
+        }
+        if valid_dest_path.starts_with(&valid_src_path)
+            || valid_src_path.starts_with(&valid_dest_path)
+        {
+            return Err(ServiceError::FromString(format!(
+                "Cannot merge {} into {}: one contains the other",
+                valid_src_path.display(),
+                valid_dest_path.display()
+            )));
+        }
+
+        let overwrite = match on_conflict.unwrap_or("error") {
+            "error" => {
+                let conflicts = merge_conflicts(&valid_src_path, &valid_dest_path)?;
+                if !conflicts.is_empty() {
+                    return Err(ServiceError::FromString(format!(
+                        "Cannot merge, these entries already exist in the destination: {}",
+                        conflicts
+                            .iter()
+                            .map(|conflict| conflict.display().to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )));
+                }
+                false
+            }
+            "overwrite" => true,
+            "rename" => false,
+            other => {
+                return Err(ServiceError::FromString(format!(
+                    "Invalid on_conflict value '{}'. Expected one of: error, overwrite, rename",
+                    other
+                )));
+            }
+        };
+
+        if !dry_run.unwrap_or(false) {
+            let (src, dest) = (valid_src_path.clone(), valid_dest_path.clone());
+            tokio::task::spawn_blocking(move || merge_directories(&src, &dest, overwrite))
+                .await
+                .map_err(std::io::Error::other)??;
+        }
+        Ok(valid_dest_path)
+    }
+
+    /// Returns the `count` largest files under `root_path` with their sizes, largest first,
+    /// optionally restricted to files whose path relative to the root matches `pattern`.
+    /// Only the current top `count` files are kept in memory while walking the tree.
+    pub fn largest_files(
+        &self,
+        root_path: &Path,
+        count: usize,
+        pattern: Option<&str>,
+    ) -> ServiceResult<Vec<(PathBuf, u64)>> {
+        let valid_path = self.validate_path(root_path)?;
+        let pattern = pattern.map(Pattern::new).transpose()?;
+
+        if count == 0 {
+            return Ok(vec![]);
+        }
+
+        // A min-heap of the largest files seen so far: the smallest of them is evicted first
+        let mut largest: BinaryHeap<Reverse<(u64, PathBuf)>> = BinaryHeap::with_capacity(count + 1);
+        for entry in WalkDir::new(&valid_path)
+            .into_iter()
+            .filter_entry(|entry| self.validate_path(entry.path()).is_ok())
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+        {
+            if let Some(pattern) = &pattern {
+                let relative_path = entry
+                    .path()
+                    .strip_prefix(&valid_path)
+                    .unwrap_or(entry.path());
+                if !pattern.matches(&relative_path.to_string_lossy().replace('\\', "/")) {
+                    continue;
+                }
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            largest.push(Reverse((metadata.len(), entry.into_path())));
+            if largest.len() > count {
+                largest.pop();
+            }
+        }
+
+        Ok(largest
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, path))| (path, size))
+            .collect())
+    }
+
+    /// Finds the directories under `root_path` that contain no files, directly or in any
+    /// subdirectory, and removes them when `prune` is set. Results are ordered bottom-up, so
+    /// nested directories come before their parents; `root_path` itself is never included.
+    /// Symlinks and entries outside the allowed directories count as content.
+    pub fn find_empty_dirs(&self, root_path: &Path, prune: bool) -> ServiceResult<Vec<PathBuf>> {
+        let valid_path = if prune {
+            self.validate_write_path(root_path)?
+        } else {
+            self.validate_path(root_path)?
+        };
+
+        let mut empty_dirs = vec![];
+        for entry in fs::read_dir(&valid_path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                self.collect_empty_dirs(&entry.path(), &mut empty_dirs)?;
+            }
+        }
+
+        if prune {
+            for dir in &empty_dirs {
+                fs::remove_dir(self.validate_write_path(dir)?)?;
+            }
+        }
+        Ok(empty_dirs)
+    }
+
+    /// Appends the recursively empty directories at or below `dir` to `empty_dirs`, children
+    /// first, and returns whether `dir` itself is empty.
+    fn collect_empty_dirs(&self, dir: &Path, empty_dirs: &mut Vec<PathBuf>) -> ServiceResult<bool> {
+        if self.validate_path(dir).is_err() {
+            return Ok(false);
+        }
+        let mut is_empty = true;
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir()
+                || !self.collect_empty_dirs(&entry.path(), empty_dirs)?
+            {
+                is_empty = false;
+            }
+        }
+        if is_empty {
+            empty_dirs.push(dir.to_path_buf());
+        }
+        Ok(is_empty)
+    }
+
+    /// Moves each `(source, destination)` pair in order. All paths are validated before anything
+    /// is moved. If a move fails, the moves completed before it are undone in reverse order
+    /// (best effort) and the remaining pairs are skipped. Destinations must not exist yet.
+    pub async fn batch_move(&self, moves: &[(String, String)]) -> ServiceResult<Vec<MoveOutcome>> {
+        let mut outcomes = Vec::with_capacity(moves.len());
+        for (source, destination) in moves {
+            let source = self.validate_write_path(Path::new(source))?;
+            let destination = self.validate_write_path(Path::new(destination))?;
+            if tokio::fs::symlink_metadata(&source).await.is_err() {
+                return Err(ServiceError::NotFound(source.display().to_string()));
+            }
+            outcomes.push(MoveOutcome {
+                source,
+                destination,
+                status: MoveStatus::Skipped,
+            });
+        }
+
+        for index in 0..outcomes.len() {
+            let outcome = &outcomes[index];
+            let result = self
+                .move_file(&outcome.source, &outcome.destination, None, None)
+                .await;
+            match result {
+                Ok(_) => outcomes[index].status = MoveStatus::Moved,
+                Err(err) => {
+                    outcomes[index].status = MoveStatus::Failed(err.to_string());
+                    for completed in outcomes[..index].iter_mut().rev() {
+                        completed.status =
+                            match rename_or_copy(&completed.destination, &completed.source).await {
+                                Ok(_) => MoveStatus::RolledBack,
+                                Err(err) => MoveStatus::RollbackFailed(err.to_string()),
+                            };
+                    }
+                    break;
+                }
+            }
+        }
+        Ok(outcomes)
+    }
+
+    /// Deletes a file or directory. Non-empty directories are only removed when `recursive` is set.
+    /// When `trash` is enabled (defaults to whether a trash directory is configured), the item is
+    /// moved into the trash directory instead, with a ` (n)` suffix if the name is already taken.
+    /// Returns the new location of the item when it was moved to the trash.
+    pub async fn delete(
+        &self,
+        path: &Path,
+        recursive: bool,
+        trash: Option<bool>,
+    ) -> ServiceResult<Option<PathBuf>> {
+        let valid_path = self.validate_write_path(path)?;
+        let normalized_path = normalize_path(&valid_path);
+
+        if self
+            .allowed_path
+            .iter()
+            .chain(self.trash_dir.iter())
+            .any(|dir| normalize_path(dir) == normalized_path)
+        {
+            return Err(ServiceError::FromString(format!(
+                "Refusing to delete {}: it is an allowed or trash directory",
+                valid_path.display()
+            )));
+        }
+
+        let metadata = tokio::fs::symlink_metadata(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+        if metadata.is_dir()
+            && !recursive
+            && tokio::fs::read_dir(&valid_path)
+                .await?
+                .next_entry()
+                .await?
+                .is_some()
+        {
+            return Err(ServiceError::FromString(format!(
+                "Directory is not empty: {}. Set recursive to delete it with its contents.",
+                valid_path.display()
+            )));
+        }
+
+        if trash.unwrap_or(self.trash_dir.is_some()) {
+            let trash_dir = self.trash_dir.as_ref().ok_or(ServiceError::FromString(
+                "Trash was requested but no trash directory is configured (see --trash-dir)"
+                    .to_string(),
+            ))?;
+            if normalize_path(trash_dir).starts_with(&normalized_path) {
+                return Err(ServiceError::FromString(format!(
+                    "Refusing to move {} to the trash: it contains the trash directory",
+                    valid_path.display()
+                )));
+            }
+            let file_name = valid_path
+                .file_name()
+                .ok_or(ServiceError::FromString(format!(
+                    "Invalid path: {}",
+                    valid_path.display()
+                )))?;
+            let trash_path = unique_path(&trash_dir.join(file_name));
+            rename_or_copy(&valid_path, &trash_path).await?;
+            return Ok(Some(trash_path));
+        }
+
+        if metadata.is_dir() {
+            tokio::fs::remove_dir_all(&valid_path).await?;
+        } else {
+            tokio::fs::remove_file(&valid_path).await?;
+        }
+        Ok(None)
+    }
+
+    /// Lists the entries of a directory, optionally sorted and paged.
+    /// Paged listings without an explicit `sort_by` are sorted by name so pages are stable
+    /// across calls, and `next_offset` points at the next page.
+    pub async fn list_directory(
+        &self,
+        dir_path: &Path,
+        options: &ListDirectoryOptions,
+    ) -> ServiceResult<DirectoryPage> {
+        let valid_path = self.validate_path(dir_path)?;
+        let not_ignored = options
+            .respect_gitignore
+            .then(|| not_ignored_paths(&valid_path, Some(1)));
+
+        let mut dir = tokio::fs::read_dir(&valid_path)
+            .await
+            .map_err(io_error_at(&valid_path))?;
+
+        let mut entries = Vec::new();
+
+        let is_paged = options.offset.is_some() || options.limit.is_some();
+        let sort_by = match options.sort_by.as_deref() {
+            Some(sort_by) => Some(sort_by),
+            None if is_paged || options.reverse || options.directories_first => Some("name"),
+            None => None,
+        };
+
+        if let Some(sort_by) = sort_by {
+            if !matches!(sort_by, "name" | "size" | "modified") {
+                return Err(ServiceError::FromString(format!(
+                    "Invalid sort_by '{}'. Expected one of: name, size, modified",
+                    sort_by
+                )));
+            }
+        }
+        let fetch_metadata = options.with_metadata || sort_by.is_some();
+
+        // Use a loop to collect the directory entries
+        while let Some(entry) = dir.next_entry().await? {
+            if not_ignored
+                .as_ref()
+                .is_some_and(|not_ignored| !not_ignored.contains(&entry.path()))
+            {
+                continue;
+            }
+            if options.skip_hidden && is_hidden(&entry.path()) {
+                continue;
+            }
+            let metadata = if fetch_metadata {
+                entry.metadata().await.ok()
+            } else {
+                None
+            };
+            entries.push(DirectoryEntry { entry, metadata });
+        }
+
+        if let Some(sort_by) = sort_by {
+            entries.sort_by(|entry_a, entry_b| {
+                let (meta_a, meta_b) = (&entry_a.metadata, &entry_b.metadata);
+                let ordering = match sort_by {
+                    "size" => meta_a
+                        .as_ref()
+                        .map(|meta| meta.len())
+                        .cmp(&meta_b.as_ref().map(|meta| meta.len())),
+                    "modified" => meta_a
+                        .as_ref()
+                        .and_then(|meta| meta.modified().ok())
+                        .cmp(&meta_b.as_ref().and_then(|meta| meta.modified().ok())),
+                    _ => Ordering::Equal,
+                }
+                .then_with(|| entry_a.file_name().cmp(&entry_b.file_name()));
+                let ordering = if options.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                };
+
+                if options.directories_first {
+                    let is_dir =
+                        |meta: &Option<fs::Metadata>| meta.as_ref().is_some_and(|m| m.is_dir());
+                    is_dir(meta_b).cmp(&is_dir(meta_a)).then(ordering)
+                } else {
+                    ordering
+                }
+            });
         }
 
-        let file = BufReader::new(File::open(zip_file).await?);
-        let mut zip = ZipFileReader::with_tokio(file).await?;
+        if !is_paged {
+            return Ok(DirectoryPage {
+                entries,
+                next_offset: None,
+            });
+        }
 
-        let file_count = zip.file().entries().len();
+        let total = entries.len();
+        let offset = options.offset.unwrap_or(0).min(total);
+        let end = options
+            .limit
+            .map_or(total, |limit| offset.saturating_add(limit).min(total));
 
-        for index in 0..file_count {
-            let entry = zip.file().entries().get(index).unwrap();
-            let entry_path = target_dir_path.join(entry.filename().as_str()?);
-            // Ensure the parent directory exists
-            if let Some(parent) = entry_path.parent() {
+        Ok(DirectoryPage {
+            entries: entries.drain(offset..end).collect(),
+            next_offset: (end < total).then_some(end),
+        })
+    }
+
+    pub async fn write_bytes(
+        &self,
+        file_path: &Path,
+        content: &[u8],
+        create_parents: bool,
+    ) -> ServiceResult<()> {
+        let valid_path = self.validate_write_path(file_path)?;
+        if create_parents {
+            if let Some(parent) = valid_path.parent() {
                 tokio::fs::create_dir_all(parent).await?;
             }
-
-            // Extract the file
-            let reader = zip.reader_without_entry(index).await?;
-            let mut compat_reader = reader.compat();
-            let mut output_file = File::create(&entry_path).await?;
-
-            tokio::io::copy(&mut compat_reader, &mut output_file).await?;
-            output_file.flush().await?;
         }
-
-        let result_message = format!(
-            "Successfully extracted {} {} into '{}'.",
-            file_count,
-            if file_count == 1 { "file" } else { "files" },
-            target_dir_path.display()
-        );
-
-        Ok(result_message)
+        write_atomic(&valid_path, content).await?;
+        Ok(())
     }
 
-    pub async fn read_file(&self, file_path: &Path) -> ServiceResult<String> {
+    /// Copies an existing file to `<path>.bak`, replacing any previous backup.
+    /// Returns the backup path, or `None` when there is no file to back up.
+    pub async fn backup_file(&self, file_path: &Path) -> ServiceResult<Option<PathBuf>> {
         let valid_path = self.validate_path(file_path)?;
-        let content = tokio::fs::read_to_string(valid_path).await?;
-        Ok(content)
+        if !valid_path.is_file() {
+            return Ok(None);
+        }
+
+        let mut backup_name = valid_path.as_os_str().to_os_string();
+        backup_name.push(".bak");
+        let backup_path = self.validate_write_path(Path::new(&backup_name))?;
+
+        tokio::fs::copy(&valid_path, &backup_path).await?;
+        Ok(Some(backup_path))
     }
 
-    pub async fn create_directory(&self, file_path: &Path) -> ServiceResult<()> {
-        let valid_path = self.validate_path(file_path)?;
-        tokio::fs::create_dir_all(valid_path).await?;
+    pub async fn write_file(
+        &self,
+        file_path: &Path,
+        content: impl AsRef<[u8]>,
+        backup: Option<bool>,
+    ) -> ServiceResult<()> {
+        let valid_path = self.validate_write_path(file_path)?;
+        if backup.unwrap_or(self.backup) {
+            self.backup_file(&valid_path).await?;
+        }
+        write_atomic(&valid_path, content).await?;
         Ok(())
     }
 
-    pub async fn move_file(&self, src_path: &Path, dest_path: &Path) -> ServiceResult<()> {
-        let valid_src_path = self.validate_path(src_path)?;
-        let valid_dest_path = self.validate_path(dest_path)?;
-        tokio::fs::rename(valid_src_path, valid_dest_path).await?;
-        Ok(())
+    /// Writes the file only if its current content hashes to `expected_sha256`, or, when
+    /// `expected_sha256` is empty, only if the file does not exist yet.
+    pub async fn write_file_if_matches(
+        &self,
+        file_path: &Path,
+        content: impl AsRef<[u8]>,
+        backup: Option<bool>,
+        expected_sha256: &str,
+    ) -> ServiceResult<()> {
+        let valid_path = self.validate_write_path(file_path)?;
+        let expected = expected_sha256.trim().to_lowercase();
+        let actual = match tokio::fs::read(&valid_path).await {
+            Ok(bytes) => Some(compute_digest("sha256", &bytes)?),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+        let conflict = match &actual {
+            Some(actual) if *actual != expected => Some(format!("has sha256 {}", actual)),
+            None if !expected.is_empty() => Some("does not exist".to_string()),
+            _ => None,
+        };
+        if let Some(actual) = conflict {
+            return Err(ServiceError::WriteConflict {
+                path: file_path.display().to_string(),
+                expected: if expected.is_empty() {
+                    "<none>".to_string()
+                } else {
+                    expected
+                },
+                actual,
+            });
+        }
+        self.write_file(file_path, content, backup).await
     }
 
-    pub async fn list_directory(&self, dir_path: &Path) -> ServiceResult<Vec<tokio::fs::DirEntry>> {
-        let valid_path = self.validate_path(dir_path)?;
+    pub fn sanitize_filename(
+        &self,
+        filename: &str,
+        directory: Option<&Path>,
+    ) -> ServiceResult<PathBuf> {
+        let sanitized = sanitize_filename(filename);
+        match directory {
+            Some(directory) => Ok(self.validate_path(directory)?.join(sanitized)),
+            None => Ok(PathBuf::from(sanitized)),
+        }
+    }
 
-        let mut dir = tokio::fs::read_dir(valid_path).await?;
+    pub async fn render_template(
+        &self,
+        template_path: &Path,
+        variables: &HashMap<String, String>,
+        output_path: &Path,
+        dry_run: Option<bool>,
+    ) -> ServiceResult<String> {
+        let valid_template_path = self.validate_path(template_path)?;
+        let valid_output_path = if dry_run.unwrap_or(false) {
+            self.validate_path(output_path)?
+        } else {
+            self.validate_write_path(output_path)?
+        };
 
-        let mut entries = Vec::new();
+        let template = tokio::fs::read_to_string(valid_template_path).await?;
+        let rendered =
+            render_template_content(&template, variables).map_err(ServiceError::FromString)?;
 
-        // Use a loop to collect the directory entries
-        while let Some(entry) = dir.next_entry().await? {
-            entries.push(entry);
+        if !dry_run.unwrap_or(false) {
+            write_atomic(&valid_output_path, &rendered).await?;
         }
 
-        Ok(entries)
-    }
-
-    pub async fn write_file(&self, file_path: &Path, content: &String) -> ServiceResult<()> {
-        let valid_path = self.validate_path(file_path)?;
-        tokio::fs::write(valid_path, content).await?;
-        Ok(())
+        Ok(rendered)
     }
 
     pub fn search_files(
         &self,
         // root_path: impl Into<PathBuf>,
         root_path: &Path,
-        pattern: String,
-        exclude_patterns: Vec<String>,
+        patterns: &[String],
+        options: &SearchOptions,
     ) -> ServiceResult<Vec<walkdir::DirEntry>> {
         let valid_path = self.validate_path(root_path)?;
+        let (want_files, want_dirs) = parse_entry_type(options.type_filter.as_deref())?;
+        let not_ignored = options
+            .respect_gitignore
+            .then(|| not_ignored_paths(&valid_path, options.max_depth));
+        // Patterns are compiled once into sets, so each entry is matched in a single pass.
+        // Patterns without `*` match anywhere in the path or name.
+        let exclude_patterns: Vec<String> = options
+            .exclude_patterns
+            .iter()
+            .map(|pattern| {
+                if pattern.contains('*') {
+                    pattern.clone()
+                } else {
+                    format!("*{}*", pattern)
+                }
+            })
+            .collect();
+        let exclude_set = glob_set(&exclude_patterns, options.case_sensitive);
+        let include_patterns: Vec<String> = patterns
+            .iter()
+            .map(|pattern| {
+                if pattern.contains('*') {
+                    pattern.clone()
+                } else {
+                    format!("**/*{}*", pattern)
+                }
+            })
+            .collect();
+        let include_set = glob_set(&include_patterns, options.case_sensitive);
 
-        let result = WalkDir::new(valid_path)
-            .follow_links(true)
-            .into_iter()
-            .filter_entry(|dir_entry| {
-                let full_path = dir_entry.path();
-
-                // Validate each path before processing
-                let validated_path = self.validate_path(full_path).ok();
+        let mut walker = WalkDir::new(valid_path).follow_links(true);
+        if let Some(max_depth) = options.max_depth {
+            walker = walker.max_depth(max_depth);
+        }
+        let result = walker.into_iter().filter_entry(|dir_entry| {
+            let full_path = dir_entry.path();
+
+            if not_ignored
+                .as_ref()
+                .is_some_and(|not_ignored| !not_ignored.contains(full_path))
+            {
+                return false;
+            }
 
-                if validated_path.is_none() {
-                    // Skip invalid paths during search
-                    return false;
-                }
+            // The root itself is searched even when hidden
+            if options.skip_hidden && dir_entry.depth() > 0 && is_hidden(full_path) {
+                return false;
+            }
 
-                // Get the relative path from the root_path
-                let relative_path = full_path.strip_prefix(root_path).unwrap_or(full_path);
+            // Validate each path before processing
+            let validated_path = self.validate_path(full_path).ok();
 
-                let should_exclude = exclude_patterns.iter().any(|pattern| {
-                    let glob_pattern = if pattern.contains('*') {
-                        pattern.clone()
-                    } else {
-                        format!("*{}*", pattern)
-                    };
+            if validated_path.is_none() {
+                // Skip invalid paths during search
+                return false;
+            }
 
-                    Pattern::new(&glob_pattern)
-                        .map(|glob| glob.matches(relative_path.to_str().unwrap_or("")))
-                        .unwrap_or(false)
-                });
+            // Get the relative path from the root_path
+            let relative_path = full_path.strip_prefix(root_path).unwrap_or(full_path);
 
-                !should_exclude
-            });
+            !exclude_set.is_match(relative_path.to_str().unwrap_or(""))
+        });
 
-        let updated_pattern = if pattern.contains('*') {
-            pattern.to_lowercase()
-        } else {
-            format!("**/*{}*", &pattern.to_lowercase())
-        };
-        let glob_pattern = Pattern::new(&updated_pattern);
+        // An entry matching any of the patterns is returned; invalid patterns match nothing
         let final_result = result
             .into_iter()
             .filter_map(|v| v.ok())
@@ -430,25 +2412,104 @@ impl FileSystemService {
                 if root_path == entry.path() {
                     return false;
                 }
+                let is_dir = entry.file_type().is_dir();
+                if (is_dir && !want_dirs) || (!is_dir && !want_files) {
+                    return false;
+                }
 
-                let is_match = glob_pattern
-                    .as_ref()
-                    .map(|glob| {
-                        glob.matches(&entry.file_name().to_str().unwrap_or("").to_lowercase())
-                    })
-                    .unwrap_or(false);
-
-                is_match
+                include_set.is_match(entry.file_name().to_str().unwrap_or(""))
             })
             .collect::<Vec<walkdir::DirEntry>>();
         Ok(final_result)
     }
 
+    /// Runs `search_files` in every allowed directory and merges the results. Entries reachable
+    /// from several allowed directories (e.g. nested ones) are reported once.
+    pub fn search_all_directories(
+        &self,
+        patterns: &[String],
+        options: &SearchOptions,
+    ) -> ServiceResult<Vec<walkdir::DirEntry>> {
+        let mut seen = HashSet::new();
+        let mut results = vec![];
+        for root in self.allowed_directories() {
+            let entries = self.search_files(root, patterns, options)?;
+            results.extend(
+                entries
+                    .into_iter()
+                    .filter(|entry| seen.insert(entry.path().to_path_buf())),
+            );
+        }
+        Ok(results)
+    }
+
+    /// Recursively finds entries under `root_path` that satisfy every provided filter.
+    /// Size filters only match files; timestamps are RFC 3339 strings; `entry_type` is `file` or `dir`.
+    pub fn find(
+        &self,
+        root_path: &Path,
+        min_size: Option<u64>,
+        max_size: Option<u64>,
+        modified_after: Option<&str>,
+        modified_before: Option<&str>,
+        entry_type: Option<&str>,
+    ) -> ServiceResult<Vec<walkdir::DirEntry>> {
+        let valid_path = self.validate_path(root_path)?;
+        let modified_after = modified_after.map(parse_rfc3339).transpose()?;
+        let modified_before = modified_before.map(parse_rfc3339).transpose()?;
+        let (want_files, want_dirs) = parse_entry_type(entry_type)?;
+        let has_size_filter = min_size.is_some() || max_size.is_some();
+
+        let result = WalkDir::new(&valid_path)
+            .follow_links(true)
+            .into_iter()
+            .filter_entry(|dir_entry| self.validate_path(dir_entry.path()).is_ok())
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                if entry.path() == valid_path {
+                    return false;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    return false;
+                };
+
+                let type_matches = if metadata.is_dir() {
+                    want_dirs && !has_size_filter
+                } else {
+                    want_files
+                };
+                if !type_matches {
+                    return false;
+                }
+
+                let size = metadata.len();
+                if min_size.is_some_and(|min| size < min) || max_size.is_some_and(|max| size > max)
+                {
+                    return false;
+                }
+
+                if modified_after.is_some() || modified_before.is_some() {
+                    let Ok(modified) = metadata.modified() else {
+                        return false;
+                    };
+                    if modified_after.is_some_and(|after| modified < after)
+                        || modified_before.is_some_and(|before| modified > before)
+                    {
+                        return false;
+                    }
+                }
+                true
+            })
+            .collect::<Vec<walkdir::DirEntry>>();
+        Ok(result)
+    }
+
     pub fn create_unified_diff(
         &self,
         original_content: &str,
         new_content: &str,
         filepath: Option<String>,
+        context_lines: Option<usize>,
     ) -> String {
         // Ensure consistent line endings for diff
         let normalized_original = normalize_line_endings(original_content);
@@ -465,63 +2526,494 @@ impl FileSystemService {
                 format!("{}\toriginal", file_name).as_str(),
                 format!("{}\tmodified", file_name).as_str(),
             )
-            .context_radius(4)
+            .context_radius(context_lines.unwrap_or(4))
             .to_string();
 
         format!("Index: {}\n{}\n{}", file_name, "=".repeat(68), patch)
     }
 
+    /// Returns a unified diff between two files, labeled with their paths.
+    pub async fn compare_files(
+        &self,
+        left: &Path,
+        right: &Path,
+        context_lines: Option<usize>,
+    ) -> ServiceResult<String> {
+        let left_path = self.validate_path(left)?;
+        let right_path = self.validate_path(right)?;
+
+        for path in [&left_path, &right_path] {
+            if !path.is_file() {
+                return Err(ServiceError::FromString(format!(
+                    "Cannot compare: '{}' does not exist or is not a file",
+                    path.display()
+                )));
+            }
+        }
+
+        let left_label = left_path.display().to_string();
+        let right_label = right_path.display().to_string();
+
+        // Like `diff`, binary files are only reported as identical or different
+        if is_probably_binary(&left_path)? || is_probably_binary(&right_path)? {
+            let identical =
+                tokio::fs::read(&left_path).await? == tokio::fs::read(&right_path).await?;
+            return Ok(format!(
+                "Binary files {} and {} {}",
+                left_label,
+                right_label,
+                if identical { "are identical" } else { "differ" }
+            ));
+        }
+
+        let left_content = tokio::fs::read_to_string(&left_path).await?;
+        let right_content = tokio::fs::read_to_string(&right_path).await?;
+
+        let normalized_left = normalize_line_endings(&left_content);
+        let normalized_right = normalize_line_endings(&right_content);
+        let diff = TextDiff::from_lines(&normalized_left, &normalized_right);
+
+        let patch = diff
+            .unified_diff()
+            .header(&left_label, &right_label)
+            .context_radius(context_lines.unwrap_or(4))
+            .to_string();
+
+        Ok(format!(
+            "Index: {} <-> {}\n{}\n{}",
+            left_label,
+            right_label,
+            "=".repeat(68),
+            patch
+        ))
+    }
+
+    /// Replaces every occurrence of `old_text` with `new_text` in the text files under `root_path`
+    /// selected by `options`. Each file is edited through `apply_file_edits`; binary files are
+    /// skipped. Returns the combined diff and the number of files changed, or that would change
+    /// in a dry run.
+    pub async fn replace_in_files(
+        &self,
+        root_path: &Path,
+        old_text: &str,
+        new_text: &str,
+        options: &ReplaceInFilesOptions,
+    ) -> ServiceResult<(String, usize)> {
+        let valid_path = self.validate_path(root_path)?;
+        let (is_regex, dry_run) = (options.is_regex, options.dry_run);
+
+        // Literal text is escaped so that every occurrence is replaced verbatim
+        let edit = if is_regex {
+            EditOperation {
+                old_text: old_text.to_string(),
+                new_text: new_text.to_string(),
+                is_regex: Some(true),
+            }
+        } else {
+            EditOperation {
+                old_text: regex::escape(old_text),
+                new_text: new_text.replace('$', "$$"),
+                is_regex: Some(true),
+            }
+        };
+        let regex = Regex::new(&normalize_line_endings(&edit.old_text))?;
+
+        let files = self.select_files(
+            &valid_path,
+            &options.include_patterns,
+            &options.exclude_patterns,
+        )?;
+
+        // Matching files are collected, with their content for a rollback, before any is written
+        let mut originals: Vec<(PathBuf, Vec<u8>)> = vec![];
+        for file in files {
+            if is_probably_binary(&file)? {
+                continue;
+            }
+            let Ok(content) = tokio::fs::read(&file).await else {
+                continue;
+            };
+            let Ok(text) = std::str::from_utf8(&content) else {
+                continue;
+            };
+            if !regex.is_match(&normalize_line_endings(text)) {
+                continue;
+            }
+            if !dry_run {
+                self.validate_write_path(&file)?;
+            }
+            originals.push((file, content));
+        }
+
+        let edit_options = EditOptions::default();
+        let mut diffs: Vec<String> = vec![];
+        for (index, (file, _)) in originals.iter().enumerate() {
+            match self
+                .apply_file_edits(file, vec![edit.clone()], Some(dry_run), None, &edit_options)
+                .await
+            {
+                Ok(diff) => diffs.push(diff),
+                Err(err) if dry_run => return Err(err),
+                Err(err) => {
+                    return Err(Self::rollback_edits(&originals[..index], file, err).await);
+                }
+            }
+        }
+
+        let changed = diffs.len();
+        Ok((diffs.join(""), changed))
+    }
+
+    /// Restores the files in `written` to their original content after editing `failed_path`
+    /// failed with `err`, and returns the error to report, naming any file that couldn't be
+    /// restored.
+    async fn rollback_edits(
+        written: &[(PathBuf, Vec<u8>)],
+        failed_path: &Path,
+        err: ServiceError,
+    ) -> ServiceError {
+        let mut restore_failures = vec![];
+        for (written_path, content) in written {
+            if let Err(restore_err) = write_atomic(written_path, content).await {
+                restore_failures.push(format!("{}: {}", written_path.display(), restore_err));
+            }
+        }
+        if !restore_failures.is_empty() {
+            return ServiceError::FromString(format!(
+                "Editing {} failed: {}. Restoring the files edited before it also failed, so these may be left changed: {}",
+                failed_path.display(),
+                err,
+                restore_failures.join("; ")
+            ));
+        }
+        ServiceError::FromString(format!(
+            "Editing {} failed, so no files were changed: {}",
+            failed_path.display(),
+            err
+        ))
+    }
+
+    /// Concatenates the text files under `root_path` selected by `options` into one document,
+    /// each preceded by a `== relative/path ==` header, in path order. Binary files are skipped,
+    /// as are files that would take the bundle past its size cap; the paths of the latter are
+    /// returned alongside the bundle.
+    pub async fn bundle_directory(
+        &self,
+        root_path: &Path,
+        options: &BundleDirectoryOptions,
+    ) -> ServiceResult<(String, Vec<String>)> {
+        let valid_path = self.validate_path(root_path)?;
+        let max_size = options.max_size.unwrap_or(DEFAULT_BUNDLE_MAX_SIZE);
+        let files = self.select_files(
+            &valid_path,
+            &options.include_patterns,
+            &options.exclude_patterns,
+        )?;
+
+        let mut bundle = String::new();
+        let mut omitted: Vec<String> = vec![];
+        for file in files {
+            if is_probably_binary(&file)? {
+                continue;
+            }
+            let Ok(mut content) = tokio::fs::read_to_string(&file).await else {
+                continue;
+            };
+            if !content.ends_with('\n') {
+                content.push('\n');
+            }
+            let relative_path = relative_slash_path(&file, &valid_path);
+            let header = format!("== {} ==\n", relative_path);
+            if (bundle.len() + header.len() + content.len()) as u64 > max_size {
+                omitted.push(relative_path);
+                continue;
+            }
+            bundle.push_str(&header);
+            bundle.push_str(&content);
+        }
+        Ok((bundle, omitted))
+    }
+
+    /// Copies the files under `source_dir` selected by `options` to the same relative paths under
+    /// `target_dir`, creating directories as needed. Existing files are skipped unless
+    /// `options.overwrite` is set. Returns the relative paths of the copied and skipped files.
+    pub async fn copy_directory(
+        &self,
+        source_dir: &Path,
+        target_dir: &Path,
+        options: &CopyDirectoryOptions,
+    ) -> ServiceResult<(Vec<String>, Vec<String>)> {
+        let valid_source = self.validate_path(source_dir)?;
+        let valid_target = self.validate_write_path(target_dir)?;
+        if !valid_source.is_dir() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("'{}' is not a directory", valid_source.display()),
+            )
+            .into());
+        }
+        if valid_target.starts_with(&valid_source) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "The destination must not be inside the source directory",
+            )
+            .into());
+        }
+
+        let files = self.select_files(
+            &valid_source,
+            &options.include_patterns,
+            &options.exclude_patterns,
+        )?;
+        let mut copied: Vec<String> = vec![];
+        let mut skipped: Vec<String> = vec![];
+        for file in files {
+            let relative_path = relative_slash_path(&file, &valid_source);
+            let destination = self.validate_write_path(&valid_target.join(&relative_path))?;
+            if destination.exists() && !options.overwrite {
+                skipped.push(relative_path);
+                continue;
+            }
+            if let Some(parent) = destination.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            if options.preserve_metadata {
+                tokio::task::spawn_blocking(move || copy_preserving_metadata(&file, &destination))
+                    .await
+                    .map_err(std::io::Error::other)??;
+            } else {
+                tokio::fs::copy(&file, &destination).await?;
+            }
+            copied.push(relative_path);
+        }
+        Ok((copied, skipped))
+    }
+
+    /// Collects the files under `valid_path`, sorted by path, whose path relative to it matches
+    /// one of `include_patterns` (all files when empty) and none of `exclude_patterns`. Excluded
+    /// directories are not descended into, and paths failing validation are left out.
+    fn select_files(
+        &self,
+        valid_path: &Path,
+        include_patterns: &[String],
+        exclude_patterns: &[String],
+    ) -> ServiceResult<Vec<PathBuf>> {
+        let to_patterns = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| Pattern::new(pattern))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let include_patterns = to_patterns(include_patterns)?;
+        let exclude_patterns = to_patterns(exclude_patterns)?;
+
+        Ok(WalkDir::new(valid_path)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|entry| {
+                self.validate_path(entry.path()).is_ok()
+                    && !exclude_patterns.iter().any(|pattern| {
+                        pattern.matches(&relative_slash_path(entry.path(), valid_path))
+                    })
+            })
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .map(|entry| entry.into_path())
+            .filter(|path| {
+                include_patterns.is_empty()
+                    || include_patterns
+                        .iter()
+                        .any(|pattern| pattern.matches(&relative_slash_path(path, valid_path)))
+            })
+            .collect())
+    }
+
     pub async fn apply_file_edits(
         &self,
         file_path: &Path,
         edits: Vec<EditOperation>,
         dry_run: Option<bool>,
         save_to: Option<&Path>,
+        options: &EditOptions,
+    ) -> ServiceResult<String> {
+        let edits = edits.into_iter().map(FileEdit::from).collect();
+        self.apply_edits(file_path, edits, dry_run, save_to, options)
+            .await
+    }
+
+    /// Applies edits to several files as a single operation and returns the combined diff. If
+    /// editing any file fails, the files already written are restored to their original content
+    /// (best effort) before the error is returned, so either every file is changed or none is.
+    pub async fn apply_multi_file_edits(
+        &self,
+        files: Vec<(String, Vec<EditOperation>)>,
+        dry_run: Option<bool>,
+        options: &EditOptions,
+    ) -> ServiceResult<String> {
+        let mut originals: Vec<(PathBuf, Vec<u8>)> = Vec::with_capacity(files.len());
+        for (path, _) in &files {
+            let valid_path = self.validate_write_path(Path::new(path))?;
+            if originals.iter().any(|(seen, _)| *seen == valid_path) {
+                return Err(ServiceError::FromString(format!(
+                    "{} is listed more than once; combine its edits into one entry",
+                    valid_path.display()
+                )));
+            }
+            let content = tokio::fs::read(&valid_path)
+                .await
+                .map_err(io_error_at(&valid_path))?;
+            originals.push((valid_path, content));
+        }
+
+        let mut diffs = String::new();
+        for (index, ((_, edits), (valid_path, _))) in files.into_iter().zip(&originals).enumerate()
+        {
+            match self
+                .apply_file_edits(valid_path, edits, dry_run, None, options)
+                .await
+            {
+                Ok(diff) => diffs.push_str(&diff),
+                Err(err) if dry_run.unwrap_or(false) => {
+                    return Err(ServiceError::FromString(format!(
+                        "Editing {} failed: {}",
+                        valid_path.display(),
+                        err
+                    )));
+                }
+                Err(err) => {
+                    return Err(Self::rollback_edits(&originals[..index], valid_path, err).await);
+                }
+            }
+        }
+        Ok(diffs)
+    }
+
+    /// Applies text replacements and line-based edits in order, each one seeing the result of
+    /// the previous ones, and returns a git-style diff. See `apply_file_edits`.
+    pub async fn apply_edits(
+        &self,
+        file_path: &Path,
+        edits: Vec<FileEdit>,
+        dry_run: Option<bool>,
+        save_to: Option<&Path>,
+        options: &EditOptions,
     ) -> ServiceResult<String> {
+        if let Some(threshold) = options.fuzzy_threshold {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(ServiceError::FromString(format!(
+                    "Fuzzy threshold must be between 0.0 and 1.0, got {threshold}"
+                )));
+            }
+        }
         let valid_path = self.validate_path(file_path)?;
 
         // Read file content and normalize line endings
-        let content_str = tokio::fs::read_to_string(&valid_path).await?;
-        let original_line_ending = self.detect_line_ending(&content_str);
-        let content_str = normalize_line_endings(&content_str);
+        let original_content = tokio::fs::read_to_string(&valid_path).await?;
+        let content_str = normalize_line_endings(&original_content);
 
         // Apply edits sequentially
         let mut modified_content = content_str.clone();
+        let skip_unmatched = options.skip_unmatched;
+        let total_edits = edits.len();
+        let mut unmatched_edits: Vec<String> = Vec::new();
+        let mut fuzzy_matches: Vec<String> = Vec::new();
 
         for edit in edits {
-            let normalized_old = normalize_line_endings(&edit.old_text);
-            let normalized_new = normalize_line_endings(&edit.new_text);
-            // If exact match exists, use it
-            if modified_content.contains(&normalized_old) {
-                modified_content = modified_content.replacen(&normalized_old, &normalized_new, 1);
-                continue;
-            }
-
-            // Otherwise, try line-by-line matching with flexibility for whitespace
-            let old_lines: Vec<String> = normalized_old
-                .trim_end()
-                .split('\n')
-                .map(|s| s.to_string())
-                .collect();
+            let previous_content = options.strict_span.then(|| modified_content.clone());
+            // The lines of the content before the edit that it's expected to change, including
+            // the line before an insert or deletion whose line break it may add or remove
+            let span = 'edit: {
+                let edit = match edit {
+                    FileEdit::Replace(edit) => edit,
+                    FileEdit::Insert(insert) => {
+                        let line = insert.line as usize;
+                        modified_content = insert_lines(
+                            &modified_content,
+                            line,
+                            &normalize_line_endings(&insert.text),
+                        )?;
+                        break 'edit Some(line.saturating_sub(2)..line);
+                    }
+                    FileEdit::DeleteLines(delete) => {
+                        let (start, end) = (delete.start as usize, delete.end as usize);
+                        modified_content = delete_lines(&modified_content, start, end)?;
+                        break 'edit Some(start.saturating_sub(2)..end);
+                    }
+                };
+                let normalized_old = normalize_line_endings(&edit.old_text);
+                let normalized_new = normalize_line_endings(&edit.new_text);
+
+                if edit.is_regex.unwrap_or(false) {
+                    let regex = Regex::new(&normalized_old)?;
+                    let mut matches = regex.find_iter(&modified_content);
+                    let Some(first_match) = matches.next() else {
+                        if skip_unmatched {
+                            unmatched_edits.push(edit.old_text);
+                            break 'edit None;
+                        }
+                        return Err(RpcError::internal_error()
+                            .with_message(format!(
+                                "Could not find a match for regex edit:\n{}",
+                                edit.old_text
+                            ))
+                            .into());
+                    };
+                    let last_match = matches.last().unwrap_or(first_match);
+                    let span = line_span(&modified_content, first_match.start()..last_match.end());
+                    modified_content = regex
+                        .replace_all(&modified_content, normalized_new.as_str())
+                        .into_owned();
+                    break 'edit Some(span);
+                }
 
-            let content_lines: Vec<String> = modified_content
-                .trim_end()
-                .split('\n')
-                .map(|s| s.to_string())
-                .collect();
+                // If exact match exists, use it
+                if let Some(position) = modified_content.find(&normalized_old) {
+                    let span =
+                        line_span(&modified_content, position..position + normalized_old.len());
+                    modified_content =
+                        modified_content.replacen(&normalized_old, &normalized_new, 1);
+                    break 'edit Some(span);
+                }
 
-            let mut match_found = false;
+                // Otherwise, try line-by-line matching with flexibility for whitespace
+                let old_lines: Vec<String> = normalized_old
+                    .trim_end()
+                    .split('\n')
+                    .map(|s| s.to_string())
+                    .collect();
 
-            for i in 0..=content_lines.len() - old_lines.len() {
-                let potential_match = &content_lines[i..i + old_lines.len()];
+                let content_lines: Vec<String> = modified_content
+                    .trim_end()
+                    .split('\n')
+                    .map(|s| s.to_string())
+                    .collect();
 
                 // Compare lines with normalized whitespace
-                let is_match = old_lines.iter().enumerate().all(|(j, old_line)| {
-                    let content_line = &potential_match[j];
-                    old_line.trim() == content_line.trim()
-                });
+                let mut match_index =
+                    (0..(content_lines.len() + 1).saturating_sub(old_lines.len())).find(|&i| {
+                        let potential_match = &content_lines[i..i + old_lines.len()];
+                        old_lines.iter().enumerate().all(|(j, old_line)| {
+                            let content_line = &potential_match[j];
+                            old_line.trim() == content_line.trim()
+                        })
+                    });
+
+                // Then, if allowed, for the most similar block of lines
+                if let (None, Some(threshold)) = (match_index, options.fuzzy_threshold) {
+                    if let Some((i, similarity)) = closest_line_window(&content_lines, &old_lines) {
+                        if similarity >= threshold as f64 {
+                            fuzzy_matches.push(format!(
+                                "Fuzzy match at line {} (similarity {:.2}) for edit:\n{}",
+                                i + 1,
+                                similarity,
+                                edit.old_text
+                            ));
+                            match_index = Some(i);
+                        }
+                    }
+                }
 
-                if is_match {
+                if let Some(i) = match_index {
                     // Preserve original indentation of first line
                     let original_indent = content_lines[i]
                         .chars()
@@ -575,17 +3067,22 @@ impl FileSystemService {
                     let mut content_lines = content_lines.clone();
                     content_lines.splice(i..i + old_lines.len(), new_lines);
                     modified_content = content_lines.join("\n");
-                    match_found = true;
-                    break;
+                    Some(i..i + old_lines.len())
+                } else if skip_unmatched {
+                    unmatched_edits.push(edit.old_text);
+                    None
+                } else {
+                    let mut message =
+                        format!("Could not find exact match for edit:\n{}", edit.old_text);
+                    if let Some(report) = closest_match_report(&content_lines, &old_lines) {
+                        message.push_str("\n\n");
+                        message.push_str(&report);
+                    }
+                    return Err(RpcError::internal_error().with_message(message).into());
                 }
-            }
-            if !match_found {
-                return Err(RpcError::internal_error()
-                    .with_message(format!(
-                        "Could not find exact match for edit:\n{}",
-                        edit.old_text
-                    ))
-                    .into());
+            };
+            if let (Some(previous_content), Some(span)) = (previous_content, span) {
+                check_edit_span(&previous_content, &modified_content, span)?;
             }
         }
 
@@ -593,6 +3090,7 @@ impl FileSystemService {
             &content_str,
             &modified_content,
             Some(valid_path.display().to_string()),
+            options.context_lines,
         );
 
         // Format diff with appropriate number of backticks
@@ -600,19 +3098,39 @@ impl FileSystemService {
         while diff.contains(&"`".repeat(num_backticks)) {
             num_backticks += 1;
         }
-        let formatted_diff = format!(
+        let mut formatted_diff = format!(
             "{}diff\n{}{}\n\n",
             "`".repeat(num_backticks),
             diff,
             "`".repeat(num_backticks)
         );
 
+        for fuzzy_match in &fuzzy_matches {
+            formatted_diff.push_str(fuzzy_match);
+            formatted_diff.push_str("\n\n");
+        }
+
+        if skip_unmatched {
+            formatted_diff.push_str(&format!(
+                "{}/{} edits applied, {} had no match",
+                total_edits - unmatched_edits.len(),
+                total_edits,
+                unmatched_edits.len()
+            ));
+            for old_text in &unmatched_edits {
+                formatted_diff.push_str(&format!("\n- {}", old_text));
+            }
+        }
+
         let is_dry_run = dry_run.unwrap_or(false);
 
         if !is_dry_run {
-            let target = save_to.unwrap_or(valid_path.as_path());
-            let modified_content = modified_content.replace("\n", original_line_ending);
-            tokio::fs::write(target, modified_content).await?;
+            let target = self.validate_write_path(save_to.unwrap_or(valid_path.as_path()))?;
+            if options.backup.unwrap_or(self.backup) {
+                self.backup_file(&target).await?;
+            }
+            let modified_content = restore_line_endings(&original_content, &modified_content);
+            write_atomic(&target, modified_content).await?;
         }
 
         Ok(formatted_diff)
@@ -1,7 +1,12 @@
 use std::fs::{self};
+use std::path::PathBuf;
 use std::time::SystemTime;
 
-use super::utils::{format_permissions, format_system_time};
+use serde::Serialize;
+
+#[cfg(unix)]
+use super::utils::{format_group, format_user};
+use super::utils::{format_permissions, format_system_time, format_system_time_rfc3339};
 
 #[derive(Debug)]
 pub struct FileInfo {
@@ -11,9 +16,78 @@ pub struct FileInfo {
     pub accessed: Option<SystemTime>,
     pub is_directory: bool,
     pub is_file: bool,
+    pub is_symlink: bool,
+    pub symlink_target: Option<PathBuf>,
+    pub mime_type: Option<String>,
+    /// Line ending style of a text file (`lf`, `crlf`, `cr` or `mixed`), when it was detected.
+    pub line_ending: Option<String>,
+    /// Charset of a text file, when it was detected.
+    pub encoding: Option<String>,
+    #[cfg(unix)]
+    pub uid: u32,
+    #[cfg(unix)]
+    pub gid: u32,
     pub metadata: fs::Metadata,
 }
 
+/// Machine-readable view of a `FileInfo`, with timestamps in RFC 3339 format.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SerializableFileInfo {
+    pub size: u64,
+    pub created: Option<String>,
+    pub modified: Option<String>,
+    pub accessed: Option<String>,
+    pub is_directory: bool,
+    pub is_file: bool,
+    pub is_symlink: bool,
+    pub symlink_target: Option<String>,
+    pub permissions: String,
+    #[cfg(unix)]
+    pub owner: String,
+    #[cfg(unix)]
+    pub group: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_ending: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+impl From<&FileInfo> for SerializableFileInfo {
+    fn from(info: &FileInfo) -> Self {
+        Self {
+            size: info.size,
+            created: info.created.map(format_system_time_rfc3339),
+            modified: info.modified.map(format_system_time_rfc3339),
+            accessed: info.accessed.map(format_system_time_rfc3339),
+            is_directory: info.is_directory,
+            is_file: info.is_file,
+            is_symlink: info.is_symlink,
+            symlink_target: info
+                .symlink_target
+                .as_ref()
+                .map(|target| target.display().to_string()),
+            permissions: format_permissions(&info.metadata),
+            #[cfg(unix)]
+            owner: format_user(info.uid),
+            #[cfg(unix)]
+            group: format_group(info.gid),
+            mime_type: info.mime_type.clone(),
+            line_ending: info.line_ending.clone(),
+            encoding: info.encoding.clone(),
+        }
+    }
+}
+
+impl FileInfo {
+    /// Returns the file information as a JSON object; see `SerializableFileInfo`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(SerializableFileInfo::from(self)).unwrap_or_default()
+    }
+}
+
 impl std::fmt::Display for FileInfo {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -24,6 +98,8 @@ modified: {}
 accessed: {}
 isDirectory: {}
 isFile: {}
+isSymlink: {}
+symlinkTarget: {}
 permissions: {}
 "#,
             self.size,
@@ -32,7 +108,28 @@ permissions: {}
             self.accessed.map_or("".to_string(), format_system_time),
             self.is_directory,
             self.is_file,
+            self.is_symlink,
+            self.symlink_target
+                .as_ref()
+                .map_or("".to_string(), |target| target.display().to_string()),
             format_permissions(&self.metadata)
-        )
+        )?;
+        #[cfg(unix)]
+        write!(
+            f,
+            "owner: {}\ngroup: {}\n",
+            format_user(self.uid),
+            format_group(self.gid)
+        )?;
+        if let Some(mime_type) = &self.mime_type {
+            writeln!(f, "mimeType: {}", mime_type)?;
+        }
+        if let Some(line_ending) = &self.line_ending {
+            writeln!(f, "lineEnding: {}", line_ending)?;
+        }
+        if let Some(encoding) = &self.encoding {
+            writeln!(f, "encoding: {}", encoding)?;
+        }
+        Ok(())
     }
 }
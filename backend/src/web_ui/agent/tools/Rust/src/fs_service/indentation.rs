@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Spaces,
+    Tabs,
+    None,
+}
+
+impl std::fmt::Display for IndentStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndentStyle::Spaces => write!(f, "spaces"),
+            IndentStyle::Tabs => write!(f, "tabs"),
+            IndentStyle::None => write!(f, "none"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct IndentDeviation {
+    /// 1-based line number
+    pub line: usize,
+    pub reason: String,
+}
+
+#[derive(Debug)]
+pub struct IndentationReport {
+    pub style: IndentStyle,
+    /// Indentation width in characters (spaces) or 1 for tabs
+    pub width: usize,
+    pub indented_lines: usize,
+    pub deviations: Vec<IndentDeviation>,
+}
+
+impl IndentationReport {
+    /// Analyzes the leading whitespace of every non-blank line and determines the
+    /// dominant indentation style and width, along with the lines that deviate from it.
+    pub fn analyze(content: &str) -> Self {
+        let indents: Vec<(usize, &str)> = content
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(index, line)| {
+                let indent_len = line.len() - line.trim_start().len();
+                (index + 1, &line[..indent_len])
+            })
+            .collect();
+
+        let tab_lines = indents
+            .iter()
+            .filter(|(_, indent)| indent.starts_with('\t'))
+            .count();
+        let space_lines = indents
+            .iter()
+            .filter(|(_, indent)| indent.starts_with(' '))
+            .count();
+
+        let style = if tab_lines == 0 && space_lines == 0 {
+            IndentStyle::None
+        } else if tab_lines > space_lines {
+            IndentStyle::Tabs
+        } else {
+            IndentStyle::Spaces
+        };
+
+        let width = match style {
+            IndentStyle::Spaces => Self::detect_space_width(&indents),
+            IndentStyle::Tabs => 1,
+            IndentStyle::None => 0,
+        };
+
+        let mut deviations = Vec::new();
+        for (line, indent) in indents.iter().filter(|(_, indent)| !indent.is_empty()) {
+            let reason = match style {
+                IndentStyle::Spaces if indent.contains('\t') => {
+                    Some("uses tabs where spaces are expected".to_string())
+                }
+                IndentStyle::Tabs if indent.contains(' ') => {
+                    Some("uses spaces where tabs are expected".to_string())
+                }
+                IndentStyle::Spaces if indent.len() % width != 0 => Some(format!(
+                    "indentation of {} spaces is not a multiple of {}",
+                    indent.len(),
+                    width
+                )),
+                _ => None,
+            };
+            if let Some(reason) = reason {
+                deviations.push(IndentDeviation {
+                    line: *line,
+                    reason,
+                });
+            }
+        }
+
+        Self {
+            style,
+            width,
+            indented_lines: tab_lines + space_lines,
+            deviations,
+        }
+    }
+
+    /// Percentage of indented lines that follow the dominant style.
+    pub fn consistency(&self) -> f64 {
+        if self.indented_lines == 0 {
+            return 100.0;
+        }
+        let consistent = self.indented_lines - self.deviations.len();
+        consistent as f64 * 100.0 / self.indented_lines as f64
+    }
+
+    // The most common increase in indentation between consecutive space-indented lines
+    fn detect_space_width(indents: &[(usize, &str)]) -> usize {
+        let mut steps: HashMap<usize, usize> = HashMap::new();
+        let mut previous = 0;
+        for (_, indent) in indents {
+            if indent.contains('\t') {
+                continue;
+            }
+            let current = indent.len();
+            if current > previous {
+                *steps.entry(current - previous).or_default() += 1;
+            }
+            previous = current;
+        }
+
+        steps
+            .into_iter()
+            .max_by(|(step_a, count_a), (step_b, count_b)| {
+                count_a.cmp(count_b).then(step_b.cmp(step_a))
+            })
+            .map(|(step, _)| step)
+            .unwrap_or(4)
+    }
+}
+
+impl std::fmt::Display for IndentationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            r#"style: {}
+width: {}
+indentedLines: {}
+deviatingLines: {}
+consistency: {:.1}%
+"#,
+            self.style,
+            self.width,
+            self.indented_lines,
+            self.deviations.len(),
+            self.consistency()
+        )?;
+        for deviation in &self.deviations {
+            writeln!(f, "line {}: {}", deviation.line, deviation.reason)?;
+        }
+        Ok(())
+    }
+}
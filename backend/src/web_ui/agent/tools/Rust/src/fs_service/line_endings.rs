@@ -0,0 +1,118 @@
+use similar::{DiffOp, TextDiff};
+
+use super::utils::normalize_line_endings;
+
+/// Line ending style of a text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+    Cr,
+    /// More than one style is used.
+    Mixed,
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::Lf => write!(f, "lf"),
+            LineEnding::Crlf => write!(f, "crlf"),
+            LineEnding::Cr => write!(f, "cr"),
+            LineEnding::Mixed => write!(f, "mixed"),
+        }
+    }
+}
+
+/// Number of line breaks of each style in a text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LineEndingCounts {
+    pub lf: usize,
+    pub crlf: usize,
+    pub cr: usize,
+}
+
+impl LineEndingCounts {
+    /// Counts the line breaks in `text`; a `\r\n` pair counts as one CRLF, not a CR and an LF.
+    pub fn count(text: &str) -> Self {
+        let mut counts = Self::default();
+        let mut bytes = text.bytes().peekable();
+        while let Some(byte) = bytes.next() {
+            match byte {
+                b'\n' => counts.lf += 1,
+                b'\r' if bytes.next_if_eq(&b'\n').is_some() => counts.crlf += 1,
+                b'\r' => counts.cr += 1,
+                _ => {}
+            }
+        }
+        counts
+    }
+
+    /// Classifies the text as using a single style or a mix of them. Returns `None` for text
+    /// without line breaks.
+    pub fn style(&self) -> Option<LineEnding> {
+        match (self.lf, self.crlf, self.cr) {
+            (0, 0, 0) => None,
+            (_, 0, 0) => Some(LineEnding::Lf),
+            (0, _, 0) => Some(LineEnding::Crlf),
+            (0, 0, _) => Some(LineEnding::Cr),
+            _ => Some(LineEnding::Mixed),
+        }
+    }
+
+    /// Returns the most frequent line break, preferring CRLF, then LF on ties, or LF for text
+    /// without line breaks.
+    pub fn dominant(&self) -> &'static str {
+        if self.crlf > 0 && self.crlf >= self.lf && self.crlf >= self.cr {
+            "\r\n"
+        } else if self.cr > self.lf {
+            "\r"
+        } else {
+            "\n"
+        }
+    }
+}
+
+/// Converts the LF line breaks of `modified`, an edited copy of `original` with normalized line
+/// endings, back to the endings of `original`: lines left unchanged by the edit keep their own
+/// ending, while changed and inserted lines get the dominant ending of `original`.
+pub fn restore_line_endings(original: &str, modified: &str) -> String {
+    let mut original_endings: Vec<&str> = vec![];
+    let mut rest = original;
+    while !rest.is_empty() {
+        let Some(index) = rest.find(['\r', '\n']) else {
+            original_endings.push("");
+            break;
+        };
+        let ending = if rest[index..].starts_with("\r\n") {
+            "\r\n"
+        } else {
+            &rest[index..index + 1]
+        };
+        original_endings.push(ending);
+        rest = &rest[index + ending.len()..];
+    }
+    let default_ending = LineEndingCounts::count(original).dominant();
+
+    let normalized = normalize_line_endings(original);
+    let mut endings = vec![default_ending; modified.split_inclusive('\n').count()];
+    for op in TextDiff::from_lines(normalized.as_str(), modified).ops() {
+        if let DiffOp::Equal {
+            old_index,
+            new_index,
+            len,
+        } = *op
+        {
+            endings[new_index..new_index + len]
+                .copy_from_slice(&original_endings[old_index..old_index + len]);
+        }
+    }
+
+    modified
+        .split_inclusive('\n')
+        .zip(endings)
+        .map(|(line, ending)| match line.strip_suffix('\n') {
+            Some(line) => format!("{}{}", line, ending),
+            None => line.to_string(),
+        })
+        .collect()
+}
@@ -1,15 +1,30 @@
 use std::{
+    collections::HashMap,
     fs::{self},
+    io::Read,
+    ops::Range,
     path::{Component, Path, PathBuf, Prefix},
     time::SystemTime,
 };
 
-use async_zip::{error::ZipError, tokio::write::ZipFileWriter, Compression, ZipEntryBuilder};
-use chrono::{DateTime, Local};
+use async_zip::{
+    error::ZipError, tokio::write::ZipFileWriter, Compression, ZipDateTime, ZipEntryBuilder,
+};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use chardetng::EncodingDetector;
+use chrono::{DateTime, Local, Utc};
 use dirs::home_dir;
+use encoding_rs::{Encoding, UTF_8, WINDOWS_1252};
+use filetime::FileTime;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use sha2::{Digest, Sha256, Sha512};
+use similar::TextDiff;
+
+use super::{ProgressCallback, MAX_IN_MEMORY_ZIP_SIZE};
+use crate::error::{ServiceError, ServiceResult};
 
 use tokio::fs::File;
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
@@ -23,6 +38,24 @@ pub fn format_system_time(system_time: SystemTime) -> String {
     datetime.format("%a %b %d %Y %H:%M:%S %:z").to_string()
 }
 
+/// Formats a timestamp as RFC 3339 / ISO 8601 in the local time zone, keeping sub-second
+/// precision so it parses back to the same instant, e.g. `2025-04-12T14:30:45.123456789+00:00`
+pub fn format_system_time_rfc3339(system_time: SystemTime) -> String {
+    DateTime::<Local>::from(system_time).to_rfc3339()
+}
+
+/// Parses an RFC 3339 / ISO 8601 timestamp such as `2025-05-01T12:00:00Z`
+pub fn parse_rfc3339(value: &str) -> ServiceResult<SystemTime> {
+    DateTime::parse_from_rfc3339(value)
+        .map(SystemTime::from)
+        .map_err(|err| {
+            ServiceError::FromString(format!(
+                "Invalid timestamp '{}', expected RFC 3339 (e.g. 2025-05-01T12:00:00Z): {}",
+                value, err
+            ))
+        })
+}
+
 pub fn format_permissions(metadata: &fs::Metadata) -> String {
     #[cfg(unix)]
     {
@@ -55,8 +88,154 @@ pub fn format_permissions(metadata: &fs::Metadata) -> String {
     }
 }
 
+/// Formats a Unix user id along with its user name when it can be resolved, e.g. `1000 (alice)`
+#[cfg(unix)]
+pub fn format_user(uid: u32) -> String {
+    match uzers::get_user_by_uid(uid) {
+        Some(user) => format!("{} ({})", uid, user.name().to_string_lossy()),
+        None => uid.to_string(),
+    }
+}
+
+/// Formats a Unix group id along with its group name when it can be resolved, e.g. `100 (users)`
+#[cfg(unix)]
+pub fn format_group(gid: u32) -> String {
+    match uzers::get_group_by_gid(gid) {
+        Some(group) => format!("{} ({})", gid, group.name().to_string_lossy()),
+        None => gid.to_string(),
+    }
+}
+
+/// Resolves a path to its canonical form. If the path does not exist yet, the deepest existing
+/// ancestor is canonicalized (resolving symlinks) and the remaining components are appended
+/// with `.` and `..` collapsed lexically, so `..` can never be used to escape a directory.
+///
+/// On Windows, verbatim (`\\?\`) prefixes are removed whenever the path can be expressed without
+/// them, so long, UNC and verbatim paths compare consistently with `starts_with`.
 pub fn normalize_path(path: &Path) -> PathBuf {
-    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+    if let Ok(canonical) = dunce::canonicalize(path) {
+        return canonical;
+    }
+
+    let path = dunce::simplified(path);
+    let components: Vec<Component> = path.components().collect();
+    for index in (1..components.len()).rev() {
+        let ancestor: PathBuf = components[..index].iter().collect();
+        if let Ok(canonical) = dunce::canonicalize(&ancestor) {
+            let remainder: PathBuf = components[index..].iter().collect();
+            return clean_path(&canonical.join(remainder));
+        }
+    }
+    clean_path(path)
+}
+
+/// Lexically normalizes a path without touching the filesystem: `.` components are removed and
+/// `..` removes the preceding component. `..` at the root is dropped, as the root is its own parent.
+pub fn clean_path(path: &Path) -> PathBuf {
+    let mut cleaned: Vec<Component> = Vec::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match cleaned.last() {
+                Some(Component::Normal(_)) => {
+                    cleaned.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => cleaned.push(component),
+            },
+            _ => cleaned.push(component),
+        }
+    }
+
+    if cleaned.is_empty() {
+        return PathBuf::from(".");
+    }
+    cleaned.iter().collect()
+}
+
+/// Returns `true` if the given path contains glob metacharacters (`*`, `?` or `[`).
+pub fn is_glob_pattern(path: &str) -> bool {
+    path.contains(['*', '?', '['])
+}
+
+/// Expands brace alternatives in a glob pattern into one pattern per alternative, e.g.
+/// `*.{rs,toml}` into `*.rs` and `*.toml`, since `glob::Pattern` doesn't support them. Braces
+/// can be nested; braces without a comma inside and unbalanced braces are kept literally.
+pub fn expand_braces(pattern: &str) -> Vec<String> {
+    let mut depth = 0;
+    let mut open = 0;
+    for (index, c) in pattern.char_indices() {
+        match c {
+            '{' => {
+                if depth == 0 {
+                    open = index;
+                }
+                depth += 1;
+            }
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth > 0 {
+                    continue;
+                }
+                let alternatives = split_top_level_commas(&pattern[open + 1..index]);
+                if alternatives.len() > 1 {
+                    let (prefix, suffix) = (&pattern[..open], &pattern[index + 1..]);
+                    return alternatives
+                        .iter()
+                        .flat_map(|alternative| {
+                            expand_braces(&format!("{}{}{}", prefix, alternative, suffix))
+                        })
+                        .collect();
+                }
+            }
+            _ => {}
+        }
+    }
+    vec![pattern.to_string()]
+}
+
+/// Compiles glob patterns, after brace expansion (see `expand_braces`), into a single set that
+/// matches a path against all of them at once. As with `glob::Pattern`, `*` also matches path
+/// separators and leftover braces are literal. Invalid patterns are left out, so they match
+/// nothing.
+pub fn glob_set<'a>(
+    patterns: impl IntoIterator<Item = &'a String>,
+    case_sensitive: bool,
+) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns
+        .into_iter()
+        .flat_map(|pattern| expand_braces(pattern))
+    {
+        let escaped = pattern.replace('{', "[{]").replace('}', "[}]");
+        if let Ok(glob) = GlobBuilder::new(&escaped)
+            .case_insensitive(!case_sensitive)
+            .build()
+        {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
+}
+
+/// Splits `text` at the commas that are not inside nested braces.
+fn split_top_level_commas(text: &str) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
 }
 
 pub fn expand_home(path: PathBuf) -> PathBuf {
@@ -85,27 +264,379 @@ pub fn format_bytes(bytes: u64) -> String {
     format!("{} bytes", bytes)
 }
 
-pub async fn write_zip_entry(
+pub async fn write_zip_entry<W: tokio::io::AsyncWrite + Unpin>(
     filename: &str,
     input_path: &Path,
-    zip_writer: &mut ZipFileWriter<File>,
+    zip_writer: &mut ZipFileWriter<W>,
 ) -> Result<(), ZipError> {
     let mut input_file = File::open(input_path).await?;
-    let input_file_size = input_file.metadata().await?.len() as usize;
+    let metadata = input_file.metadata().await?;
+    let input_file_size = metadata.len() as usize;
 
     let mut buffer = Vec::with_capacity(input_file_size);
     input_file.read_to_end(&mut buffer).await?;
 
-    let builder = ZipEntryBuilder::new(filename.into(), Compression::Deflate);
+    let builder = with_zip_attributes(
+        ZipEntryBuilder::new(filename.into(), Compression::Deflate),
+        &metadata,
+    );
     zip_writer.write_entry_whole(builder, &buffer).await?;
 
     Ok(())
 }
 
+/// Writes a directory entry (a name ending in `/` with no content), used to preserve
+/// empty directories in an archive.
+pub async fn write_zip_dir_entry<W: tokio::io::AsyncWrite + Unpin>(
+    dirname: &str,
+    input_path: &Path,
+    zip_writer: &mut ZipFileWriter<W>,
+) -> Result<(), ZipError> {
+    let metadata = tokio::fs::metadata(input_path).await?;
+    let builder = with_zip_attributes(
+        ZipEntryBuilder::new(
+            format!("{}/", dirname.trim_end_matches('/')).into(),
+            Compression::Stored,
+        ),
+        &metadata,
+    );
+    zip_writer.write_entry_whole(builder, &[]).await?;
+
+    Ok(())
+}
+
+/// Writes the walked `entries` of `base_dir` to the archive, named relative to `base_dir` and
+/// prefixed with `root_prefix/` when given (see `zip_root_prefix`). `progress` is called after
+/// each entry.
+pub async fn write_zip_directory_entries<W: tokio::io::AsyncWrite + Unpin>(
+    entries: &[PathBuf],
+    base_dir: &Path,
+    root_prefix: Option<&str>,
+    zip_writer: &mut ZipFileWriter<W>,
+    progress: Option<ProgressCallback>,
+) -> ServiceResult<()> {
+    for (index, entry_path_buf) in entries.iter().enumerate() {
+        let entry_path = entry_path_buf.as_path();
+        let mut entry_name = zip_entry_name(entry_path, base_dir)?;
+        if let Some(root_prefix) = root_prefix {
+            entry_name = format!("{}/{}", root_prefix, entry_name);
+        }
+        if entry_path.is_dir() {
+            // Only empty directories need their own entry, others are implied by their contents
+            if fs::read_dir(entry_path)?.next().is_none() {
+                write_zip_dir_entry(&entry_name, entry_path, zip_writer).await?;
+            }
+        } else {
+            write_zip_entry(&entry_name, entry_path, zip_writer).await?;
+        }
+        if let Some(progress) = &progress {
+            progress(index + 1, entries.len());
+        }
+    }
+    Ok(())
+}
+
+/// Normalizes a folder path to prepend to archive entry names, such as `project` or
+/// `release/v1`, by trimming surrounding slashes. Fails if it is empty, uses backslashes or has
+/// empty, `.` or `..` segments, which could make entries extract outside the target directory.
+pub fn zip_root_prefix(root_prefix: &str) -> ServiceResult<String> {
+    let trimmed = root_prefix.trim_matches('/');
+    if trimmed.is_empty()
+        || trimmed.contains('\\')
+        || trimmed
+            .split('/')
+            .any(|segment| matches!(segment, "" | "." | ".."))
+    {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("Invalid root prefix '{}'", root_prefix),
+        )
+        .into());
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Resolves the extraction path of archive entry `entry_name` under `target_dir`. Fails for
+/// absolute names and names with `..` segments (either separator), which could write outside
+/// `target_dir` ("zip slip").
+pub fn zip_entry_path(target_dir: &Path, entry_name: &str) -> ServiceResult<PathBuf> {
+    let relative = Path::new(entry_name);
+    if entry_name.split(['/', '\\']).any(|segment| segment == "..")
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(ServiceError::AccessDenied(format!(
+            "archive entry '{}' would extract outside of {}",
+            entry_name,
+            target_dir.display()
+        )));
+    }
+    Ok(target_dir.join(relative))
+}
+
+/// Names the archive entries for `source_paths` after their file names. A name already in
+/// `entry_names` is skipped and returned in the second list when `skip_duplicates` is set,
+/// otherwise it's an error. New names are added to `entry_names`.
+#[allow(clippy::type_complexity)]
+pub fn zip_file_entries(
+    source_paths: Vec<PathBuf>,
+    entry_names: &mut Vec<String>,
+    skip_duplicates: bool,
+) -> ServiceResult<(Vec<(String, PathBuf)>, Vec<String>)> {
+    let mut new_entries: Vec<(String, PathBuf)> = vec![];
+    let mut skipped: Vec<String> = vec![];
+    for path in source_paths {
+        let filename = path.file_name().ok_or(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Invalid path!",
+        ))?;
+
+        let filename = filename
+            .to_str()
+            .ok_or(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid UTF-8 in file name",
+            ))?
+            .to_string();
+
+        if entry_names.contains(&filename) {
+            if skip_duplicates {
+                skipped.push(filename);
+                continue;
+            }
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                format!("Entry '{}' already exists in the archive!", filename),
+            )
+            .into());
+        }
+        entry_names.push(filename.clone());
+        new_entries.push((filename, path));
+    }
+    Ok((new_entries, skipped))
+}
+
+/// Fails if the files among `paths` total more than `MAX_IN_MEMORY_ZIP_SIZE` bytes, before an
+/// in-memory archive of them is built.
+pub fn check_in_memory_zip_size(paths: &[PathBuf]) -> ServiceResult<()> {
+    let mut size = 0;
+    for path in paths {
+        let metadata = fs::metadata(path)?;
+        if metadata.is_file() {
+            size += metadata.len();
+        }
+    }
+    if size > MAX_IN_MEMORY_ZIP_SIZE {
+        return Err(ServiceError::InMemoryZipSizeExceeded {
+            size,
+            limit: MAX_IN_MEMORY_ZIP_SIZE,
+        });
+    }
+    Ok(())
+}
+
+/// Records the modification time and, on Unix, the permissions of the source so they can be
+/// restored on extraction.
+fn with_zip_attributes(mut builder: ZipEntryBuilder, metadata: &fs::Metadata) -> ZipEntryBuilder {
+    if let Ok(modified) = metadata.modified() {
+        builder = builder
+            .last_modification_date(ZipDateTime::from_chrono(&DateTime::<Utc>::from(modified)));
+    }
+    #[cfg(unix)]
+    {
+        builder = builder.unix_permissions(metadata.permissions().mode() as u16);
+    }
+    builder
+}
+
+/// Builds a portable ZIP entry name for `path` relative to `base_dir`, joining the
+/// components with `/` regardless of the platform's path separator.
+pub fn zip_entry_name(path: &Path, base_dir: &Path) -> std::io::Result<String> {
+    let relative_path = path.strip_prefix(base_dir).map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Entry file path does not start with base input directory path.",
+        )
+    })?;
+
+    relative_path
+        .components()
+        .map(|component| {
+            component.as_os_str().to_str().ok_or(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "Invalid UTF-8 in file name",
+            ))
+        })
+        .collect::<std::io::Result<Vec<_>>>()
+        .map(|components| components.join("/"))
+}
+
+/// Returns `path` relative to `base_dir` with `/` separators, or the whole path if it isn't
+/// under `base_dir`.
+pub fn relative_slash_path(path: &Path, base_dir: &Path) -> String {
+    path.strip_prefix(base_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
 pub fn normalize_line_endings(text: &str) -> String {
     text.replace("\r\n", "\n").replace('\r', "\n")
 }
 
+/// Converts an I/O error raised while accessing `path`, reporting a missing path as
+/// `ServiceError::NotFound` so that it names the path and can't be mistaken for a denial.
+pub fn io_error_at(path: &Path) -> impl FnOnce(std::io::Error) -> ServiceError + '_ {
+    move |err| match err.kind() {
+        std::io::ErrorKind::NotFound => ServiceError::NotFound(path.display().to_string()),
+        _ => err.into(),
+    }
+}
+
+/// Finds the block of `target.len()` consecutive lines in `lines` most similar to `target`,
+/// ignoring leading and trailing whitespace on each line. Returns the index of its first line
+/// and its similarity from 0.0 (nothing in common) to 1.0 (identical).
+pub fn closest_line_window(lines: &[String], target: &[String]) -> Option<(usize, f64)> {
+    if target.is_empty() || target.len() > lines.len() {
+        return None;
+    }
+    let join_trimmed = |lines: &[String]| {
+        lines
+            .iter()
+            .map(|line| line.trim())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    let window_len = target.len();
+    let target = join_trimmed(target);
+    lines
+        .windows(window_len)
+        .map(join_trimmed)
+        .map(|window| strsim::normalized_levenshtein(&window, &target))
+        .enumerate()
+        // Reversed so that the first of equally similar windows wins
+        .rev()
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+}
+
+/// Describes where `target` comes closest to matching in `lines`: the 1-based line number,
+/// the similarity and a unified diff of the expected against the actual lines.
+pub fn closest_match_report(lines: &[String], target: &[String]) -> Option<String> {
+    let (index, similarity) = closest_line_window(lines, target)?;
+    let expected = format!("{}\n", target.join("\n"));
+    let actual = format!("{}\n", lines[index..index + target.len()].join("\n"));
+    let diff = TextDiff::from_lines(&expected, &actual)
+        .unified_diff()
+        .context_radius(2)
+        .header("expected", &format!("actual (line {})", index + 1))
+        .to_string();
+    Some(format!(
+        "Closest match at line {} (similarity {:.2}):\n{}",
+        index + 1,
+        similarity,
+        diff
+    ))
+}
+
+/// Splits `\n`-separated text into lines, reporting whether it ended with a line break.
+/// Empty text has no lines.
+fn split_lines(content: &str) -> (Vec<&str>, bool) {
+    if content.is_empty() {
+        return (vec![], false);
+    }
+    match content.strip_suffix('\n') {
+        Some(content) => (content.split('\n').collect(), true),
+        None => (content.split('\n').collect(), false),
+    }
+}
+
+/// Returns the 0-based range of lines of `text` that the byte range `range` is part of.
+pub fn line_span(text: &str, range: Range<usize>) -> Range<usize> {
+    let first = text[..range.start].matches('\n').count();
+    let last = text[..range.end].matches('\n').count();
+    first..last + 1
+}
+
+/// Fails unless every line that differs between `before` and `after` lies within `span`, the
+/// 0-based range of lines of `before` that an edit was expected to change. A line whose line
+/// break was added or removed counts as changed.
+pub fn check_edit_span(before: &str, after: &str, span: Range<usize>) -> ServiceResult<()> {
+    let before_lines: Vec<&str> = before.split_inclusive('\n').collect();
+    let after_lines: Vec<&str> = after.split_inclusive('\n').collect();
+    let common_prefix = before_lines
+        .iter()
+        .zip(&after_lines)
+        .take_while(|(before, after)| before == after)
+        .count();
+    if common_prefix == before_lines.len() && common_prefix == after_lines.len() {
+        return Ok(());
+    }
+    let common_suffix = before_lines
+        .iter()
+        .rev()
+        .zip(after_lines.iter().rev())
+        .take(before_lines.len().min(after_lines.len()) - common_prefix)
+        .take_while(|(before, after)| before == after)
+        .count();
+
+    let changed = common_prefix..before_lines.len() - common_suffix;
+    if changed.start < span.start || changed.end > span.end {
+        return Err(ServiceError::FromString(format!(
+            "Edit changed lines {}-{}, outside of the expected lines {}-{}; no changes were written",
+            changed.start + 1,
+            changed.end.max(changed.start + 1),
+            span.start + 1,
+            span.end.max(span.start + 1)
+        )));
+    }
+    Ok(())
+}
+
+/// Inserts `text` before the 1-based `line` of `\n`-separated `content`. `line` may be one past
+/// the last line to append. A single trailing line break of `text` is ignored, since the
+/// inserted lines are joined with line breaks.
+pub fn insert_lines(content: &str, line: usize, text: &str) -> ServiceResult<String> {
+    let (mut lines, trailing_newline) = split_lines(content);
+    if line == 0 || line > lines.len() + 1 {
+        return Err(ServiceError::FromString(format!(
+            "Cannot insert at line {}: the file has {} lines, so the line must be between 1 and {}",
+            line,
+            lines.len(),
+            lines.len() + 1
+        )));
+    }
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    lines.splice(line - 1..line - 1, text.split('\n'));
+
+    let mut result = lines.join("\n");
+    if trailing_newline {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// Removes the 1-based, inclusive line range `start..=end` from `\n`-separated `content`.
+pub fn delete_lines(content: &str, start: usize, end: usize) -> ServiceResult<String> {
+    let (mut lines, trailing_newline) = split_lines(content);
+    if start == 0 || start > end || end > lines.len() {
+        return Err(ServiceError::FromString(format!(
+            "Cannot delete lines {}-{}: the file has {} lines, and the range must satisfy 1 <= start <= end <= {}",
+            start,
+            end,
+            lines.len(),
+            lines.len()
+        )));
+    }
+    lines.drain(start - 1..end);
+
+    let mut result = lines.join("\n");
+    if trailing_newline && !lines.is_empty() {
+        result.push('\n');
+    }
+    Ok(result)
+}
+
 // checks if path component is a  Prefix::VerbatimDisk
 fn is_verbatim_disk(component: &Component) -> bool {
     match component {
@@ -114,6 +645,55 @@ fn is_verbatim_disk(component: &Component) -> bool {
     }
 }
 
+/// Formats `bytes` like `hexdump -C`: each line holds the offset, 16 bytes in hex (split into
+/// two groups of 8) and their printable ASCII characters, with `.` for the others.
+pub fn format_hex_dump(bytes: &[u8]) -> String {
+    let mut dump = String::new();
+    for (index, chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for position in 0..16 {
+            if position == 8 {
+                hex.push(' ');
+            }
+            match chunk.get(position) {
+                Some(byte) => hex.push_str(&format!("{:02x} ", byte)),
+                None => hex.push_str("   "),
+            }
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&byte| {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    byte as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        dump.push_str(&format!("{:08x}  {} |{}|\n", index * 16, hex, ascii));
+    }
+    dump
+}
+
+/// Whether `path` is a hidden entry: its name starts with `.` or, on Windows, it has the hidden
+/// attribute.
+pub fn is_hidden(path: &Path) -> bool {
+    if path
+        .file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+    {
+        return true;
+    }
+    #[cfg(windows)]
+    {
+        const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+        fs::symlink_metadata(path)
+            .is_ok_and(|metadata| metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0)
+    }
+    #[cfg(not(windows))]
+    false
+}
+
 /// Check path contains a symlink
 pub fn contains_symlink<P: AsRef<Path>>(path: P) -> std::io::Result<bool> {
     let mut current_path = PathBuf::new();
@@ -140,3 +720,494 @@ pub fn contains_symlink<P: AsRef<Path>>(path: P) -> std::io::Result<bool> {
 
     Ok(false)
 }
+
+/// Renders `{{name}}` placeholders in `template` using `variables`.
+/// A placeholder may provide a fallback value as `{{name|default}}`; referencing an
+/// undefined variable without a default is an error.
+pub fn render_template_content(
+    template: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, String> {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let after_open = &rest[start + 2..];
+        let Some(end) = after_open.find("}}") else {
+            // No closing braces, keep the remainder verbatim
+            rendered.push_str(&rest[start..]);
+            return Ok(rendered);
+        };
+
+        let placeholder = &after_open[..end];
+        let (name, default_value) = match placeholder.split_once('|') {
+            Some((name, default_value)) => (name.trim(), Some(default_value.trim())),
+            None => (placeholder.trim(), None),
+        };
+
+        match (variables.get(name), default_value) {
+            (Some(value), _) => rendered.push_str(value),
+            (None, Some(default_value)) => rendered.push_str(default_value),
+            (None, None) => return Err(format!("Undefined template variable: '{}'", name)),
+        }
+        rest = &after_open[end + 2..];
+    }
+
+    rendered.push_str(rest);
+    Ok(rendered)
+}
+
+/// Returns a unique, hidden temporary path next to `target`, suitable for writing a
+/// replacement that is later renamed over it.
+pub fn temp_sibling_path(target: &Path) -> std::io::Result<PathBuf> {
+    let file_name = target
+        .file_name()
+        .ok_or(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Invalid path!",
+        ))?
+        .to_string_lossy();
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    Ok(target.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        nanos
+    )))
+}
+
+/// Writes `content` to a hidden temporary file next to `target` and returns its path.
+/// The temporary file is flushed and synced to disk, but `target` is left untouched.
+pub async fn write_temp_sibling(target: &Path, content: &[u8]) -> std::io::Result<PathBuf> {
+    let temp_path = temp_sibling_path(target)?;
+
+    let mut temp_file = File::create(&temp_path).await?;
+    let write_result = async {
+        temp_file.write_all(content).await?;
+        temp_file.flush().await?;
+        temp_file.sync_all().await
+    }
+    .await;
+
+    if let Err(err) = write_result {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+    Ok(temp_path)
+}
+
+/// Follows `target` through any symlinks to the file they point at. `target` itself may not
+/// exist yet, but a symlink whose final target is missing is refused, since that target
+/// was never checked against the allowed directories.
+fn resolve_symlinks(target: &Path) -> std::io::Result<PathBuf> {
+    let mut resolved = target.to_path_buf();
+    // Bounded like the OS limit, so a symlink loop cannot spin forever
+    for hops in 0..40 {
+        match fs::symlink_metadata(&resolved) {
+            Ok(metadata) if metadata.is_symlink() => {}
+            Ok(_) => return Ok(resolved),
+            // The path was validated, but a dangling link's final target never was
+            Err(_) if hops > 0 => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    format!(
+                        "Refusing to write through dangling symbolic link: {} -> {}",
+                        target.display(),
+                        resolved.display()
+                    ),
+                ))
+            }
+            Err(_) => return Ok(resolved),
+        }
+        let link = fs::read_link(&resolved)?;
+        resolved = match resolved.parent() {
+            Some(parent) => parent.join(link),
+            None => link,
+        };
+    }
+    Err(std::io::Error::other(format!(
+        "Too many levels of symbolic links: {}",
+        target.display()
+    )))
+}
+
+/// Atomically replaces `target` with `content` by writing a sibling temporary file
+/// and renaming it over the target, so readers never observe a partially written file.
+/// Symlinks are followed and the file they point at is replaced, keeping its permissions
+/// and owner. Files with several hard links are rewritten in place instead, so every link
+/// sees the new content.
+pub async fn write_atomic(target: &Path, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let target = &resolve_symlinks(target)?;
+    let metadata = tokio::fs::metadata(target).await.ok();
+    #[cfg(unix)]
+    if metadata
+        .as_ref()
+        .is_some_and(|metadata| std::os::unix::fs::MetadataExt::nlink(metadata) > 1)
+    {
+        return tokio::fs::write(target, content).await;
+    }
+
+    let temp_path = write_temp_sibling(target, content.as_ref()).await?;
+    // Keep the permissions and owner of the file being replaced
+    if let Some(metadata) = metadata {
+        let _ = tokio::fs::set_permissions(&temp_path, metadata.permissions()).await;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            let _ =
+                std::os::unix::fs::chown(&temp_path, Some(metadata.uid()), Some(metadata.gid()));
+        }
+    }
+    if let Err(err) = tokio::fs::rename(&temp_path, target).await {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+        return Err(err);
+    }
+    Ok(())
+}
+
+/// Copies `src` to `dest`, recursing into directories, and carries over permissions and
+/// access/modification times so the copy looks like the original. Symlinks are recreated
+/// rather than followed on Unix.
+pub fn copy_preserving_metadata(src: &Path, dest: &Path) -> std::io::Result<()> {
+    let metadata = fs::symlink_metadata(src)?;
+    if metadata.file_type().is_symlink() {
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(fs::read_link(src)?, dest)?;
+        #[cfg(not(unix))]
+        fs::copy(src, dest)?;
+        return Ok(());
+    }
+
+    if metadata.is_dir() {
+        fs::create_dir_all(dest)?;
+        for entry in fs::read_dir(src)? {
+            let entry = entry?;
+            copy_preserving_metadata(&entry.path(), &dest.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(src, dest)?;
+    }
+    // Applied after the contents so read-only directories can still be filled
+    fs::set_permissions(dest, metadata.permissions())?;
+    filetime::set_file_times(
+        dest,
+        FileTime::from_last_access_time(&metadata),
+        FileTime::from_last_modification_time(&metadata),
+    )?;
+    Ok(())
+}
+
+/// Renames `src` to `dest`. When they are on different filesystems, falls back to copying with
+/// `copy_preserving_metadata` and removing the source.
+pub async fn rename_or_copy(src: &Path, dest: &Path) -> std::io::Result<()> {
+    match tokio::fs::rename(src, dest).await {
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => {
+            let (src, dest) = (src.to_path_buf(), dest.to_path_buf());
+            tokio::task::spawn_blocking(move || copy_and_remove(&src, &dest)).await?
+        }
+        result => result,
+    }
+}
+
+/// Blocking version of `rename_or_copy`.
+pub fn rename_or_copy_blocking(src: &Path, dest: &Path) -> std::io::Result<()> {
+    match fs::rename(src, dest) {
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => copy_and_remove(src, dest),
+        result => result,
+    }
+}
+
+fn copy_and_remove(src: &Path, dest: &Path) -> std::io::Result<()> {
+    copy_preserving_metadata(src, dest)?;
+    if fs::symlink_metadata(src)?.is_dir() {
+        fs::remove_dir_all(src)
+    } else {
+        fs::remove_file(src)
+    }
+}
+
+/// Returns the entries of directory `src` that `merge_directories` would find already present
+/// in `dest`, outside of subdirectories that exist in both and are merged.
+pub fn merge_conflicts(src: &Path, dest: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut conflicts = vec![];
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dest.join(entry.file_name());
+        match fs::symlink_metadata(&target) {
+            Ok(metadata) if metadata.is_dir() && entry.file_type()?.is_dir() => {
+                conflicts.extend(merge_conflicts(&entry.path(), &target)?);
+            }
+            Ok(_) => conflicts.push(target),
+            Err(_) => {}
+        }
+    }
+    Ok(conflicts)
+}
+
+/// Moves the contents of directory `src` into the existing directory `dest`, then removes
+/// `src`. Subdirectories that exist in both are merged recursively. Other entries that already
+/// exist in `dest` are replaced when `overwrite` is set, or else kept, with the moved entry taking
+/// a free name from `unique_path`.
+pub fn merge_directories(src: &Path, dest: &Path, overwrite: bool) -> std::io::Result<()> {
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let mut target = dest.join(entry.file_name());
+        match fs::symlink_metadata(&target) {
+            Ok(metadata) if metadata.is_dir() && entry.file_type()?.is_dir() => {
+                merge_directories(&entry.path(), &target, overwrite)?;
+                continue;
+            }
+            Ok(metadata) if overwrite => {
+                if metadata.is_dir() {
+                    fs::remove_dir_all(&target)?;
+                } else {
+                    fs::remove_file(&target)?;
+                }
+            }
+            Ok(_) => target = unique_path(&target),
+            Err(_) => {}
+        }
+        rename_or_copy_blocking(&entry.path(), &target)?;
+    }
+    fs::remove_dir(src)
+}
+
+const MAX_FILENAME_BYTES: usize = 255;
+const WINDOWS_RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Returns `path` if nothing exists there, otherwise the first free path created by appending
+/// ` (1)`, ` (2)`, ... to the file stem, keeping the extension (e.g. `report (1).txt`).
+pub fn unique_path(path: &Path) -> PathBuf {
+    if fs::symlink_metadata(path).is_err() {
+        return path.to_path_buf();
+    }
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+
+    (1..)
+        .map(|index| path.with_file_name(format!("{} ({}){}", stem, index, extension)))
+        .find(|candidate| fs::symlink_metadata(candidate).is_err())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Turns arbitrary input into a filename that is safe to use on all supported platforms.
+/// Path separators and traversal segments are collapsed, characters that are illegal on
+/// Windows or Unix (including null bytes) are replaced, reserved Windows device names are
+/// prefixed and the result is truncated to 255 bytes while keeping the extension.
+pub fn sanitize_filename(input: &str) -> String {
+    let joined = input
+        .split(['/', '\\'])
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+        .collect::<Vec<_>>()
+        .join("_");
+
+    let replaced: String = joined
+        .chars()
+        .filter(|c| *c != '\0')
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '|' | '?' | '*' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    // Windows does not allow names ending with a dot or a space, leading dots would hide the file
+    let mut sanitized = replaced
+        .trim_start_matches(['.', ' '])
+        .trim_end_matches(['.', ' '])
+        .to_string();
+
+    if sanitized.is_empty() {
+        return "unnamed".to_string();
+    }
+
+    let stem = sanitized.split('.').next().unwrap_or_default();
+    if WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(stem))
+    {
+        sanitized.insert(0, '_');
+    }
+
+    if sanitized.len() > MAX_FILENAME_BYTES {
+        let extension = Path::new(&sanitized)
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .filter(|ext| ext.len() < MAX_FILENAME_BYTES / 2)
+            .unwrap_or_default();
+        let mut end = MAX_FILENAME_BYTES - extension.len();
+        while !sanitized.is_char_boundary(end) {
+            end -= 1;
+        }
+        sanitized = format!("{}{}", &sanitized[..end], extension);
+    }
+
+    sanitized
+}
+
+/// Computes the digest of `bytes` with the named algorithm (`sha256` or `sha512`) as
+/// lowercase hex.
+pub fn compute_digest(algorithm: &str, bytes: &[u8]) -> ServiceResult<String> {
+    match algorithm.to_lowercase().as_str() {
+        "sha256" => Ok(format!("{:x}", Sha256::digest(bytes))),
+        "sha512" => Ok(format!("{:x}", Sha512::digest(bytes))),
+        _ => Err(ServiceError::FromString(format!(
+            "Unsupported hash algorithm '{}'. Expected one of: sha256, sha512",
+            algorithm
+        ))),
+    }
+}
+
+/// Decodes file bytes to a string using an encoding mode: `utf-8` (fails on invalid UTF-8),
+/// `lossy` (invalid sequences are replaced with U+FFFD), `base64` (raw bytes, base64-encoded) or
+/// `auto`. In `auto` mode the charset is taken from a byte order mark, assumed to be UTF-8 when
+/// the bytes are valid UTF-8 and guessed otherwise; its name is returned alongside the text.
+pub fn decode_bytes(
+    bytes: Vec<u8>,
+    encoding: &str,
+) -> ServiceResult<(String, Option<&'static str>)> {
+    match encoding.to_lowercase().as_str() {
+        "utf-8" | "utf8" => {
+            let content = String::from_utf8(bytes)
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+            Ok((content, None))
+        }
+        "lossy" => Ok((String::from_utf8_lossy(&bytes).into_owned(), None)),
+        "base64" => Ok((BASE64_STANDARD.encode(bytes), None)),
+        "auto" => {
+            let charset = detect_charset(&bytes);
+            // `decode` strips a matching byte order mark
+            let (content, _, _) = charset.decode(&bytes);
+            Ok((content.into_owned(), Some(charset.name())))
+        }
+        other => Err(ServiceError::FromString(format!(
+            "Unsupported encoding '{}'. Expected one of: utf-8, lossy, base64, auto",
+            other
+        ))),
+    }
+}
+
+/// Detects the charset of `bytes`: it is taken from a byte order mark, assumed to be UTF-8 when
+/// the bytes are valid UTF-8 and guessed otherwise.
+pub fn detect_charset(bytes: &[u8]) -> &'static Encoding {
+    match Encoding::for_bom(bytes) {
+        Some((charset, _)) => charset,
+        None if std::str::from_utf8(bytes).is_ok() => UTF_8,
+        None => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(bytes, true);
+            detector.guess(None, true)
+        }
+    }
+}
+
+/// Encodes text for writing as `utf-8`, `utf-16le` (with a byte order mark, so readers can
+/// detect it) or `latin1` (Windows-1252). Fails if the text has characters the encoding
+/// cannot represent.
+pub fn encode_text(content: &str, encoding: &str) -> ServiceResult<Vec<u8>> {
+    match encoding.to_lowercase().as_str() {
+        "utf-8" | "utf8" => Ok(content.as_bytes().to_vec()),
+        "utf-16le" | "utf16le" => {
+            // encoding_rs only decodes UTF-16, so the code units are written directly
+            let mut bytes = vec![0xFF, 0xFE];
+            bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+            Ok(bytes)
+        }
+        "latin1" | "latin-1" | "iso-8859-1" | "windows-1252" => {
+            let (bytes, _, had_unmappable) = WINDOWS_1252.encode(content);
+            if had_unmappable {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "Content contains characters that cannot be encoded as latin1",
+                )
+                .into());
+            }
+            Ok(bytes.into_owned())
+        }
+        other => Err(ServiceError::FromString(format!(
+            "Unsupported encoding '{}'. Expected one of: utf-8, utf-16le, latin1",
+            other
+        ))),
+    }
+}
+
+/// Identifies the file behind a path so that `tail_follow` can notice when it is replaced.
+/// Only Unix exposes a stable identity (the inode); elsewhere truncation is still detected.
+pub fn file_identity(metadata: &fs::Metadata) -> Option<u64> {
+    #[cfg(unix)]
+    {
+        Some(std::os::unix::fs::MetadataExt::ino(metadata))
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        None
+    }
+}
+
+/// Whether two paths refer to the same file, even through `..` segments, symlinks or hard links.
+pub fn is_same_file(a: &Path, b: &Path) -> bool {
+    if normalize_path(a) == normalize_path(b) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        if let (Ok(a), Ok(b)) = (fs::metadata(a), fs::metadata(b)) {
+            return a.dev() == b.dev() && a.ino() == b.ino();
+        }
+    }
+    false
+}
+
+/// Number of bytes sampled from the start of a file by `is_probably_binary`.
+const BINARY_SAMPLE_SIZE: u64 = 8 * 1024;
+
+/// Guesses whether a file holds binary rather than text data from its first few KB: it is
+/// considered binary if the sample contains a NUL byte or more than 30% of it is invalid UTF-8.
+pub fn is_probably_binary(path: &Path) -> std::io::Result<bool> {
+    let mut sample = Vec::new();
+    fs::File::open(path)?
+        .take(BINARY_SAMPLE_SIZE)
+        .read_to_end(&mut sample)?;
+    if sample.contains(&0) {
+        return Ok(true);
+    }
+
+    let mut invalid_bytes = 0;
+    let mut rest = sample.as_slice();
+    while let Err(err) = std::str::from_utf8(rest) {
+        // A multi-byte sequence cut off by the end of the sample isn't counted as invalid
+        let Some(error_len) = err.error_len() else {
+            break;
+        };
+        invalid_bytes += error_len;
+        rest = &rest[err.valid_up_to() + error_len..];
+    }
+    Ok(invalid_bytes * 10 > sample.len() * 3)
+}
+
+/// Detects the MIME type of a file by sniffing its magic bytes, falling back to its extension.
+pub fn detect_mime_type(path: &Path) -> Option<String> {
+    if let Ok(Some(kind)) = infer::get_from_path(path) {
+        return Some(kind.mime_type().to_string());
+    }
+    mime_guess::from_path(path)
+        .first()
+        .map(|mime| mime.essence_str().to_string())
+}
@@ -0,0 +1,53 @@
+use serde::Serialize;
+
+/// Line, word and byte counts of a file, computed incrementally so large files
+/// never need to be held in memory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct WordCount {
+    pub lines: u64,
+    pub words: u64,
+    pub bytes: u64,
+    /// Whether the previous chunk ended in the middle of a word
+    #[serde(skip)]
+    in_word: bool,
+    /// Whether the previous byte was a `\r`, so a following `\n` completes a `\r\n` pair
+    #[serde(skip)]
+    after_cr: bool,
+    /// Whether the last byte seen was a line terminator
+    #[serde(skip)]
+    at_line_start: bool,
+}
+
+impl WordCount {
+    /// Feeds the next chunk of the file into the counters.
+    ///
+    /// `\n`, `\r\n` and a lone `\r` are all treated as a single line terminator,
+    /// even when a `\r\n` pair is split across two chunks.
+    pub fn update(&mut self, chunk: &[u8]) {
+        for &byte in chunk {
+            match byte {
+                b'\n' if self.after_cr => {}
+                b'\n' | b'\r' => self.lines += 1,
+                _ => {}
+            }
+            self.after_cr = byte == b'\r';
+            self.at_line_start = byte == b'\n' || byte == b'\r';
+
+            if byte.is_ascii_whitespace() {
+                self.in_word = false;
+            } else if !self.in_word {
+                self.in_word = true;
+                self.words += 1;
+            }
+        }
+        self.bytes += chunk.len() as u64;
+    }
+
+    /// Completes the count, including a trailing line that has no line terminator.
+    pub fn finish(mut self) -> Self {
+        if self.bytes > 0 && !self.at_line_start {
+            self.lines += 1;
+        }
+        self
+    }
+}
@@ -1,31 +1,243 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    collections::HashSet,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
+use crate::audit_log::AuditLog;
 use crate::cli::CommandArguments;
 use crate::error::ServiceError;
-use crate::{error::ServiceResult, fs_service::FileSystemService, tools::*};
+use crate::rate_limit::RateLimiter;
+use crate::{
+    error::ServiceResult,
+    fs_service::{AccessLevel, FileSystemService, ProgressCallback},
+    tools::*,
+};
 use async_trait::async_trait;
+use rust_mcp_schema::{schema_utils::NotificationFromServer, InitializeRequest, InitializeResult};
 use rust_mcp_schema::{
-    schema_utils::CallToolError, CallToolRequest, CallToolResult, ListToolsRequest,
-    ListToolsResult, RpcError,
+    schema_utils::{CallToolError, UnknownTool},
+    CallToolRequest, CallToolResult, ListToolsRequest, ListToolsResult, LoggingLevel,
+    LoggingMessageNotification, LoggingMessageNotificationParams, ProgressNotification,
+    ProgressNotificationParams, ProgressToken, RpcError, SetLevelRequest, Tool,
 };
-use rust_mcp_schema::{InitializeRequest, InitializeResult};
 use rust_mcp_sdk::mcp_server::ServerHandler;
 use rust_mcp_sdk::McpServer;
+use tokio::sync::Semaphore;
+use tokio_util::sync::CancellationToken;
+
+/// Tool calls taking longer than this are reported to the client as slow.
+const SLOW_OPERATION_THRESHOLD: Duration = Duration::from_secs(5);
+
+/// Orders logging levels from least to most severe.
+fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
+
+/// The machine-readable code of a failed tool call, sent to clients as `_meta.errorCode`.
+pub fn error_code(err: &CallToolError) -> &'static str {
+    if let Some(err) = err.0.downcast_ref::<ServiceError>() {
+        err.code()
+    } else if err.0.is::<UnknownTool>() {
+        "UNKNOWN_TOOL"
+    } else if err.0.is::<serde_json::Error>() {
+        "INVALID_ARGUMENT"
+    } else {
+        "INTERNAL_ERROR"
+    }
+}
+
+/// Builds the result of a failed tool call, carrying the human-readable message as content and
+/// the error code in `_meta.errorCode`.
+pub fn error_result(code: &str, message: String) -> CallToolResult {
+    let mut meta = serde_json::Map::new();
+    meta.insert("errorCode".to_string(), code.into());
+    CallToolResult::with_error(CallToolError::new(ServiceError::FromString(message)))
+        .with_meta(Some(meta))
+}
 
 pub struct MyServerHandler {
     readonly: bool,
     fs_service: FileSystemService,
+    audit_log: Option<Arc<AuditLog>>,
+    operation_limit: Option<Semaphore>,
+    rate_limiter: Option<RateLimiter>,
+    disabled_tools: HashSet<String>,
+    log_level: RwLock<LoggingLevel>,
 }
 
 impl MyServerHandler {
     pub fn new(args: &CommandArguments) -> ServiceResult<Self> {
-        let fs_service = FileSystemService::try_new(&args.allowed_directories)?;
+        let default_access = if args.allow_write {
+            AccessLevel::ReadWrite
+        } else {
+            AccessLevel::ReadOnly
+        };
+        let fs_service = FileSystemService::try_new(&args.allowed_directories)?
+            .with_default_access(default_access)
+            .with_backup(args.backup)
+            .with_denied(&args.deny)?
+            .with_trash_dir(args.trash_dir.as_deref())?
+            .with_max_extract_size(args.max_extract_size);
+        // Directories marked `:rw` accept writes even when the server is otherwise read-only;
+        // per-directory access is then enforced by the service.
+        let readonly = !fs_service.has_writable_directories();
+        let audit_log = args
+            .audit_log
+            .as_ref()
+            .map(|path| AuditLog::open(Path::new(path)).map(Arc::new))
+            .transpose()?;
+        let operation_limit = match args.max_concurrent_operations {
+            Some(0) => {
+                return Err(ServiceError::FromString(
+                    "Error: --max-concurrent-operations must be at least 1".to_string(),
+                ))
+            }
+            Some(limit) => Some(Semaphore::new(limit)),
+            None => None,
+        };
+        let rate_limiter = args.rate_limit.map(RateLimiter::new).transpose()?;
+        let disabled_tools = Self::disabled_tools(&args.enable, &args.disable)?;
         Ok(Self {
             fs_service,
-            readonly: !&args.allow_write,
+            readonly,
+            audit_log,
+            operation_limit,
+            rate_limiter,
+            disabled_tools,
+            log_level: RwLock::new(LoggingLevel::Info),
         })
     }
 
+    /// Sets the minimum level of log messages sent to the client.
+    pub fn set_log_level(&self, level: LoggingLevel) {
+        if let Ok(mut log_level) = self.log_level.write() {
+            *log_level = level;
+        }
+    }
+
+    /// Builds a log message for the client, or `None` if `level` is below the current log level.
+    pub fn log_message(
+        &self,
+        level: LoggingLevel,
+        logger: &str,
+        data: serde_json::Value,
+    ) -> Option<LoggingMessageNotificationParams> {
+        let min_level = self
+            .log_level
+            .read()
+            .map_or(LoggingLevel::Info, |level| *level);
+        (severity(level) >= severity(min_level)).then(|| LoggingMessageNotificationParams {
+            data,
+            level,
+            logger: Some(logger.to_string()),
+        })
+    }
+
+    /// Log messages describing a completed tool call: failures, including rejected paths and
+    /// exceeded limits, are reported as warnings and calls slower than
+    /// `SLOW_OPERATION_THRESHOLD` as notices.
+    pub fn tool_call_log_messages(
+        &self,
+        tool: &str,
+        error: Option<&str>,
+        elapsed: Duration,
+    ) -> Vec<LoggingMessageNotificationParams> {
+        let failure = error.and_then(|error| {
+            self.log_message(
+                LoggingLevel::Warning,
+                "tool_call",
+                serde_json::json!({ "tool": tool, "error": error }),
+            )
+        });
+        let slow = (elapsed >= SLOW_OPERATION_THRESHOLD)
+            .then(|| {
+                self.log_message(
+                    LoggingLevel::Notice,
+                    "slow_operation",
+                    serde_json::json!({ "tool": tool, "elapsedMs": elapsed.as_millis() as u64 }),
+                )
+            })
+            .flatten();
+        failure.into_iter().chain(slow).collect()
+    }
+
+    /// Names of the tools turned off by `--enable` and `--disable`. Unknown names are rejected
+    /// so that a typo doesn't silently leave a tool exposed.
+    fn disabled_tools(enable: &[String], disable: &[String]) -> ServiceResult<HashSet<String>> {
+        let all_tools: HashSet<String> = FileSystemTools::tools()
+            .into_iter()
+            .map(|tool| tool.name)
+            .collect();
+        if let Some(unknown) = enable
+            .iter()
+            .chain(disable)
+            .find(|name| !all_tools.contains(*name))
+        {
+            return Err(ServiceError::FromString(format!(
+                "Error: unknown tool '{}' in --enable or --disable",
+                unknown
+            )));
+        }
+        Ok(all_tools
+            .into_iter()
+            .filter(|name| (!enable.is_empty() && !enable.contains(name)) || disable.contains(name))
+            .collect())
+    }
+
+    /// The tools advertised to clients, leaving out disabled ones.
+    pub fn tools(&self) -> Vec<Tool> {
+        FileSystemTools::tools()
+            .into_iter()
+            .filter(|tool| !self.disabled_tools.contains(&tool.name))
+            .collect()
+    }
+
+    fn assert_tool_enabled(&self, name: &str) -> std::result::Result<(), CallToolError> {
+        if self.disabled_tools.contains(name) {
+            Err(CallToolError::new(ServiceError::ToolDisabled(
+                name.to_string(),
+            )))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Turns a tool call request into the tool's parameters, rejecting disabled tools.
+    pub fn parse_tool_call(
+        &self,
+        request: CallToolRequest,
+    ) -> std::result::Result<FileSystemTools, CallToolError> {
+        self.assert_tool_enabled(&request.params.name)?;
+        FileSystemTools::try_from(request.params).map_err(CallToolError::new)
+    }
+
+    /// Runs `operation` once fewer than `--max-concurrent-operations` operations are running,
+    /// or right away when no limit is set. The SDK's server runtime awaits each request before
+    /// reading the next, so the limit only queues calls when the handler is driven concurrently.
+    pub async fn with_operation_permit<T>(
+        &self,
+        operation: impl Future<Output = std::result::Result<T, CallToolError>>,
+    ) -> std::result::Result<T, CallToolError> {
+        let _permit = match &self.operation_limit {
+            Some(limit) => Some(limit.acquire().await.map_err(CallToolError::new)?),
+            None => None,
+        };
+        operation.await
+    }
+
     pub fn assert_write_access(&self) -> std::result::Result<(), CallToolError> {
         if self.readonly {
             Err(CallToolError::new(ServiceError::NoWriteAccess))
@@ -34,6 +246,296 @@ impl MyServerHandler {
         }
     }
 
+    /// Resolves the path arguments of a tool call to their validated absolute forms.
+    /// Paths that fail validation are left out.
+    fn resolved_paths(&self, tool_params: &FileSystemTools) -> Vec<PathBuf> {
+        tool_params
+            .paths()
+            .into_iter()
+            .filter_map(|path| self.fs_service.validate_path(Path::new(path)).ok())
+            .collect()
+    }
+
+    /// Runs a tool that reports events through a callback, forwarding each event to the client
+    /// as the notification built by `to_notification`. The token passed to the tool is cancelled
+    /// once notifications can no longer be delivered.
+    async fn run_with_notifications<T, F, Fut>(
+        runtime: &dyn McpServer,
+        to_notification: impl Fn(T) -> NotificationFromServer + Send,
+        run: F,
+    ) -> std::result::Result<CallToolResult, CallToolError>
+    where
+        T: Send + 'static,
+        F: FnOnce(Arc<dyn Fn(T) + Send + Sync>, CancellationToken) -> Fut,
+        Fut: Future<Output = std::result::Result<CallToolResult, CallToolError>>,
+    {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let notify: Arc<dyn Fn(T) + Send + Sync> = Arc::new(move |event| {
+            let _ = sender.send(event);
+        });
+        let cancel = CancellationToken::new();
+        // The tool owns the only sender, so the forwarding loop ends once the tool completes.
+        let forward_notifications = async {
+            while let Some(event) = receiver.recv().await {
+                if runtime
+                    .send_notification(to_notification(event))
+                    .await
+                    .is_err()
+                {
+                    cancel.cancel();
+                }
+            }
+        };
+        // `CallToolError` isn't `Send`, so the error is carried as its code and message while
+        // notifications are still being forwarded.
+        let run_tool = async {
+            run(notify, cancel.clone())
+                .await
+                .map_err(|err| (error_code(&err), err.to_string()))
+        };
+        let (result, _) = tokio::join!(run_tool, forward_notifications);
+        result
+            .map_err(|(code, message)| CallToolError::new(ServiceError::WithCode { code, message }))
+    }
+
+    /// Runs a long-running tool, forwarding its progress to the client as
+    /// `notifications/progress` when the caller supplied a progress token.
+    async fn run_with_progress<F, Fut>(
+        runtime: &dyn McpServer,
+        progress_token: Option<String>,
+        run: F,
+    ) -> std::result::Result<CallToolResult, CallToolError>
+    where
+        F: FnOnce(Option<ProgressCallback>) -> Fut,
+        Fut: Future<Output = std::result::Result<CallToolResult, CallToolError>>,
+    {
+        let Some(progress_token) = progress_token else {
+            return run(None).await;
+        };
+
+        let to_notification = move |(processed, total): (usize, usize)| {
+            ProgressNotification::new(ProgressNotificationParams {
+                message: None,
+                progress: processed as f64,
+                progress_token: ProgressToken::String(progress_token.clone()),
+                total: Some(total as f64),
+            })
+            .into()
+        };
+        Self::run_with_notifications(runtime, to_notification, |notify, _| {
+            run(Some(Arc::new(move |processed, total| {
+                notify((processed, total))
+            })))
+        })
+        .await
+    }
+
+    async fn run_tool(
+        &self,
+        tool_params: FileSystemTools,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        // Verify write access for tools that modify the file system
+        if tool_params.require_write_access() {
+            self.assert_write_access()?;
+        }
+
+        match tool_params {
+            FileSystemTools::ReadFileTool(params) => {
+                ReadFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ReadMultipleFilesTool(params) => {
+                ReadMultipleFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::WriteFileTool(params) => {
+                WriteFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::EditFileTool(params) => {
+                EditFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::CreateDirectoryTool(params) => {
+                CreateDirectoryTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ListDirectoryTool(params) => {
+                ListDirectoryTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::DirectoryTreeTool(params) => {
+                DirectoryTreeTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::MoveFileTool(params) => {
+                MoveFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::SearchFilesTool(params) => {
+                SearchFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::GetFileInfoTool(params) => {
+                GetFileInfoTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ListAllowedDirectoriesTool(params) => {
+                ListAllowedDirectoriesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ZipFilesTool(params) => {
+                Self::run_with_progress(runtime, params.progress_token.clone(), |progress| {
+                    ZipFilesTool::run_tool(params, &self.fs_service, progress)
+                })
+                .await
+            }
+            FileSystemTools::UnzipFileTool(params) => {
+                Self::run_with_progress(runtime, params.progress_token.clone(), |progress| {
+                    UnzipFileTool::run_tool(params, &self.fs_service, progress)
+                })
+                .await
+            }
+            FileSystemTools::ZipDirectoryTool(params) => {
+                Self::run_with_progress(runtime, params.progress_token.clone(), |progress| {
+                    ZipDirectoryTool::run_tool(params, &self.fs_service, progress)
+                })
+                .await
+            }
+            FileSystemTools::RenderTemplateTool(params) => {
+                RenderTemplateTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::IndentationReportTool(params) => {
+                IndentationReportTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::SanitizeFilenameTool(params) => {
+                SanitizeFilenameTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::WriteBinaryFileTool(params) => {
+                WriteBinaryFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::SetPermissionsTool(params) => {
+                SetPermissionsTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::TouchTool(params) => {
+                TouchTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::FindTool(params) => FindTool::run_tool(params, &self.fs_service).await,
+            FileSystemTools::CompareFilesTool(params) => {
+                CompareFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::WordCountTool(params) => {
+                WordCountTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::TruncateFileTool(params) => {
+                TruncateFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::DeleteTool(params) => {
+                DeleteTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::GzipTool(params) => GzipTool::run_tool(params, &self.fs_service).await,
+            FileSystemTools::GunzipTool(params) => {
+                GunzipTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ZstdCompressTool(params) => {
+                ZstdCompressTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ZstdDecompressTool(params) => {
+                ZstdDecompressTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::GetMultipleFileInfoTool(params) => {
+                GetMultipleFileInfoTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::TailFollowTool(params) => {
+                let path = params.path.clone();
+                let to_notification = move |line: String| {
+                    LoggingMessageNotification::new(LoggingMessageNotificationParams {
+                        data: serde_json::json!({ "path": path, "line": line }),
+                        level: LoggingLevel::Info,
+                        logger: Some("tail_follow".to_string()),
+                    })
+                    .into()
+                };
+                Self::run_with_notifications(runtime, to_notification, |notify, cancel| {
+                    TailFollowTool::run_tool(params, &self.fs_service, Some(notify), cancel)
+                })
+                .await
+            }
+            FileSystemTools::BatchMoveTool(params) => {
+                BatchMoveTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ReplaceInFilesTool(params) => {
+                ReplaceInFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::FindEmptyDirsTool(params) => {
+                FindEmptyDirsTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::LargestFilesTool(params) => {
+                LargestFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ServerInfoTool(params) => {
+                ServerInfoTool::run_tool(
+                    params,
+                    &self.fs_service,
+                    &runtime.server_info().protocol_version,
+                )
+                .await
+            }
+            FileSystemTools::DiskSpaceTool(params) => {
+                DiskSpaceTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ExistsTool(params) => {
+                ExistsTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::RealPathTool(params) => {
+                RealPathTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::CreateTempFileTool(params) => {
+                CreateTempFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::ConcatFilesTool(params) => {
+                ConcatFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::SplitFileTool(params) => {
+                SplitFileTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::EditMultipleFilesTool(params) => {
+                EditMultipleFilesTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::BundleDirectoryTool(params) => {
+                BundleDirectoryTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::CopyDirectoryTool(params) => {
+                CopyDirectoryTool::run_tool(params, &self.fs_service).await
+            }
+            FileSystemTools::HexDumpTool(params) => {
+                HexDumpTool::run_tool(params, &self.fs_service).await
+            }
+        }
+    }
+
+    /// The audit log configured with `--audit-log`, shared so it can be closed at shutdown.
+    pub fn audit_log(&self) -> Option<Arc<AuditLog>> {
+        self.audit_log.clone()
+    }
+
+    /// Records a tool call in the audit log when one is configured, returning the reason when
+    /// the record couldn't be written.
+    pub fn audit(&self, tool: &str, paths: &[PathBuf], error: Option<&str>) -> Option<String> {
+        let audit_log = self.audit_log.as_ref()?;
+        audit_log
+            .record(tool, paths, error.map(str::to_string))
+            .err()
+            .map(|err| err.to_string())
+    }
+
+    /// Parses and runs a tool call once it passes the rate limit and gets an operation permit.
+    /// The validated paths of the call are stored in `paths` as soon as it is parsed.
+    async fn call_tool(
+        &self,
+        request: CallToolRequest,
+        runtime: &dyn McpServer,
+        paths: &mut Vec<PathBuf>,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        if let Some(rate_limiter) = &self.rate_limiter {
+            rate_limiter.try_acquire().map_err(CallToolError::new)?;
+        }
+        let tool_params = self.parse_tool_call(request)?;
+        *paths = self.resolved_paths(&tool_params);
+        self.with_operation_permit(self.run_tool(tool_params, runtime))
+            .await
+    }
+
     pub fn startup_message(&self) -> String {
         format!(
             "Secure MCP Filesystem Server running in \"{}\" mode.\nAllowed directories:\n{}",
@@ -45,7 +547,15 @@ impl MyServerHandler {
             self.fs_service
                 .allowed_directories()
                 .iter()
-                .map(|p| p.display().to_string())
+                .map(|p| match self.fs_service.access_level(p) {
+                    Ok(AccessLevel::ReadOnly) if !self.readonly => {
+                        format!("{} (readonly)", p.display())
+                    }
+                    Ok(AccessLevel::ReadWrite) if self.readonly => {
+                        format!("{} (read/write)", p.display())
+                    }
+                    _ => p.display().to_string(),
+                })
                 .collect::<Vec<String>>()
                 .join(",\n")
         )
@@ -65,7 +575,7 @@ impl ServerHandler for MyServerHandler {
         _: &dyn McpServer,
     ) -> std::result::Result<ListToolsResult, RpcError> {
         Ok(ListToolsResult {
-            tools: FileSystemTools::tools(),
+            tools: self.tools(),
             meta: None,
             next_cursor: None,
         })
@@ -95,59 +605,50 @@ impl ServerHandler for MyServerHandler {
     async fn handle_call_tool_request(
         &self,
         request: CallToolRequest,
-        _: &dyn McpServer,
+        runtime: &dyn McpServer,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        let tool_params: FileSystemTools =
-            FileSystemTools::try_from(request.params).map_err(CallToolError::new)?;
+        let tool_name = request.params.name.clone();
+        let started = Instant::now();
+        let mut paths = vec![];
+        // `CallToolError` isn't `Send`, so the error is carried as its code and message while
+        // log messages are sent.
+        let result = self
+            .call_tool(request, runtime, &mut paths)
+            .await
+            .map_err(|err| (error_code(&err), err.to_string()));
 
-        // Verify write access for tools that modify the file system
-        if tool_params.require_write_access() {
-            self.assert_write_access()?;
-        }
-
-        match tool_params {
-            FileSystemTools::ReadFileTool(params) => {
-                ReadFileTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::ReadMultipleFilesTool(params) => {
-                ReadMultipleFilesTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::WriteFileTool(params) => {
-                WriteFileTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::EditFileTool(params) => {
-                EditFileTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::CreateDirectoryTool(params) => {
-                CreateDirectoryTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::ListDirectoryTool(params) => {
-                ListDirectoryTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::DirectoryTreeTool(params) => {
-                DirectoryTreeTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::MoveFileTool(params) => {
-                MoveFileTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::SearchFilesTool(params) => {
-                SearchFilesTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::GetFileInfoTool(params) => {
-                GetFileInfoTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::ListAllowedDirectoriesTool(params) => {
-                ListAllowedDirectoriesTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::ZipFilesTool(params) => {
-                ZipFilesTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::UnzipFileTool(params) => {
-                UnzipFileTool::run_tool(params, &self.fs_service).await
-            }
-            FileSystemTools::ZipDirectoryTool(params) => {
-                ZipDirectoryTool::run_tool(params, &self.fs_service).await
+        let error = match &result {
+            Ok(call_result) if call_result.is_error.unwrap_or(false) => {
+                Some("Tool returned an error result")
             }
+            Ok(_) => None,
+            Err((_, message)) => Some(message.as_str()),
+        };
+        let mut log_messages = self.tool_call_log_messages(&tool_name, error, started.elapsed());
+        // Every call is audited, including those rejected before running. The call has already
+        // run, so a failure to record it is reported without changing its result.
+        if let Some(audit_error) = self.audit(&tool_name, &paths, error) {
+            let _ = runtime
+                .stderr_message(format!("Failed to write the audit log: {}", audit_error))
+                .await;
+            log_messages.extend(self.log_message(
+                LoggingLevel::Error,
+                "audit_log",
+                serde_json::json!({ "tool": tool_name, "error": audit_error }),
+            ));
+        }
+        for params in log_messages {
+            let _ = runtime.send_logging_message(params).await;
         }
+        Ok(result.unwrap_or_else(|(code, message)| error_result(code, message)))
+    }
+
+    async fn handle_set_level_request(
+        &self,
+        request: SetLevelRequest,
+        _: &dyn McpServer,
+    ) -> std::result::Result<rust_mcp_schema::Result, RpcError> {
+        self.set_log_level(request.params.level);
+        Ok(rust_mcp_schema::Result::default())
     }
 }
@@ -1,6 +1,8 @@
+pub mod audit_log;
 pub mod cli;
 pub mod error;
 pub mod fs_service;
 pub mod handler;
+pub mod rate_limit;
 pub mod server;
 pub mod tools;
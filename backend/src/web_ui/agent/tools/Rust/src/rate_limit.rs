@@ -0,0 +1,62 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::error::{ServiceError, ServiceResult};
+
+/// A token bucket allowing up to `ops_per_sec` operations per second on average.
+/// The bucket holds one second's worth of tokens (at least one), so short bursts are allowed.
+pub struct RateLimiter {
+    ops_per_sec: f64,
+    capacity: f64,
+    state: Mutex<BucketState>,
+}
+
+struct BucketState {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(ops_per_sec: f64) -> ServiceResult<Self> {
+        if !ops_per_sec.is_finite() || ops_per_sec <= 0.0 {
+            return Err(ServiceError::FromString(format!(
+                "Error: --rate-limit must be a positive number, got {}",
+                ops_per_sec
+            )));
+        }
+        let capacity = ops_per_sec.max(1.0);
+        Ok(Self {
+            ops_per_sec,
+            capacity,
+            state: Mutex::new(BucketState {
+                tokens: capacity,
+                updated: Instant::now(),
+            }),
+        })
+    }
+
+    /// Takes a token for one operation. When the bucket is empty, returns
+    /// `ServiceError::RateLimited` with the time until the next token is available
+    /// instead of waiting for it.
+    pub fn try_acquire(&self) -> ServiceResult<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|err| ServiceError::FromString(err.to_string()))?;
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.updated).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.ops_per_sec).min(self.capacity);
+        state.updated = now;
+
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            return Ok(());
+        }
+        let retry_after = Duration::from_secs_f64((1.0 - state.tokens) / self.ops_per_sec);
+        Err(ServiceError::RateLimited {
+            retry_after_ms: retry_after.as_millis().max(1) as u64,
+        })
+    }
+}
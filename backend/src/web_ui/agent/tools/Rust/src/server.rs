@@ -14,7 +14,7 @@ pub fn server_details() -> InitializeResult {
         },
         capabilities: ServerCapabilities {
             experimental: None,
-            logging: None,
+            logging: Some(serde_json::Map::new()),
             prompts: None,
             resources: None,
             tools: Some(ServerCapabilitiesTools { list_changed: None }),
@@ -27,12 +27,19 @@ pub fn server_details() -> InitializeResult {
 }
 
 pub async fn start_server(args: CommandArguments) -> ServiceResult<()> {
+    let args = args.with_env_allowed_directories().with_config_file()?;
     let transport = StdioTransport::new(TransportOptions::default())?;
 
     let handler = MyServerHandler::new(&args)?;
+    let audit_log = handler.audit_log();
     let server = server_runtime::create_server(server_details(), transport, handler);
 
-    server.start().await?;
+    let result = server.start().await;
+    // Write out the records still queued before exiting
+    if let Some(audit_log) = audit_log {
+        audit_log.close().await?;
+    }
+    result?;
 
     Ok(())
 }
@@ -1,29 +1,89 @@
+mod batch_move;
+mod bundle_directory;
+mod compare_files;
+mod concat_files;
+mod copy_directory;
 mod create_directory;
+mod create_temp_file;
+mod delete;
 mod directory_tree;
+mod disk_space;
 mod edit_file;
+mod edit_multiple_files;
+mod exists;
+mod find;
+mod find_empty_dirs;
 mod get_file_info;
+mod get_multiple_file_info;
+mod gzip;
+mod hex_dump;
+mod indentation_report;
+mod largest_files;
 mod list_allowed_directories;
 mod list_directory;
 mod move_file;
 mod read_files;
 mod read_multiple_files;
+mod real_path;
+mod render_template;
+mod replace_in_files;
+mod sanitize_filename;
 mod search_file;
+mod server_info;
+mod set_permissions;
+mod split_file;
+mod tail_follow;
+mod touch;
+mod truncate_file;
+mod word_count;
+mod write_binary_file;
 mod write_file;
 mod zip_unzip;
+mod zstd_compress;
 
+pub use batch_move::{BatchMoveTool, MoveOperation};
+pub use bundle_directory::BundleDirectoryTool;
+pub use compare_files::CompareFilesTool;
+pub use concat_files::ConcatFilesTool;
+pub use copy_directory::CopyDirectoryTool;
 pub use create_directory::CreateDirectoryTool;
+pub use create_temp_file::CreateTempFileTool;
+pub use delete::DeleteTool;
 pub use directory_tree::DirectoryTreeTool;
-pub use edit_file::{EditFileTool, EditOperation};
+pub use disk_space::DiskSpaceTool;
+pub use edit_file::{DeleteLinesOperation, EditFileTool, EditOperation, InsertOperation};
+pub use edit_multiple_files::{EditMultipleFilesTool, FileEdits};
+pub use exists::ExistsTool;
+pub use find::FindTool;
+pub use find_empty_dirs::FindEmptyDirsTool;
 pub use get_file_info::GetFileInfoTool;
+pub use get_multiple_file_info::GetMultipleFileInfoTool;
+pub use gzip::{GunzipTool, GzipTool};
+pub use hex_dump::HexDumpTool;
+pub use indentation_report::IndentationReportTool;
+pub use largest_files::LargestFilesTool;
 pub use list_allowed_directories::ListAllowedDirectoriesTool;
 pub use list_directory::ListDirectoryTool;
 pub use move_file::MoveFileTool;
 pub use read_files::ReadFileTool;
 pub use read_multiple_files::ReadMultipleFilesTool;
+pub use real_path::RealPathTool;
+pub use render_template::{RenderTemplateTool, TemplateVariable};
+pub use replace_in_files::ReplaceInFilesTool;
 pub use rust_mcp_sdk::tool_box;
-pub use search_file::SearchFilesTool;
+pub use sanitize_filename::SanitizeFilenameTool;
+pub use search_file::{SearchFilesTool, ALL_ROOTS};
+pub use server_info::ServerInfoTool;
+pub use set_permissions::SetPermissionsTool;
+pub use split_file::SplitFileTool;
+pub use tail_follow::{LineCallback, TailFollowTool};
+pub use touch::TouchTool;
+pub use truncate_file::TruncateFileTool;
+pub use word_count::WordCountTool;
+pub use write_binary_file::WriteBinaryFileTool;
 pub use write_file::WriteFileTool;
 pub use zip_unzip::{UnzipFileTool, ZipDirectoryTool, ZipFilesTool};
+pub use zstd_compress::{ZstdCompressTool, ZstdDecompressTool};
 
 //Generate FileSystemTools enum , tools() function, and TryFrom<CallToolRequestParams> trait implementation
 tool_box!(
@@ -42,7 +102,39 @@ tool_box!(
         WriteFileTool,
         ZipFilesTool,
         UnzipFileTool,
-        ZipDirectoryTool
+        ZipDirectoryTool,
+        RenderTemplateTool,
+        IndentationReportTool,
+        SanitizeFilenameTool,
+        WriteBinaryFileTool,
+        SetPermissionsTool,
+        TouchTool,
+        FindTool,
+        CompareFilesTool,
+        WordCountTool,
+        TruncateFileTool,
+        DeleteTool,
+        GzipTool,
+        GunzipTool,
+        ZstdCompressTool,
+        ZstdDecompressTool,
+        GetMultipleFileInfoTool,
+        TailFollowTool,
+        BatchMoveTool,
+        ReplaceInFilesTool,
+        FindEmptyDirsTool,
+        LargestFilesTool,
+        ServerInfoTool,
+        DiskSpaceTool,
+        ExistsTool,
+        RealPathTool,
+        CreateTempFileTool,
+        ConcatFilesTool,
+        SplitFileTool,
+        EditMultipleFilesTool,
+        BundleDirectoryTool,
+        CopyDirectoryTool,
+        HexDumpTool
     ]
 );
 
@@ -55,9 +147,24 @@ impl FileSystemTools {
             | FileSystemTools::MoveFileTool(_)
             | FileSystemTools::WriteFileTool(_)
             | FileSystemTools::EditFileTool(_)
-            | FileSystemTools::ZipFilesTool(_)
             | FileSystemTools::UnzipFileTool(_)
-            | FileSystemTools::ZipDirectoryTool(_) => true,
+            | FileSystemTools::RenderTemplateTool(_)
+            | FileSystemTools::WriteBinaryFileTool(_)
+            | FileSystemTools::SetPermissionsTool(_)
+            | FileSystemTools::TouchTool(_)
+            | FileSystemTools::TruncateFileTool(_)
+            | FileSystemTools::DeleteTool(_)
+            | FileSystemTools::GzipTool(_)
+            | FileSystemTools::GunzipTool(_)
+            | FileSystemTools::ZstdCompressTool(_)
+            | FileSystemTools::ZstdDecompressTool(_)
+            | FileSystemTools::BatchMoveTool(_)
+            | FileSystemTools::ReplaceInFilesTool(_)
+            | FileSystemTools::CreateTempFileTool(_)
+            | FileSystemTools::ConcatFilesTool(_)
+            | FileSystemTools::SplitFileTool(_)
+            | FileSystemTools::EditMultipleFilesTool(_)
+            | FileSystemTools::CopyDirectoryTool(_) => true,
 
             FileSystemTools::ReadFileTool(_)
             | FileSystemTools::DirectoryTreeTool(_)
@@ -65,7 +172,122 @@ impl FileSystemTools {
             | FileSystemTools::ListAllowedDirectoriesTool(_)
             | FileSystemTools::ListDirectoryTool(_)
             | FileSystemTools::ReadMultipleFilesTool(_)
-            | FileSystemTools::SearchFilesTool(_) => false,
+            | FileSystemTools::SearchFilesTool(_)
+            | FileSystemTools::IndentationReportTool(_)
+            | FileSystemTools::SanitizeFilenameTool(_)
+            | FileSystemTools::FindTool(_)
+            | FileSystemTools::CompareFilesTool(_)
+            | FileSystemTools::WordCountTool(_)
+            | FileSystemTools::GetMultipleFileInfoTool(_)
+            | FileSystemTools::TailFollowTool(_)
+            | FileSystemTools::LargestFilesTool(_)
+            | FileSystemTools::ServerInfoTool(_)
+            | FileSystemTools::DiskSpaceTool(_)
+            | FileSystemTools::ExistsTool(_)
+            | FileSystemTools::RealPathTool(_)
+            | FileSystemTools::BundleDirectoryTool(_)
+            | FileSystemTools::HexDumpTool(_) => false,
+
+            // Only pruning modifies the file system
+            FileSystemTools::FindEmptyDirsTool(params) => params.prune.unwrap_or(false),
+            // In-memory archives are returned without writing anything
+            FileSystemTools::ZipFilesTool(params) => params.target_zip_file.is_some(),
+            FileSystemTools::ZipDirectoryTool(params) => params.target_zip_file.is_some(),
+        }
+    }
+}
+
+impl FileSystemTools {
+    // Returns the raw path arguments of the tool call, used to record the affected paths in the audit log.
+    pub fn paths(&self) -> Vec<&str> {
+        match self {
+            FileSystemTools::ReadFileTool(params) => vec![&params.path],
+            FileSystemTools::CreateDirectoryTool(params) => vec![&params.path],
+            FileSystemTools::DirectoryTreeTool(params) => vec![&params.path],
+            FileSystemTools::EditFileTool(params) => [params.path.as_str()]
+                .into_iter()
+                .chain(params.save_to.as_deref())
+                .collect(),
+            FileSystemTools::GetFileInfoTool(params) => vec![&params.path],
+            FileSystemTools::ListAllowedDirectoriesTool(_) => vec![],
+            FileSystemTools::ListDirectoryTool(params) => vec![&params.path],
+            FileSystemTools::MoveFileTool(params) => vec![&params.source, &params.destination],
+            FileSystemTools::ReadMultipleFilesTool(params) => {
+                params.paths.iter().map(String::as_str).collect()
+            }
+            FileSystemTools::SearchFilesTool(params) => params
+                .path
+                .iter()
+                .filter(|path| path.as_str() != ALL_ROOTS)
+                .map(String::as_str)
+                .collect(),
+            FileSystemTools::WriteFileTool(params) => vec![&params.path],
+            FileSystemTools::ZipFilesTool(params) => params
+                .input_files
+                .iter()
+                .map(String::as_str)
+                .chain(params.target_zip_file.as_deref())
+                .collect(),
+            FileSystemTools::UnzipFileTool(params) => vec![&params.zip_file, &params.target_path],
+            FileSystemTools::ZipDirectoryTool(params) => [params.input_directory.as_str()]
+                .into_iter()
+                .chain(params.target_zip_file.as_deref())
+                .collect(),
+            FileSystemTools::RenderTemplateTool(params) => {
+                vec![&params.template_path, &params.output_path]
+            }
+            FileSystemTools::IndentationReportTool(params) => vec![&params.path],
+            FileSystemTools::SanitizeFilenameTool(params) => {
+                params.directory.as_deref().into_iter().collect()
+            }
+            FileSystemTools::WriteBinaryFileTool(params) => vec![&params.path],
+            FileSystemTools::SetPermissionsTool(params) => vec![&params.path],
+            FileSystemTools::TouchTool(params) => vec![&params.path],
+            FileSystemTools::FindTool(params) => vec![&params.path],
+            FileSystemTools::CompareFilesTool(params) => vec![&params.left, &params.right],
+            FileSystemTools::WordCountTool(params) => vec![&params.path],
+            FileSystemTools::TruncateFileTool(params) => vec![&params.path],
+            FileSystemTools::DeleteTool(params) => vec![&params.path],
+            FileSystemTools::GzipTool(params) => vec![&params.input_file, &params.target_file],
+            FileSystemTools::GunzipTool(params) => vec![&params.input_file, &params.target_file],
+            FileSystemTools::ZstdCompressTool(params) => {
+                vec![&params.input_file, &params.target_file]
+            }
+            FileSystemTools::ZstdDecompressTool(params) => {
+                vec![&params.input_file, &params.target_file]
+            }
+            FileSystemTools::GetMultipleFileInfoTool(params) => {
+                params.paths.iter().map(String::as_str).collect()
+            }
+            FileSystemTools::TailFollowTool(params) => vec![&params.path],
+            FileSystemTools::BatchMoveTool(params) => params
+                .moves
+                .iter()
+                .flat_map(|operation| [operation.source.as_str(), operation.destination.as_str()])
+                .collect(),
+            FileSystemTools::ReplaceInFilesTool(params) => vec![&params.path],
+            FileSystemTools::FindEmptyDirsTool(params) => vec![&params.path],
+            FileSystemTools::LargestFilesTool(params) => vec![&params.path],
+            FileSystemTools::ServerInfoTool(_) => vec![],
+            FileSystemTools::DiskSpaceTool(params) => vec![&params.path],
+            FileSystemTools::ExistsTool(params) => vec![&params.path],
+            FileSystemTools::RealPathTool(params) => vec![&params.path],
+            FileSystemTools::CreateTempFileTool(params) => {
+                params.dir.as_deref().into_iter().collect()
+            }
+            FileSystemTools::ConcatFilesTool(params) => params
+                .input_files
+                .iter()
+                .map(String::as_str)
+                .chain([params.target_file.as_str()])
+                .collect(),
+            FileSystemTools::SplitFileTool(params) => vec![&params.path, &params.output_dir],
+            FileSystemTools::EditMultipleFilesTool(params) => {
+                params.files.iter().map(|file| file.path.as_str()).collect()
+            }
+            FileSystemTools::BundleDirectoryTool(params) => vec![&params.path],
+            FileSystemTools::CopyDirectoryTool(params) => vec![&params.source, &params.destination],
+            FileSystemTools::HexDumpTool(params) => vec![&params.path],
         }
     }
 }
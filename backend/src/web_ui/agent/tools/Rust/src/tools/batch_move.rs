@@ -0,0 +1,75 @@
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{FileSystemService, MoveStatus};
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+/// A single move of a `batch_move` call.
+pub struct MoveOperation {
+    /// The **absolute source path** of the file or directory to move.
+    pub source: String,
+    /// The **absolute destination path**. It must not already exist.
+    pub destination: String,
+}
+
+#[mcp_tool(
+    name = "batch_move",
+    description = concat!("Moves or renames multiple files or directories in one call. ",
+    "All paths are validated before anything is moved. Moves are performed in order; if one fails, ",
+    "the moves completed before it are undone (best effort) and the remaining ones are skipped. ",
+    "Destinations must not already exist. Returns the outcome of each move. ",
+    "IMPORTANT: All source and destination paths MUST be absolute paths. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct BatchMoveTool {
+    /// The moves to perform, in order.
+    pub moves: Vec<MoveOperation>,
+}
+
+impl BatchMoveTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let moves: Vec<(String, String)> = params
+            .moves
+            .into_iter()
+            .map(|operation| (operation.source, operation.destination))
+            .collect();
+        let outcomes = context
+            .batch_move(&moves)
+            .await
+            .map_err(CallToolError::new)?;
+
+        let moved = outcomes
+            .iter()
+            .filter(|outcome| outcome.status == MoveStatus::Moved)
+            .count();
+        let summary = if moved == outcomes.len() {
+            format!("Successfully moved {} of {} items.", moved, outcomes.len())
+        } else {
+            "Batch move failed; moves completed before the failure were undone.".to_string()
+        };
+        let details = outcomes
+            .iter()
+            .map(|outcome| {
+                format!(
+                    "{} -> {}: {}",
+                    outcome.source.display(),
+                    outcome.destination.display(),
+                    outcome.status
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(CallToolResult::text_content(
+            format!("{}\n{}", summary, details),
+            None,
+        ))
+    }
+}
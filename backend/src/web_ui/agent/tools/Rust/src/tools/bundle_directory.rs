@@ -0,0 +1,73 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{BundleDirectoryOptions, FileSystemService};
+
+#[mcp_tool(
+    name = "bundle_directory",
+    description = concat!("Reads every text file under a directory and returns them concatenated into a single output, ",
+    "each file preceded by a `== relative/path ==` header. Useful to read a whole small project in one call. ",
+    "Files are selected with `includePatterns` and `excludePatterns` globs matched against paths relative to `path` (e.g., `**/*.rs`, `target/**`); binary files are skipped. ",
+    "The output is capped at `maxSize` bytes (1 MiB by default); files that don't fit are left out and listed at the end. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct BundleDirectoryTool {
+    /// The **absolute path** of the directory to bundle.
+    pub path: String,
+    /// Glob patterns selecting the files to include, relative to `path` (e.g., `["**/*.rs"]`). Defaults to all files.
+    #[serde(
+        rename = "includePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub include_patterns: Option<Vec<String>>,
+    /// Glob patterns of files and directories to skip, relative to `path` (e.g., `["target/**"]`).
+    #[serde(
+        rename = "excludePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Maximum size of the output in bytes. Defaults to 1 MiB.
+    #[serde(
+        rename = "maxSize",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub max_size: Option<u64>,
+}
+
+impl BundleDirectoryTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let options = BundleDirectoryOptions {
+            include_patterns: params.include_patterns.unwrap_or_default(),
+            exclude_patterns: params.exclude_patterns.unwrap_or_default(),
+            max_size: params.max_size,
+        };
+        let (mut bundle, omitted) = context
+            .bundle_directory(Path::new(&params.path), &options)
+            .await
+            .map_err(CallToolError::new)?;
+
+        if !omitted.is_empty() {
+            bundle.push_str(&format!(
+                "\nOmitted {} {} exceeding the size limit: {}",
+                omitted.len(),
+                if omitted.len() == 1 { "file" } else { "files" },
+                omitted.join(", ")
+            ));
+        }
+        Ok(CallToolResult::text_content(bundle, None))
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "compare_files",
+    description = concat!("Compares two text files and returns a git-style unified diff labeled with both paths. ",
+    "Neither file is modified. Use 'context_lines' to control how many unchanged lines surround each change (defaults to 4). ",
+    "Fails with a clear error if either file does not exist. If either file is binary, only reports whether the files are identical. ",
+    "IMPORTANT: Both paths MUST be absolute paths. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct CompareFilesTool {
+    /// The **absolute path** of the original (left-hand) file.
+    pub left: String,
+    /// The **absolute path** of the modified (right-hand) file.
+    pub right: String,
+    /// Number of unchanged context lines shown around each change (defaults to 4).
+    #[serde(
+        rename = "contextLines",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub context_lines: Option<u64>,
+}
+
+impl CompareFilesTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let diff = context
+            .compare_files(
+                Path::new(&params.left),
+                Path::new(&params.right),
+                params.context_lines.map(|lines| lines as usize),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(diff, None))
+    }
+}
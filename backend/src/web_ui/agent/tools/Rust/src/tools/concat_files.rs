@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{utils::format_bytes, FileSystemService};
+
+#[mcp_tool(
+    name = "concat_files",
+    description = concat!("Concatenates a list of files, in the given order, into a single target file. ",
+    "An optional `separator` (e.g., a newline or a header line) is written between consecutive files. ",
+    "Files are streamed, so large logs or shards can be assembled without loading them into memory. ",
+    "Fails if `target_file` already exists unless `overwrite` is true. ",
+    "IMPORTANT: All file paths in `input_files` and the `target_file` path MUST be absolute paths. Relative paths are not supported. ",
+    "Both source files and the target file must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ConcatFilesTool {
+    /// An ordered list of **absolute paths** to the files to concatenate.
+    pub input_files: Vec<String>,
+    /// The **absolute path** of the file to write the combined content to.
+    pub target_file: String,
+    /// Text written between consecutive files, e.g., `\n`. Nothing is written by default.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub separator: Option<String>,
+    /// Replace `target_file` if it already exists. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub overwrite: Option<bool>,
+}
+
+impl ConcatFilesTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let written = context
+            .concat_files(
+                &params.input_files,
+                Path::new(&params.target_file),
+                params.separator.as_deref(),
+                params.overwrite.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(
+            format!(
+                "Successfully concatenated {} files into {} ({})",
+                params.input_files.len(),
+                params.target_file,
+                format_bytes(written)
+            ),
+            None,
+        ))
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{CopyDirectoryOptions, FileSystemService};
+
+#[mcp_tool(
+    name = "copy_directory",
+    description = concat!("Recursively copies the files of a directory into a destination directory, preserving their relative paths. ",
+    "Files are selected with `includePatterns` and `excludePatterns` globs matched against paths relative to `source` (e.g., `**/*.rs`, `target/**`). ",
+    "Files that already exist at the destination are skipped unless `overwrite` is true. Set `preserve_metadata` to keep permissions and timestamps. ",
+    "Returns the number of files copied and the files skipped. ",
+    "IMPORTANT: Both paths MUST be absolute paths, and the destination must not be inside the source. ",
+    "Both directories must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct CopyDirectoryTool {
+    /// The **absolute path** of the directory to copy from.
+    pub source: String,
+    /// The **absolute path** of the directory to copy into. It is created if it doesn't exist.
+    pub destination: String,
+    /// Glob patterns selecting the files to copy, relative to `source` (e.g., `["**/*.rs"]`). Defaults to all files.
+    #[serde(
+        rename = "includePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub include_patterns: Option<Vec<String>>,
+    /// Glob patterns of files and directories to skip, relative to `source` (e.g., `["target/**"]`).
+    #[serde(
+        rename = "excludePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Replace files that already exist at the destination instead of skipping them. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub overwrite: Option<bool>,
+    /// Keep the permissions and access/modification times of the copied files. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub preserve_metadata: Option<bool>,
+}
+
+impl CopyDirectoryTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let options = CopyDirectoryOptions {
+            include_patterns: params.include_patterns.unwrap_or_default(),
+            exclude_patterns: params.exclude_patterns.unwrap_or_default(),
+            overwrite: params.overwrite.unwrap_or(false),
+            preserve_metadata: params.preserve_metadata.unwrap_or(false),
+        };
+        let (copied, skipped) = context
+            .copy_directory(
+                Path::new(&params.source),
+                Path::new(&params.destination),
+                &options,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        let mut message = format!(
+            "Copied {} {} from {} to {}",
+            copied.len(),
+            if copied.len() == 1 { "file" } else { "files" },
+            &params.source,
+            &params.destination
+        );
+        if !skipped.is_empty() {
+            message.push_str(&format!(
+                "\nSkipped {} existing {}: {}",
+                skipped.len(),
+                if skipped.len() == 1 { "file" } else { "files" },
+                skipped.join(", ")
+            ));
+        }
+        Ok(CallToolResult::text_content(message, None))
+    }
+}
@@ -9,6 +9,7 @@ use crate::fs_service::FileSystemService;
     name = "create_directory",
     description = concat!("Creates a new directory, including any necessary parent directories if they do not exist. ",
     "If the directory already exists, the operation completes successfully without error. ",
+    "Set `dryRun` to list the directories that would be created without creating them. ",
     "This tool is ideal for preparing directory structures for new projects or ensuring output paths are available. ",
     "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\projects\\new_folder or /mnt/data/new_folder). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
@@ -21,6 +22,13 @@ use crate::fs_service::FileSystemService;
 pub struct CreateDirectoryTool {
     /// The **absolute path** where the directory will be created (e.g., `D:\\projects\\new_folder` or `/mnt/data/new_folder`).
     pub path: String,
+    /// Report the missing directories that would be created, without creating them. Defaults to false.
+    #[serde(
+        rename = "dryRun",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub dry_run: Option<bool>,
 }
 
 impl CreateDirectoryTool {
@@ -28,6 +36,32 @@ impl CreateDirectoryTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
+        if params.dry_run.unwrap_or(false) {
+            let missing = context
+                .missing_directories(Path::new(&params.path))
+                .await
+                .map_err(CallToolError::new)?;
+            let message = if missing.is_empty() {
+                format!("Dry run: {} already exists", &params.path)
+            } else {
+                format!(
+                    "Dry run: would create {} {}:\n{}",
+                    missing.len(),
+                    if missing.len() == 1 {
+                        "directory"
+                    } else {
+                        "directories"
+                    },
+                    missing
+                        .iter()
+                        .map(|dir| dir.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                )
+            };
+            return Ok(CallToolResult::text_content(message, None));
+        }
+
         context
             .create_directory(Path::new(&params.path))
             .await
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "create_temp_file",
+    description = concat!("Creates a new, empty file with a unique name and returns its absolute path. ",
+    "The file is created in `dir`, or in the first writable allowed directory if `dir` is omitted. ",
+    "An optional `prefix` and `suffix` (e.g., '.json') are added around the random part of the name. ",
+    "Useful as scratch space for intermediate results. The file is not removed automatically. ",
+    "IMPORTANT: The directory provided MUST be an absolute path (e.g., D:\\scratch or /tmp/scratch). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct CreateTempFileTool {
+    /// The **absolute path** of the directory to create the file in (e.g., `D:\\scratch` or `/tmp/scratch`). Defaults to the first writable allowed directory.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub dir: Option<String>,
+    /// Text to start the file name with. Defaults to `tmp`.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub prefix: Option<String>,
+    /// Text to end the file name with, such as an extension (e.g., `.json`).
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub suffix: Option<String>,
+}
+
+impl CreateTempFileTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let path = context
+            .create_temp_file(
+                params.dir.as_deref().map(Path::new),
+                params.prefix.as_deref(),
+                params.suffix.as_deref(),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(
+            path.display().to_string(),
+            None,
+        ))
+    }
+}
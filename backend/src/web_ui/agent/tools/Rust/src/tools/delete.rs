@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "delete",
+    description = concat!("Deletes a file or directory. Non-empty directories are only deleted when 'recursive' is true. ",
+    "If the server was started with a trash directory, the item is moved there instead of being removed permanently (renamed with a ` (n)` suffix on collision), giving a recovery window. ",
+    "Set 'trash' to true or false to override this behavior for a single call. Allowed directories themselves cannot be deleted. ",
+    "IMPORTANT: The path provided MUST be an absolute path. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = true,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct DeleteTool {
+    /// The **absolute path** of the file or directory to delete.
+    pub path: String,
+    /// Delete non-empty directories along with their contents. Defaults to false.
+    pub recursive: Option<bool>,
+    /// Move the item to the trash directory instead of deleting it permanently. Defaults to true when the server has a trash directory configured.
+    pub trash: Option<bool>,
+}
+
+impl DeleteTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let trash_path = context
+            .delete(
+                Path::new(&params.path),
+                params.recursive.unwrap_or(false),
+                params.trash,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        let message = match trash_path {
+            Some(trash_path) => format!(
+                "Successfully moved {} to trash at {}",
+                &params.path,
+                trash_path.display()
+            ),
+            None => format!("Successfully deleted {}", &params.path),
+        };
+        Ok(CallToolResult::text_content(message, None))
+    }
+}
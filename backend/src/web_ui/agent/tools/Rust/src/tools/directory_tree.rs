@@ -4,7 +4,7 @@ use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 use serde_json::json;
 
-use crate::fs_service::FileSystemService;
+use crate::fs_service::{FileSystemService, ListDirectoryOptions};
 
 #[mcp_tool(
     name = "directory_tree",
@@ -14,6 +14,7 @@ use crate::fs_service::FileSystemService;
 "🚀 PERFORMANCE: Very fast for large directories since it only reads directory structure, not file contents. ",
 "❌ LIMITATIONS: No token counting, no complexity analysis, no file content examination. ",
 "✅ IDEAL FOR: Quick structure overview, performance-critical tasks, basic directory mapping. ",
+"Set `respect_gitignore` to leave out entries excluded by `.gitignore` files, and `include_hidden` to false to leave out hidden (dot-prefixed) entries. ",
 "IMPORTANT: Requires absolute paths only (e.g., D:\\data\\folder). Restricted to pre-configured directories."),
     destructive_hint = false,
     idempotent_hint = false,
@@ -24,6 +25,12 @@ use crate::fs_service::FileSystemService;
 pub struct DirectoryTreeTool {
     /// The **absolute root path** for which to generate the directory tree (e.g., `D:\\data\\folder` or `/srv/project_files`).
     pub path: String,
+    /// If true, leaves out entries excluded by `.gitignore` (and `.ignore`) files. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub respect_gitignore: Option<bool>,
+    /// If false, leaves out hidden entries: names starting with `.` and, on Windows, entries with the hidden attribute. Defaults to true.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub include_hidden: Option<bool>,
 }
 impl DirectoryTreeTool {
     pub async fn run_tool(
@@ -31,9 +38,17 @@ impl DirectoryTreeTool {
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
         let entries = context
-            .list_directory(Path::new(&params.path))
+            .list_directory(
+                Path::new(&params.path),
+                &ListDirectoryOptions {
+                    respect_gitignore: params.respect_gitignore.unwrap_or(false),
+                    skip_hidden: !params.include_hidden.unwrap_or(true),
+                    ..Default::default()
+                },
+            )
             .await
-            .map_err(CallToolError::new)?;
+            .map_err(CallToolError::new)?
+            .entries;
 
         let json_tree: Vec<serde_json::Value> = entries
             .iter()
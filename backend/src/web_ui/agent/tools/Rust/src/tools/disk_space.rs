@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{utils::format_bytes, FileSystemService};
+
+#[mcp_tool(
+    name = "disk_space",
+    description = concat!("Reports the total, used and available space of the filesystem holding a path, ",
+    "e.g. to check there is enough room before writing large files. ",
+    "Available space is what the server can actually use and may be less than total minus used. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct DiskSpaceTool {
+    /// The **absolute path** of a file or directory on the filesystem to check.
+    pub path: String,
+}
+
+impl DiskSpaceTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let space = context
+            .disk_space(Path::new(&params.path))
+            .await
+            .map_err(CallToolError::new)?;
+        let result = format!(
+            "total: {} ({} bytes)\nused: {} ({} bytes)\navailable: {} ({} bytes)",
+            format_bytes(space.total),
+            space.total,
+            format_bytes(space.used),
+            space.used,
+            format_bytes(space.available),
+            space.available
+        );
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
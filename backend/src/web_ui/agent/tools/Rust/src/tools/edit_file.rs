@@ -3,9 +3,12 @@ use std::path::Path;
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::{
+    error::ServiceError,
+    fs_service::{EditOptions, FileEdit, FileSystemService},
+};
 
-#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, Default, JsonSchema)]
 /// Represents a text replacement operation.
 pub struct EditOperation {
     /// Text to search for. For multi-line text, ensure line endings match the target file's predominant style (e.g., LF or CRLF) or normalize before sending. The match must be exact.
@@ -14,12 +17,45 @@ pub struct EditOperation {
     #[serde(rename = "newText")]
     /// Text to replace the matched `oldText` with. Line endings should be consistent.
     pub new_text: String,
+    /// If true, `oldText` is treated as a regular expression and every match is replaced. `newText` may reference capture groups (e.g., `$1` or `${name}`).
+    #[serde(
+        rename = "isRegex",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub is_regex: Option<bool>,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, Default, JsonSchema)]
+/// Inserts text before a line without matching existing content.
+#[serde(rename_all = "camelCase")]
+pub struct InsertOperation {
+    /// The 1-based line to insert before. Use the number of lines plus one to append after the last line.
+    pub line: u64,
+    /// Text to insert. Multiple lines are inserted as-is, including their indentation; line endings are converted to the file's.
+    pub text: String,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, Default, JsonSchema)]
+/// Removes an inclusive range of lines.
+#[serde(rename_all = "camelCase")]
+pub struct DeleteLinesOperation {
+    /// The first 1-based line to remove.
+    pub start: u64,
+    /// The last 1-based line to remove (inclusive). Must not be less than `start` or past the end of the file.
+    pub end: u64,
 }
 
 #[mcp_tool(
     name = "edit_file",
     description = concat!("Performs line-based edits on a text file by replacing exact sequences of text. ",
-    "Multiple edits can be specified. Returns a git-style diff of the changes. ",
+    "Multiple edits can be specified. Edits marked with `isRegex` match a regular expression and support capture references in the replacement. Returns a git-style diff of the changes. ",
+    "Text can also be inserted before a given line with `inserts`, e.g., to add imports or headers without matching existing content, ",
+    "and inclusive line ranges can be removed with `deleteLines`. ",
+    "Inserts and line deletions refer to line numbers of the original file, must not overlap, and are applied before the text edits. ",
+    "With `fuzzyThreshold`, text edits that match no lines exactly are applied to the most similar block of lines if it is similar enough. ",
+    "Set `saveTo` to write the edited content to another file, leaving the original unchanged. ",
+    "Set `strictSpan` to reject, without writing anything, edits that change lines outside the text they matched. ",
     "Useful for precise modifications to existing files. ",
     "IMPORTANT: The file path provided MUST be an absolute path (e.g., D:\\config\\settings.txt or /etc/app/config.yml). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
@@ -34,7 +70,22 @@ pub struct EditFileTool {
     pub path: String,
 
     /// A list of `EditOperation` objects detailing the changes to apply. Edits are applied sequentially.
+    #[serde(default)]
     pub edits: Vec<EditOperation>,
+    /// A list of `InsertOperation` objects inserting text before 1-based lines of the original file.
+    #[serde(
+        rename = "inserts",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub inserts: Option<Vec<InsertOperation>>,
+    /// A list of `DeleteLinesOperation` objects removing inclusive 1-based line ranges of the original file.
+    #[serde(
+        rename = "deleteLines",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub delete_lines: Option<Vec<DeleteLinesOperation>>,
     /// If true, previews changes as a git-style diff without writing to the file. If false or omitted, changes are applied directly.
     #[serde(
         rename = "dryRun",
@@ -42,6 +93,44 @@ pub struct EditFileTool {
         skip_serializing_if = "std::option::Option::is_none"
     )]
     pub dry_run: Option<bool>,
+    /// The **absolute path** to write the edited content to instead of `path`, which is then left unchanged.
+    #[serde(
+        rename = "saveTo",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub save_to: Option<String>,
+    /// If true, edits that have no match are skipped instead of failing the whole operation, and a summary of applied and unmatched edits is appended to the diff. Defaults to false.
+    #[serde(
+        rename = "skipUnmatched",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub skip_unmatched: Option<bool>,
+    /// If true, saves the previous content of the file to `<path>.bak` before applying the edits. Defaults to the server's `--backup` setting.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub backup: Option<bool>,
+    /// Number of unchanged context lines to show around each change in the returned diff. Defaults to 4.
+    #[serde(
+        rename = "contextLines",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub context_lines: Option<u64>,
+    /// Minimum similarity (0.0-1.0) for applying an edit whose `oldText` matches neither exactly nor up to whitespace to the most similar block of lines instead of failing, e.g., 0.9. Fuzzy matches are reported with their line number. Disabled by default.
+    #[serde(
+        rename = "fuzzyThreshold",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub fuzzy_threshold: Option<f32>,
+    /// If true, fails without writing anything when an edit changes lines outside the region it matched, such as the line ending of the last line. Defaults to false.
+    #[serde(
+        rename = "strictSpan",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub strict_span: Option<bool>,
 }
 
 impl EditFileTool {
@@ -49,8 +138,55 @@ impl EditFileTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
+        let inserts = params.inserts.unwrap_or_default();
+        let mut deletes = params.delete_lines.unwrap_or_default();
+        deletes.sort_by_key(|delete| delete.start);
+        let overlapping = deletes.windows(2).any(|pair| pair[1].start <= pair[0].end)
+            || inserts.iter().any(|insert| {
+                deletes
+                    .iter()
+                    .any(|delete| delete.start < insert.line && insert.line <= delete.end)
+            });
+        if overlapping {
+            return Err(CallToolError::new(ServiceError::FromString(
+                "Line deletions must not overlap each other or contain an insert position"
+                    .to_string(),
+            )));
+        }
+
+        // Line edits are applied bottom-up so each line number refers to the original file.
+        // At the same line, deletions go first and inserts keep their given order.
+        let mut line_edits: Vec<(u64, usize, FileEdit)> = deletes
+            .into_iter()
+            .map(|delete| (delete.start, usize::MAX, FileEdit::from(delete)))
+            .chain(
+                inserts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, insert)| (insert.line, index, FileEdit::from(insert))),
+            )
+            .collect();
+        line_edits.sort_by_key(|(line, order, _)| std::cmp::Reverse((*line, *order)));
+        let edits: Vec<FileEdit> = line_edits
+            .into_iter()
+            .map(|(_, _, edit)| edit)
+            .chain(params.edits.into_iter().map(FileEdit::from))
+            .collect();
+
         let diff = context
-            .apply_file_edits(Path::new(&params.path), params.edits, params.dry_run, None)
+            .apply_edits(
+                Path::new(&params.path),
+                edits,
+                params.dry_run,
+                params.save_to.as_deref().map(Path::new),
+                &EditOptions {
+                    skip_unmatched: params.skip_unmatched.unwrap_or(false),
+                    backup: params.backup,
+                    context_lines: params.context_lines.map(|lines| lines as usize),
+                    fuzzy_threshold: params.fuzzy_threshold,
+                    strict_span: params.strict_span.unwrap_or(false),
+                },
+            )
             .await
             .map_err(CallToolError::new)?;
 
@@ -0,0 +1,59 @@
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{EditOptions, FileSystemService};
+
+use super::EditOperation;
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+/// The edits to apply to one file.
+pub struct FileEdits {
+    /// The **absolute path** of the file to edit.
+    pub path: String,
+    /// A list of `EditOperation` objects to apply to the file, in order.
+    pub edits: Vec<EditOperation>,
+}
+
+#[mcp_tool(
+    name = "edit_multiple_files",
+    description = concat!("Applies text edits to several files as a single all-or-nothing operation, e.g., for a refactor that renames a symbol across files. ",
+    "Each file's edits work like those of `edit_file`. If any edit fails to match, files already changed are restored to their original content and nothing is modified. ",
+    "Returns the combined git-style diff of all files. ",
+    "IMPORTANT: All file paths MUST be absolute paths. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct EditMultipleFilesTool {
+    /// The files to edit, each with its list of edits. A file may appear only once.
+    pub files: Vec<FileEdits>,
+    /// If true, previews the changes as a git-style diff without writing any file. Defaults to false.
+    #[serde(
+        rename = "dryRun",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+impl EditMultipleFilesTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let files = params
+            .files
+            .into_iter()
+            .map(|file| (file.path, file.edits))
+            .collect();
+        let diff = context
+            .apply_multi_file_edits(files, params.dry_run, &EditOptions::default())
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(diff, None))
+    }
+}
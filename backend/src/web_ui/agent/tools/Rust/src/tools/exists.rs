@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "exists",
+    description = concat!("Checks whether a file or directory exists without failing when it doesn't. ",
+    "Returns a JSON object with `exists`, `is_file`, `is_dir` and `is_symlink` fields. ",
+    "Use `get_file_info` for full metadata. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ExistsTool {
+    /// The **absolute path** to check.
+    pub path: String,
+}
+
+impl ExistsTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let status = context
+            .path_status(Path::new(&params.path))
+            .await
+            .map_err(CallToolError::new)?;
+        let json = serde_json::to_string(&status).map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(json, None))
+    }
+}
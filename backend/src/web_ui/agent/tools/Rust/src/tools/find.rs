@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "find",
+    description = concat!("Recursively finds files and directories under a starting directory using metadata filters: ",
+    "size range (`min_size`/`max_size` in bytes, files only), modification time range (`modified_after`/`modified_before` as RFC 3339 timestamps) and entry `type` (`file` or `dir`). ",
+    "All provided filters must match. Returns a list of full absolute paths. ",
+    "Useful for queries such as \"files larger than 100MB modified this week\". ",
+    "IMPORTANT: The starting path provided MUST be an absolute path (e.g., D:\\projects or /var/log). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct FindTool {
+    /// The **absolute directory path** from which to start the search (e.g., `D:\\projects` or `/var/log`).
+    pub path: String,
+    /// Only include files at least this many bytes in size.
+    pub min_size: Option<u64>,
+    /// Only include files at most this many bytes in size.
+    pub max_size: Option<u64>,
+    /// Only include entries modified at or after this RFC 3339 timestamp (e.g., `2025-05-01T00:00:00Z`).
+    pub modified_after: Option<String>,
+    /// Only include entries modified at or before this RFC 3339 timestamp.
+    pub modified_before: Option<String>,
+    /// Restrict results to `file` or `dir` entries.
+    #[serde(rename = "type")]
+    pub entry_type: Option<String>,
+}
+
+impl FindTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let list = context
+            .find(
+                Path::new(&params.path),
+                params.min_size,
+                params.max_size,
+                params.modified_after.as_deref(),
+                params.modified_before.as_deref(),
+                params.entry_type.as_deref(),
+            )
+            .map_err(CallToolError::new)?;
+
+        let result = if !list.is_empty() {
+            list.iter()
+                .map(|entry| entry.path().display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n")
+        } else {
+            "No matches found".to_string()
+        };
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
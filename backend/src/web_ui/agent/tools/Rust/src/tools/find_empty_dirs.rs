@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "find_empty_dirs",
+    description = concat!("Recursively finds directories under a starting directory that contain no files, directly or in any subdirectory. ",
+    "Returns their full absolute paths, nested directories first. Set `prune` to true to also delete them, bottom-up; this requires write access. ",
+    "The starting directory itself is never included or deleted. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = true,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct FindEmptyDirsTool {
+    /// The **absolute path** of the directory to search.
+    pub path: String,
+    /// If true, deletes the empty directories that were found. Defaults to false.
+    pub prune: Option<bool>,
+}
+
+impl FindEmptyDirsTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let prune = params.prune.unwrap_or(false);
+        let empty_dirs = context
+            .find_empty_dirs(Path::new(&params.path), prune)
+            .map_err(CallToolError::new)?;
+
+        let result = if empty_dirs.is_empty() {
+            "No empty directories found".to_string()
+        } else {
+            let list = empty_dirs
+                .iter()
+                .map(|dir| dir.display().to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if prune {
+                format!(
+                    "Removed {} empty {}:\n{}",
+                    empty_dirs.len(),
+                    if empty_dirs.len() == 1 {
+                        "directory"
+                    } else {
+                        "directories"
+                    },
+                    list
+                )
+            } else {
+                list
+            }
+        };
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
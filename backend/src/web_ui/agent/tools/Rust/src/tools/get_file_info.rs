@@ -3,13 +3,14 @@ use std::path::Path;
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::{error::ServiceError, fs_service::FileSystemService};
 
 #[mcp_tool(
     name = "get_file_info",
     description = concat!("Retrieves detailed metadata for a specified file or directory. ",
-    "Information includes size, creation/modification timestamps, and type (file/directory). ",
-    "Useful for checking file existence, size, or type before other operations. ",
+    "Information includes size, creation/modification timestamps, type (file/directory) and whether the path is a symbolic link (with its target). ",
+    "Symbolic links are followed unless `follow_symlinks` is false, in which case the link's own metadata is reported. ",
+    "Optionally detects the MIME type of files, and with `detect_text_format` the encoding and line ending style (lf, crlf, cr or mixed) of text files. Set `format` to \"json\" for a JSON object with RFC 3339 timestamps instead of text. Useful for checking file existence, size, or type before other operations. ",
     "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\logs\\app.log or /var/www/html). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -21,6 +22,18 @@ use crate::fs_service::FileSystemService;
 pub struct GetFileInfoTool {
     /// The **absolute path** to the file or directory for which to retrieve information (e.g., `D:\\logs\\app.log` or `/var/www/html`).
     pub path: String,
+    /// If true, detects the file's MIME type from its content (magic bytes), falling back to its extension. Requires reading the start of the file. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub detect_mime: Option<bool>,
+    /// If true, detects the charset and line ending style (`lf`, `crlf`, `cr` or `mixed`) of a file. Requires reading the whole file. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub detect_text_format: Option<bool>,
+    /// Output format: "text" (default) or "json".
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub format: Option<String>,
+    /// If false, reports on a symbolic link itself (its own size and type) instead of the file it points to. Defaults to true.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub follow_symlinks: Option<bool>,
 }
 
 impl GetFileInfoTool {
@@ -28,10 +41,32 @@ impl GetFileInfoTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        let stats = context
-            .get_file_stats(Path::new(&params.path))
+        let mut stats = context
+            .get_file_stats(
+                Path::new(&params.path),
+                params.detect_mime,
+                params.follow_symlinks,
+            )
             .await
             .map_err(CallToolError::new)?;
-        Ok(CallToolResult::text_content(stats.to_string(), None))
+        if stats.is_file && params.detect_text_format.unwrap_or(false) {
+            let (encoding, line_ending) = context
+                .detect_text_format(Path::new(&params.path))
+                .await
+                .map_err(CallToolError::new)?;
+            stats.encoding = Some(encoding.to_string());
+            stats.line_ending = line_ending.map(|line_ending| line_ending.to_string());
+        }
+        let content = match params.format.as_deref().unwrap_or("text") {
+            "text" => stats.to_string(),
+            "json" => serde_json::to_string(&stats.to_json()).map_err(CallToolError::new)?,
+            format => {
+                return Err(CallToolError::new(ServiceError::FromString(format!(
+                    "Invalid format '{}'. Expected one of: text, json",
+                    format
+                ))))
+            }
+        };
+        Ok(CallToolResult::text_content(content, None))
     }
 }
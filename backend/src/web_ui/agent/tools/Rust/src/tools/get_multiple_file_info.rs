@@ -0,0 +1,60 @@
+use std::path::Path;
+
+use futures::{stream, StreamExt};
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use super::read_multiple_files::DEFAULT_READ_CONCURRENCY;
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "get_multiple_file_info",
+    description = concat!("Retrieves metadata for multiple files or directories at once. ",
+    "Returns a JSON array with one object per path, in the same order as `paths`. Each object holds the `path` and the same fields as the JSON format of `get_file_info` ",
+    "(size, RFC 3339 timestamps, type flags, symlink target, permissions). ",
+    "If a path cannot be accessed, its object contains an `error` message instead; other paths are still processed. ",
+    "IMPORTANT: All paths in the list MUST be absolute paths. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct GetMultipleFileInfoTool {
+    /// A list of **absolute paths** to the files or directories to inspect.
+    pub paths: Vec<String>,
+    /// If true, detects the MIME type of files from their content (magic bytes), falling back to their extension. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub detect_mime: Option<bool>,
+}
+
+impl GetMultipleFileInfoTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let detect_mime = params.detect_mime;
+        // `buffered` keeps the output in the same order as the input paths
+        let infos: Vec<serde_json::Value> = stream::iter(params.paths)
+            .map(|path| async move {
+                match context
+                    .get_file_stats(Path::new(&path), detect_mime, None)
+                    .await
+                {
+                    Ok(info) => {
+                        let mut json = info.to_json();
+                        json["path"] = path.into();
+                        json
+                    }
+                    Err(err) => serde_json::json!({ "path": path, "error": err.to_string() }),
+                }
+            })
+            .buffered(DEFAULT_READ_CONCURRENCY)
+            .collect()
+            .await;
+
+        let json = serde_json::to_string(&infos).map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(json, None))
+    }
+}
@@ -0,0 +1,82 @@
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "gzip_file",
+    description = concat!("Compresses a single file into a gzip (.gz) file saved at `target_file`. ",
+    "The file is streamed through the encoder, so large files are not loaded into memory. The input file is kept. ",
+    "Fails if `target_file` already exists, unless `overwrite` is true. ",
+    "IMPORTANT: The `input_file` and `target_file` paths MUST be absolute paths. Relative paths are not supported. ",
+    "Both paths must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct GzipTool {
+    /// The **absolute path** of the file to compress.
+    pub input_file: String,
+    /// The **absolute path** (usually ending in `.gz`) where the compressed file will be saved.
+    pub target_file: String,
+    /// Replace `target_file` if it already exists. Defaults to false.
+    pub overwrite: Option<bool>,
+}
+
+impl GzipTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let result_content = context
+            .gzip_file(
+                &params.input_file,
+                &params.target_file,
+                params.overwrite.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(result_content, None))
+    }
+}
+
+#[mcp_tool(
+    name = "gunzip_file",
+    description = concat!("Decompresses a gzip (.gz) file into `target_file`. ",
+    "The file is streamed through the decoder, so large files are not loaded into memory. The compressed file is kept. ",
+    "Fails if `target_file` already exists, unless `overwrite` is true. ",
+    "IMPORTANT: The `input_file` and `target_file` paths MUST be absolute paths. Relative paths are not supported. ",
+    "Both paths must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct GunzipTool {
+    /// The **absolute path** of the gzip file to decompress.
+    pub input_file: String,
+    /// The **absolute path** where the decompressed file will be saved.
+    pub target_file: String,
+    /// Replace `target_file` if it already exists. Defaults to false.
+    pub overwrite: Option<bool>,
+}
+
+impl GunzipTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let result_content = context
+            .gunzip_file(
+                &params.input_file,
+                &params.target_file,
+                params.overwrite.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(result_content, None))
+    }
+}
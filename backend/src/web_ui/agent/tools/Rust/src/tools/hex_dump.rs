@@ -0,0 +1,44 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{FileSystemService, DEFAULT_HEX_DUMP_LENGTH};
+
+#[mcp_tool(
+    name = "hex_dump",
+    description = concat!("Reads the first bytes of a file and returns them as a hex dump, similar to `hexdump -C`. ",
+    "Each line shows the offset, 16 bytes in hex and their printable ASCII characters, e.g. `00000000  89 50 4e 47 ...  |.PNG....|`. ",
+    "Useful for identifying file formats from their headers without transferring the whole file. ",
+    "Reads 256 bytes unless `length` is given, up to a maximum of 64 KiB. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct HexDumpTool {
+    /// The **absolute path** of the file to dump.
+    pub path: String,
+    /// Number of bytes to read from the start of the file. Defaults to 256.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub length: Option<u64>,
+}
+
+impl HexDumpTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let dump = context
+            .hex_dump(
+                Path::new(&params.path),
+                params.length.unwrap_or(DEFAULT_HEX_DUMP_LENGTH),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(dump, None))
+    }
+}
@@ -0,0 +1,37 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "indentation_report",
+    description = concat!("Analyzes the indentation of a source file and reports the dominant style (tabs or spaces) and width, ",
+    "the lines that deviate from it (wrong indentation character or a width that is not a multiple of the detected width), and an overall consistency percentage. ",
+    "Useful for deciding whether a file needs its indentation normalized before editing. ",
+    "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\src\\main.py or /home/user/project/main.rs). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct IndentationReportTool {
+    /// The **absolute path** of the file to analyze (e.g., `D:\\src\\main.py` or `/home/user/project/main.rs`).
+    pub path: String,
+}
+
+impl IndentationReportTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let report = context
+            .indentation_report(Path::new(&params.path))
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(report.to_string(), None))
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{utils::format_bytes, FileSystemService};
+
+/// Number of files returned when no `count` is given.
+pub const DEFAULT_LARGEST_FILES_COUNT: u64 = 10;
+
+#[mcp_tool(
+    name = "largest_files",
+    description = concat!("Finds the largest files under a directory, for disk usage triage. ",
+    "Returns up to `count` files (default 10) sorted by size, largest first, each with a human-readable size and its full absolute path. ",
+    "Optionally only considers files whose path relative to the directory matches the glob `pattern` (e.g., `**/*.log`). ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct LargestFilesTool {
+    /// The **absolute path** of the directory to search.
+    pub path: String,
+    /// How many files to return. Defaults to 10.
+    pub count: Option<u64>,
+    /// An optional glob pattern matched against paths relative to `path` (e.g., `**/*.log`).
+    pub pattern: Option<String>,
+}
+
+impl LargestFilesTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let count = params.count.unwrap_or(DEFAULT_LARGEST_FILES_COUNT) as usize;
+        let files = context
+            .largest_files(Path::new(&params.path), count, params.pattern.as_deref())
+            .map_err(CallToolError::new)?;
+
+        let result = if files.is_empty() {
+            "No files found".to_string()
+        } else {
+            files
+                .iter()
+                .map(|(path, size)| format!("{}\t{}", format_bytes(*size), path.display()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
@@ -3,12 +3,17 @@ use std::path::Path;
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::fs_service::{
+    utils::{format_bytes, format_system_time},
+    FileSystemService, ListDirectoryOptions,
+};
 
 #[mcp_tool(
     name = "list_directory",
     description = concat!("Provides a detailed listing of all files and subdirectories directly within a specified directory. ",
-    "Results are prefixed with [FILE] or [DIR] to distinguish types. ",
+    "Results are prefixed with [FILE] or [DIR] to distinguish types; set `detailed` to include each entry's size and modification time. ",
+    "Entries can be sorted by name, size or modification time, and large directories can be paged through with `offset` and `limit`. ",
+    "Set `include_hidden` to false to leave out hidden (dot-prefixed) entries. ",
     "Essential for exploring directory contents and identifying specific items. ",
     "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\archive\\documents or /usr/local/bin). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
@@ -21,6 +26,21 @@ use crate::fs_service::FileSystemService;
 pub struct ListDirectoryTool {
     /// The **absolute path** of the directory whose contents are to be listed (e.g., `D:\\archive\\documents` or `/usr/local/bin`).
     pub path: String,
+    /// Number of entries to skip before listing. When `offset` or `limit` is set, entries are sorted by name for stable paging.
+    pub offset: Option<u64>,
+    /// Maximum number of entries to return. If more entries remain, the output ends with a `next_offset` line.
+    pub limit: Option<u64>,
+    /// Sort entries by `name`, `size` or `modified`. Defaults to OS order, or `name` when paging.
+    pub sort_by: Option<String>,
+    /// If true, reverses the sort order (e.g., largest or most recently modified first).
+    pub reverse: Option<bool>,
+    /// If true, lists directories before files.
+    pub directories_first: Option<bool>,
+    /// If true, appends each entry's size and last modified time to its line.
+    pub detailed: Option<bool>,
+    /// If false, leaves out hidden entries: names starting with `.` and, on Windows, entries with the hidden attribute. Defaults to true.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub include_hidden: Option<bool>,
 }
 
 impl ListDirectoryTool {
@@ -28,15 +48,29 @@ impl ListDirectoryTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        let entries = context
-            .list_directory(Path::new(&params.path))
+        let detailed = params.detailed.unwrap_or(false);
+        let page = context
+            .list_directory(
+                Path::new(&params.path),
+                &ListDirectoryOptions {
+                    offset: params.offset.map(|offset| offset as usize),
+                    limit: params.limit.map(|limit| limit as usize),
+                    sort_by: params.sort_by,
+                    reverse: params.reverse.unwrap_or(false),
+                    directories_first: params.directories_first.unwrap_or(false),
+                    with_metadata: detailed,
+                    skip_hidden: !params.include_hidden.unwrap_or(true),
+                    ..Default::default()
+                },
+            )
             .await
             .map_err(CallToolError::new)?;
 
-        let formatted: Vec<_> = entries
+        let mut formatted: Vec<_> = page
+            .entries
             .iter()
             .map(|entry| {
-                format!(
+                let line = format!(
                     "{} {}",
                     if entry.path().is_dir() {
                         "[DIR]"
@@ -44,10 +78,25 @@ impl ListDirectoryTool {
                         "[FILE]"
                     },
                     entry.file_name().to_str().unwrap_or_default()
-                )
+                );
+                match entry.metadata.as_ref().filter(|_| detailed) {
+                    Some(metadata) => format!(
+                        "{} ({}, modified {})",
+                        line,
+                        format_bytes(metadata.len()),
+                        metadata
+                            .modified()
+                            .map_or("unknown".to_string(), format_system_time)
+                    ),
+                    None => line,
+                }
             })
             .collect();
 
+        if let Some(next_offset) = page.next_offset {
+            formatted.push(format!("next_offset: {}", next_offset));
+        }
+
         Ok(CallToolResult::text_content(formatted.join("\n"), None))
     }
 }
@@ -8,7 +8,10 @@ use crate::fs_service::FileSystemService;
 #[mcp_tool(
     name = "move_file",
     description = concat!("Moves or renames a file or directory. ",
-    "Can move items between directories or rename them within the same directory. The destination path must not already exist. ",
+    "Can move items between directories or rename them within the same directory. ",
+    "By default the destination must not already exist; set 'on_conflict' to 'overwrite' to replace it, or 'rename' to pick a free name like 'file (1).txt'. The final destination is returned. ",
+    "Set 'dryRun' to true to validate both paths and preview the move without performing it. ",
+    "Set 'merge' to true to move a directory into an existing destination directory by merging their contents; 'on_conflict' then applies to each entry that exists in both. ",
     "IMPORTANT: Both source and destination paths MUST be absolute paths (e.g., D:\\old_folder\\item.dat or /tmp/file_to_move). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -20,8 +23,20 @@ use crate::fs_service::FileSystemService;
 pub struct MoveFileTool {
     /// The **absolute source path** of the file or directory to be moved/renamed (e.g., `D:\\old_folder\\item.dat`).
     pub source: String,
-    /// The **absolute destination path** for the file or directory (e.g., `D:\\new_location\\item_new_name.dat`). This path must not already exist.
+    /// The **absolute destination path** for the file or directory (e.g., `D:\\new_location\\item_new_name.dat`).
     pub destination: String,
+    /// Preview the move without performing it. Defaults to false.
+    #[serde(
+        rename = "dryRun",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub dry_run: Option<bool>,
+    /// What to do when the destination already exists: `error` (default), `overwrite`, or `rename` to append ` (1)`, ` (2)`, ... to the destination name.
+    pub on_conflict: Option<String>,
+    /// When both the source and the existing destination are directories, merge the source's contents into the destination instead of failing. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub merge: Option<bool>,
 }
 
 impl MoveFileTool {
@@ -29,17 +44,41 @@ impl MoveFileTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        context
-            .move_file(Path::new(&params.source), Path::new(&params.destination))
-            .await
-            .map_err(CallToolError::new)?;
+        let (source, destination) = (Path::new(&params.source), Path::new(&params.destination));
+        let destination = if params.merge.unwrap_or(false) {
+            context
+                .merge_move(
+                    source,
+                    destination,
+                    params.dry_run,
+                    params.on_conflict.as_deref(),
+                )
+                .await
+        } else {
+            context
+                .move_file(
+                    source,
+                    destination,
+                    params.dry_run,
+                    params.on_conflict.as_deref(),
+                )
+                .await
+        }
+        .map_err(CallToolError::new)?;
 
-        Ok(CallToolResult::text_content(
+        let message = if params.dry_run.unwrap_or(false) {
+            format!(
+                "Dry run: would move {} to {}",
+                &params.source,
+                destination.display()
+            )
+        } else {
             format!(
                 "Successfully moved {} to {}",
-                &params.source, &params.destination
-            ),
-            None,
-        ))
+                &params.source,
+                destination.display()
+            )
+        };
+        Ok(CallToolResult::text_content(message, None))
     }
 }
@@ -1,14 +1,28 @@
 use std::path::Path;
 
-use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult, TextContent};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::{error::ServiceError, fs_service::FileSystemService};
+
+/// Chunk size used when only `offset` is given.
+pub const DEFAULT_CHUNK_SIZE: u64 = 1024 * 1024;
+/// Largest chunk a single call may return.
+pub const MAX_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
 
 #[mcp_tool(
     name = "read_file",
     description = concat!("Reads the entire content of a single text file and returns it as a string. ",
     "Suitable for examining file contents or loading configuration data. ",
+    "Files that are not valid UTF-8 can be read with the `lossy` encoding (invalid bytes are replaced), the `base64` encoding (raw bytes) ",
+    "or the `auto` encoding, which detects the charset (e.g., UTF-16 or Windows-1252), decodes it and reports it as a second content item (e.g., `encoding: UTF-16LE`). ",
+    "For large or binary files, set `offset` and/or `chunk_size` (default 1 MiB, at most 8 MiB) to read one chunk at a time: ",
+    "the result is a JSON object with the base64 `content` of the chunk, its `offset` and `size`, the file's `totalSize` and an `eof` flag, so a client can loop until `eof` is true. ",
+    "Set `start_line` and/or `end_line` (1-based, inclusive) to read only a range of lines; a range past the end of the file returns the lines that exist. ",
+    "Set `include_hash` to `sha256` or `sha512` to also get the digest of the file's bytes as a second content item (e.g., `sha256: <hex>`), computed from the same read. ",
+    "Chunked mode can't be combined with `encoding`, `include_hash` or a line range, and a line range can't be combined with `include_hash` or the `base64` encoding; such calls are rejected. ",
     "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\my_documents\\report.txt or /home/user/config.json). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -20,15 +34,131 @@ use crate::fs_service::FileSystemService;
 pub struct ReadFileTool {
     /// The **absolute path** of the file to be read (e.g., `D:\\my_documents\\report.txt` or `/home/user/config.json`).
     pub path: String,
+    /// How to decode the file content: `utf-8` (default, fails on invalid UTF-8), `lossy` (replaces invalid sequences with U+FFFD) `base64` (returns the raw bytes base64-encoded) or `auto` (detects the charset and decodes it).
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub encoding: Option<String>,
+    /// Also return the digest of the file's raw bytes using this algorithm: `sha256` or `sha512`. Off by default.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub include_hash: Option<String>,
+    /// Byte offset to start a chunked read at. Setting this or `chunk_size` enables chunked mode.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub offset: Option<u64>,
+    /// Maximum number of bytes to return in chunked mode. Defaults to 1 MiB, capped at 8 MiB.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub chunk_size: Option<u64>,
+    /// First line to return (1-based). Setting this or `end_line` returns only that range of lines.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub start_line: Option<u64>,
+    /// Last line to return (1-based, inclusive). Lines past the end of the file are ignored.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub end_line: Option<u64>,
 }
 
 impl ReadFileTool {
+    /// Rejects options that the selected read mode would otherwise ignore.
+    fn check_options(&self) -> Result<(), CallToolError> {
+        let chunked = self.offset.is_some() || self.chunk_size.is_some();
+        let line_range = self.start_line.is_some() || self.end_line.is_some();
+        let conflict = if chunked && self.encoding.is_some() {
+            Some("`offset`/`chunk_size` can't be used together with `encoding`; chunks are always base64.")
+        } else if chunked && self.include_hash.is_some() {
+            Some("`offset`/`chunk_size` can't be used together with `include_hash`.")
+        } else if chunked && line_range {
+            Some("`offset`/`chunk_size` can't be used together with `start_line`/`end_line`.")
+        } else if line_range && self.include_hash.is_some() {
+            Some("`start_line`/`end_line` can't be used together with `include_hash`.")
+        } else if line_range
+            && self
+                .encoding
+                .as_deref()
+                .is_some_and(|encoding| encoding.eq_ignore_ascii_case("base64"))
+        {
+            Some("`start_line`/`end_line` can't be used together with the `base64` encoding.")
+        } else {
+            None
+        };
+        match conflict {
+            Some(message) => Err(CallToolError::new(ServiceError::from(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                message,
+            )))),
+            None => Ok(()),
+        }
+    }
+
     pub async fn run_tool(
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
+        params.check_options()?;
+        if params.offset.is_some() || params.chunk_size.is_some() {
+            let chunk_size = params
+                .chunk_size
+                .unwrap_or(DEFAULT_CHUNK_SIZE)
+                .clamp(1, MAX_CHUNK_SIZE);
+            let chunk = context
+                .read_file_chunk(
+                    Path::new(&params.path),
+                    params.offset.unwrap_or(0),
+                    chunk_size as usize,
+                )
+                .await
+                .map_err(CallToolError::new)?;
+            let result = serde_json::json!({
+                "offset": chunk.offset,
+                "size": chunk.data.len(),
+                "totalSize": chunk.total_size,
+                "eof": chunk.eof,
+                "content": BASE64_STANDARD.encode(&chunk.data),
+            });
+            return Ok(CallToolResult::text_content(result.to_string(), None));
+        }
+
+        if params.start_line.is_some() || params.end_line.is_some() {
+            let content = context
+                .read_file_lines(
+                    Path::new(&params.path),
+                    params.encoding.as_deref(),
+                    params.start_line.map(|line| line as usize),
+                    params.end_line.map(|line| line as usize),
+                )
+                .await
+                .map_err(CallToolError::new)?;
+            return Ok(CallToolResult::text_content(content, None));
+        }
+
+        let auto_detect = params
+            .encoding
+            .as_deref()
+            .is_some_and(|encoding| encoding.eq_ignore_ascii_case("auto"));
+        if auto_detect || params.include_hash.is_some() {
+            let decoded = context
+                .read_file_decoded(
+                    Path::new(&params.path),
+                    params.encoding.as_deref(),
+                    params.include_hash.as_deref(),
+                )
+                .await
+                .map_err(CallToolError::new)?;
+            let mut content = vec![TextContent::new(decoded.content, None).into()];
+            if let Some(encoding) = decoded.detected_encoding {
+                content.push(TextContent::new(format!("encoding: {}", encoding), None).into());
+            }
+            if let (Some(algorithm), Some(digest)) = (&params.include_hash, decoded.digest) {
+                content.push(
+                    TextContent::new(format!("{}: {}", algorithm.to_lowercase(), digest), None)
+                        .into(),
+                );
+            }
+            return Ok(CallToolResult {
+                content,
+                is_error: None,
+                meta: None,
+            });
+        }
+
         let content = context
-            .read_file(Path::new(&params.path))
+            .read_file_with_encoding(Path::new(&params.path), params.encoding.as_deref())
             .await
             .map_err(CallToolError::new)?;
 
@@ -1,16 +1,20 @@
 use std::path::Path;
 
-use futures::future::join_all;
+use futures::{stream, StreamExt};
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
 use crate::fs_service::FileSystemService;
 
+/// Default number of files read at the same time, to stay well below open file descriptor limits.
+pub const DEFAULT_READ_CONCURRENCY: usize = 32;
+
 #[mcp_tool(
     name = "read_multiple_files",
     description = concat!("Reads the content of multiple text files simultaneously and returns them as a single string, with each file's content clearly demarcated. ",
     "More efficient than reading files individually when multiple files are needed. ",
     "If a file cannot be read, an error message for that specific file is included in the output; other files are still processed. ",
+    "Binary files are skipped with a note instead of being read. ",
     "IMPORTANT: All paths in the list MUST be absolute paths (e.g., D:\\sources\\file1.rs or /opt/app/data.csv). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -22,6 +26,8 @@ use crate::fs_service::FileSystemService;
 pub struct ReadMultipleFilesTool {
     /// A list of **absolute file paths** to be read (e.g., `["D:\\sources\\file1.rs", "D:\\sources\\file2.java"]`).
     pub paths: Vec<String>,
+    /// Maximum number of files read concurrently. Defaults to 32.
+    pub max_concurrency: Option<u64>,
 }
 
 impl ReadMultipleFilesTool {
@@ -29,10 +35,16 @@ impl ReadMultipleFilesTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        let content_futures: Vec<_> = params
-            .paths
-            .iter()
+        let max_concurrency = params
+            .max_concurrency
+            .map_or(DEFAULT_READ_CONCURRENCY, |value| value.max(1) as usize);
+
+        // `buffered` keeps the output in the same order as the input paths
+        let contents: Vec<String> = stream::iter(params.paths)
             .map(|path| async move {
+                if let Ok(true) = context.is_binary_file(Path::new(&path)) {
+                    return format!("{}: Skipped - binary file", path);
+                }
                 {
                     let content = context
                         .read_file(Path::new(&path))
@@ -45,9 +57,9 @@ impl ReadMultipleFilesTool {
                     )
                 }
             })
-            .collect();
-
-        let contents = join_all(content_futures).await;
+            .buffered(max_concurrency)
+            .collect()
+            .await;
 
         Ok(CallToolResult::text_content(contents.join("\n---\n"), None))
     }
@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "real_path",
+    description = concat!("Resolves a path to its canonical absolute form, following all symlinks and resolving `.` and `..` components. ",
+    "Fails if the path does not exist or if the resolved target lies outside the allowed directories, ",
+    "which makes it useful for detecting symlinks that escape the allowed directories. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct RealPathTool {
+    /// The **absolute path** to resolve.
+    pub path: String,
+}
+
+impl RealPathTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let real_path = context
+            .real_path(Path::new(&params.path))
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(
+            real_path.display().to_string(),
+            None,
+        ))
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, path::Path};
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+/// A named value substituted into `{{name}}` placeholders of a template.
+pub struct TemplateVariable {
+    /// The placeholder name, without the surrounding braces.
+    pub name: String,
+    /// The value that replaces every occurrence of the placeholder.
+    pub value: String,
+}
+
+#[mcp_tool(
+    name = "render_template",
+    description = concat!("Renders a template file by substituting `{{name}}` placeholders with the supplied variables and writes the result to an output file. ",
+    "A placeholder may declare a fallback value using `{{name|default}}`; referencing an undefined variable without a default is an error. ",
+    "Useful for scaffolding new files from boilerplate. Set `dryRun` to preview the rendered text without writing it. ",
+    "IMPORTANT: Both `template_path` and `output_path` MUST be absolute paths. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct RenderTemplateTool {
+    /// The **absolute path** of the template file to render.
+    pub template_path: String,
+    /// The **absolute path** where the rendered output will be written. Existing files are overwritten.
+    pub output_path: String,
+    /// The variables used to fill in the template placeholders.
+    pub variables: Vec<TemplateVariable>,
+    /// If true, returns the rendered text without writing the output file.
+    #[serde(
+        rename = "dryRun",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+impl RenderTemplateTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let variables: HashMap<String, String> = params
+            .variables
+            .into_iter()
+            .map(|variable| (variable.name, variable.value))
+            .collect();
+
+        let rendered = context
+            .render_template(
+                Path::new(&params.template_path),
+                &variables,
+                Path::new(&params.output_path),
+                params.dry_run,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        let result = if params.dry_run.unwrap_or(false) {
+            rendered
+        } else {
+            format!(
+                "Successfully rendered {} to {}",
+                &params.template_path, &params.output_path
+            )
+        };
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
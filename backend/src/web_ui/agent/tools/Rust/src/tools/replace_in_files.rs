@@ -0,0 +1,94 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{FileSystemService, ReplaceInFilesOptions};
+
+#[mcp_tool(
+    name = "replace_in_files",
+    description = concat!("Replaces text in every matching file under a directory, like a project-wide search and replace. ",
+    "Files are selected with `includePatterns` and `excludePatterns` globs matched against paths relative to `path` (e.g., `**/*.rs`, `target/**`); binary files are skipped. ",
+    "Every occurrence of `oldText` is replaced with `newText`; set `isRegex` to match a regular expression, whose capture groups `newText` may reference (e.g., `$1`). ",
+    "Returns a git-style diff for each changed file and the number of files changed. Set `dryRun` to preview the changes without writing them. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ReplaceInFilesTool {
+    /// The **absolute path** of the directory to search.
+    pub path: String,
+    /// Glob patterns selecting the files to edit, relative to `path` (e.g., `["**/*.rs"]`). Defaults to all files.
+    #[serde(
+        rename = "includePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub include_patterns: Option<Vec<String>>,
+    /// Glob patterns of files and directories to skip, relative to `path` (e.g., `["target/**"]`).
+    #[serde(
+        rename = "excludePatterns",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// The text to search for.
+    #[serde(rename = "oldText")]
+    pub old_text: String,
+    /// The replacement text.
+    #[serde(rename = "newText")]
+    pub new_text: String,
+    /// If true, `oldText` is treated as a regular expression. Defaults to false.
+    #[serde(
+        rename = "isRegex",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub is_regex: Option<bool>,
+    /// If true, previews the changes without writing them. Defaults to false.
+    #[serde(
+        rename = "dryRun",
+        default,
+        skip_serializing_if = "std::option::Option::is_none"
+    )]
+    pub dry_run: Option<bool>,
+}
+
+impl ReplaceInFilesTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let dry_run = params.dry_run.unwrap_or(false);
+        let options = ReplaceInFilesOptions {
+            include_patterns: params.include_patterns.unwrap_or_default(),
+            exclude_patterns: params.exclude_patterns.unwrap_or_default(),
+            is_regex: params.is_regex.unwrap_or(false),
+            dry_run,
+        };
+        let (diff, changed) = context
+            .replace_in_files(
+                Path::new(&params.path),
+                &params.old_text,
+                &params.new_text,
+                &options,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        let files = if changed == 1 { "file" } else { "files" };
+        let summary = if dry_run {
+            format!("Dry run: {} {} would be changed.", changed, files)
+        } else {
+            format!("Changed {} {}.", changed, files)
+        };
+        Ok(CallToolResult::text_content(
+            format!("{}{}", diff, summary),
+            None,
+        ))
+    }
+}
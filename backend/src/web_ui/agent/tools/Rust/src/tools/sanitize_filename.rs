@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "sanitize_filename",
+    description = concat!("Converts an arbitrary, possibly untrusted string into a filename that is safe to use on Windows, macOS and Linux. ",
+    "Path separators and traversal sequences (`..`) are collapsed, illegal characters and null bytes are replaced, reserved Windows device names (e.g., `CON`, `NUL`) are escaped, and over-long names are truncated. ",
+    "If `directory` is provided, returns the absolute path of the sanitized filename inside that directory. The file is not created. ",
+    "IMPORTANT: The `directory`, when provided, MUST be an absolute path and is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct SanitizeFilenameTool {
+    /// The untrusted filename to sanitize (e.g., `../../etc/passwd` or `report:final?.txt`).
+    pub filename: String,
+    /// Optional **absolute path** of the directory the sanitized filename will be placed in.
+    pub directory: Option<String>,
+}
+
+impl SanitizeFilenameTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let sanitized = context
+            .sanitize_filename(&params.filename, params.directory.as_deref().map(Path::new))
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(
+            sanitized.display().to_string(),
+            None,
+        ))
+    }
+}
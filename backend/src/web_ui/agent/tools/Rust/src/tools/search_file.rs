@@ -3,12 +3,23 @@ use std::path::Path;
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::{
+    error::ServiceError,
+    fs_service::{FileSystemService, SearchOptions},
+};
+
+/// `path` value that searches every allowed directory.
+pub const ALL_ROOTS: &str = "*";
+
 #[mcp_tool(
     name = "search_files",
     description = concat!("Recursively searches for files and directories matching a glob pattern within a specified starting directory. ",
-    "The search is case-insensitive and matches partial names if the pattern allows. Returns a list of full absolute paths for all matches. ",
+    "The search is case-insensitive unless `case_sensitive` is true, and matches partial names if the pattern allows. Returns a list of full absolute paths for all matches. ",
     "Useful for finding items when their exact location or full name is unknown. Supports exclude patterns. ",
+    "Pass several glob patterns in `patterns` to return entries matching any of them. Brace alternatives such as `*.{rs,toml}` are expanded. ",
+    "Use `max_depth` to limit how deep the search descends and `type_filter` (`file` or `dir`) to return only one kind of entry. ",
+    "Omit `path` or set it to `*` to search all allowed directories at once. ",
+    "Set `respect_gitignore` to skip paths excluded by `.gitignore` files, and `include_hidden` to false to skip hidden (dot-prefixed) files and directories. ",
     "IMPORTANT: The starting path provided MUST be an absolute path (e.g., D:\\projects or /var/log). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -20,26 +31,62 @@ use crate::fs_service::FileSystemService;
 
 /// A tool for searching files based on a path and pattern.
 pub struct SearchFilesTool {
-    /// The **absolute directory path** from which to start the search (e.g., `D:\\projects` or `/var/log`).
-    pub path: String,
-    /// The glob pattern to match against file/directory names (e.g., `*.txt`, `my_app*`, `**/*config*.json`). Case-insensitive.
-    pub pattern: String,
+    /// The **absolute directory path** from which to start the search (e.g., `D:\\projects` or `/var/log`). Omit it or use `*` to search every allowed directory.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub path: Option<String>,
+    /// The glob pattern to match against file/directory names (e.g., `*.txt`, `my_app*`, `**/*config*.json`). Case-insensitive by default.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub pattern: Option<String>,
+    /// Additional glob patterns; entries matching `pattern` or any of these are returned (e.g., `["*.rs", "*.toml"]`).
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub patterns: Option<Vec<String>>,
     #[serde(rename = "excludePatterns")]
     /// Optional list of glob patterns to exclude from search results (e.g., `["*.tmp", "**/cache/**"]`).
     pub exclude_patterns: Option<Vec<String>>,
+    /// If true, skips files and directories excluded by `.gitignore` (and `.ignore`) files. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub respect_gitignore: Option<bool>,
+    /// If true, the pattern and exclude patterns match case-sensitively (e.g., `README` but not `readme`). Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub case_sensitive: Option<bool>,
+    /// Maximum depth to descend below the starting directory; 1 searches only its direct entries.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub max_depth: Option<u64>,
+    /// Only return entries of this type: `file` or `dir`.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub type_filter: Option<String>,
+    /// If false, skips hidden files and directories: names starting with `.` and, on Windows, entries with the hidden attribute. Defaults to true.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub include_hidden: Option<bool>,
 }
 impl SearchFilesTool {
     pub async fn run_tool(
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        let list = context
-            .search_files(
-                Path::new(&params.path),
-                params.pattern,
-                params.exclude_patterns.unwrap_or_default(),
-            )
-            .map_err(CallToolError::new)?;
+        let patterns: Vec<String> = params
+            .pattern
+            .into_iter()
+            .chain(params.patterns.unwrap_or_default())
+            .collect();
+        if patterns.is_empty() {
+            return Err(CallToolError::new(ServiceError::FromString(
+                "Either `pattern` or `patterns` must be provided".to_string(),
+            )));
+        }
+        let options = SearchOptions {
+            exclude_patterns: params.exclude_patterns.unwrap_or_default(),
+            respect_gitignore: params.respect_gitignore.unwrap_or(false),
+            case_sensitive: params.case_sensitive.unwrap_or(false),
+            max_depth: params.max_depth.map(|max_depth| max_depth as usize),
+            type_filter: params.type_filter,
+            skip_hidden: !params.include_hidden.unwrap_or(true),
+        };
+        let list = match params.path.as_deref() {
+            None | Some(ALL_ROOTS) => context.search_all_directories(&patterns, &options),
+            Some(path) => context.search_files(Path::new(path), &patterns, &options),
+        }
+        .map_err(CallToolError::new)?;
 
         let result = if !list.is_empty() {
             list.iter()
@@ -0,0 +1,71 @@
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::{AccessLevel, FileSystemService, MAX_COMPRESSION_RATIO};
+use crate::tools::read_files::MAX_CHUNK_SIZE;
+use crate::tools::tail_follow::MAX_FOLLOW_SECS;
+
+#[mcp_tool(
+    name = "server_info",
+    description = concat!("Returns the configuration of this MCP server instance as JSON: server name and version, protocol version, ",
+    "mode (`read-only` or `read-write`), allowed directories with their access level, the deny list, the trash directory, ",
+    "whether backups are enabled and the configured limits. ",
+    "Use this tool to understand what the server permits before attempting file operations. ",
+    "No parameters are required for this tool."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ServerInfoTool {}
+
+fn access_label(access: AccessLevel) -> &'static str {
+    match access {
+        AccessLevel::ReadOnly => "read-only",
+        AccessLevel::ReadWrite => "read-write",
+    }
+}
+
+impl ServerInfoTool {
+    pub async fn run_tool(
+        _: Self,
+        context: &FileSystemService,
+        protocol_version: &str,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let allowed_directories: Vec<_> = context
+            .allowed_directories()
+            .iter()
+            .map(|dir| {
+                serde_json::json!({
+                    "path": dir.display().to_string(),
+                    "access": context.access_level(dir).map(access_label).ok(),
+                })
+            })
+            .collect();
+        let mode = if context.has_writable_directories() {
+            AccessLevel::ReadWrite
+        } else {
+            AccessLevel::ReadOnly
+        };
+
+        let info = serde_json::json!({
+            "name": env!("CARGO_PKG_NAME"),
+            "version": env!("CARGO_PKG_VERSION"),
+            "protocolVersion": protocol_version,
+            "mode": access_label(mode),
+            "allowedDirectories": allowed_directories,
+            "deny": context.denied_patterns(),
+            "trashDir": context.trash_dir().map(|dir| dir.display().to_string()),
+            "backup": context.backup_enabled(),
+            "limits": {
+                "maxExtractSize": context.max_extract_size(),
+                "maxCompressionRatio": MAX_COMPRESSION_RATIO,
+                "maxReadChunkSize": MAX_CHUNK_SIZE,
+                "maxFollowSecs": MAX_FOLLOW_SECS,
+            },
+        });
+        let result = serde_json::to_string_pretty(&info).map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "set_permissions",
+    description = concat!("Changes the permissions of a file or directory (similar to `chmod`). ",
+    "On Unix, provide `mode` as an octal string (e.g., \"0644\" or \"0755\"); alternatively `readonly` removes or restores write access. ",
+    "On Windows, only `readonly` is supported. Returns the resulting permissions. ",
+    "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\scripts\\run.bat or /usr/local/bin/script.sh). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct SetPermissionsTool {
+    /// The **absolute path** of the file or directory whose permissions will be changed.
+    pub path: String,
+    /// Unix only: the new permission bits as an octal string (e.g., `0644`).
+    pub mode: Option<String>,
+    /// Marks the file as read-only (true) or writable (false). Used when `mode` is not provided.
+    pub readonly: Option<bool>,
+}
+
+impl SetPermissionsTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let permissions = context
+            .set_permissions(
+                Path::new(&params.path),
+                params.mode.as_deref(),
+                params.readonly,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(
+            format!(
+                "Successfully updated permissions of {} to {}",
+                &params.path, permissions
+            ),
+            None,
+        ))
+    }
+}
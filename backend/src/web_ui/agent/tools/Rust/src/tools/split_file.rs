@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "split_file",
+    description = concat!("Splits a file into numbered parts named `<file name>.part0001`, `<file name>.part0002`, and so on. ",
+    "Each part holds `chunk_size` bytes or `lines` lines (exactly one must be given), except possibly the last. ",
+    "The file is streamed, so large files can be split without loading them into memory. ",
+    "Returns the absolute paths of the created parts, one per line. The parts can be joined again with `concat_files`. ",
+    "IMPORTANT: The `path` and `output_dir` MUST be absolute paths. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct SplitFileTool {
+    /// The **absolute path** of the file to split.
+    pub path: String,
+    /// The **absolute path** of the directory to write the parts to. It is created if it doesn't exist.
+    pub output_dir: String,
+    /// Maximum size of each part in bytes.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub chunk_size: Option<u64>,
+    /// Maximum number of lines in each part.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub lines: Option<u64>,
+}
+
+impl SplitFileTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let parts = context
+            .split_file(
+                Path::new(&params.path),
+                Path::new(&params.output_dir),
+                params.chunk_size,
+                params.lines,
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        let content = parts
+            .iter()
+            .map(|part| part.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(CallToolResult::text_content(content, None))
+    }
+}
@@ -0,0 +1,104 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+use tokio_util::sync::CancellationToken;
+
+use crate::fs_service::FileSystemService;
+
+/// Default number of seconds a file is followed for, kept short because other tool calls wait
+/// until following ends.
+pub const DEFAULT_FOLLOW_SECS: u64 = 5;
+/// Longest a single call may follow a file, since it occupies the server until it returns.
+pub const MAX_FOLLOW_SECS: u64 = 300;
+/// How often the followed file is checked for new content.
+pub const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Receives each line appended to a followed file.
+pub type LineCallback = Arc<dyn Fn(String) + Send + Sync>;
+
+#[mcp_tool(
+    name = "tail_follow",
+    description = concat!("Follows a file like `tail -f`, streaming lines appended after the call as `notifications/message` log notifications (logger `tail_follow`). ",
+    "Handles log rotation and truncation by reopening the file from the start. ",
+    "Following stops after `duration_secs` (default 5, at most 300), once `max_lines` lines were received, or when notifications can no longer be delivered; it can't be cancelled by the client. ",
+    "WARNING: The server handles one request at a time, so all other tool calls are blocked until following stops. Keep `duration_secs` short. ",
+    "Returns all received lines when it stops. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct TailFollowTool {
+    /// The **absolute path** of the file to follow.
+    pub path: String,
+    /// How many seconds to follow the file for. Defaults to 5, capped at 300. Other tool calls wait until following stops.
+    pub duration_secs: Option<u64>,
+    /// Stop after this many lines have been received.
+    pub max_lines: Option<u64>,
+}
+
+impl TailFollowTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+        on_line: Option<LineCallback>,
+        cancel: CancellationToken,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let duration = Duration::from_secs(
+            params
+                .duration_secs
+                .unwrap_or(DEFAULT_FOLLOW_SECS)
+                .min(MAX_FOLLOW_SECS),
+        );
+        let mut lines: Vec<String> = vec![];
+
+        let follow = async {
+            let result = context
+                .tail_follow(
+                    Path::new(&params.path),
+                    FOLLOW_POLL_INTERVAL,
+                    cancel.clone(),
+                    |line| {
+                        if let Some(on_line) = &on_line {
+                            on_line(line.clone());
+                        }
+                        lines.push(line);
+                        if params
+                            .max_lines
+                            .is_some_and(|max| lines.len() as u64 >= max)
+                        {
+                            cancel.cancel();
+                        }
+                    },
+                )
+                .await;
+            // Also ends the duration timer when following fails
+            cancel.cancel();
+            result
+        };
+        let stop_after_duration = async {
+            tokio::select! {
+                _ = cancel.cancelled() => {}
+                _ = tokio::time::sleep(duration) => cancel.cancel(),
+            }
+        };
+        let (line_count, _) = tokio::join!(follow, stop_after_duration);
+        let line_count = line_count.map_err(CallToolError::new)?;
+
+        let mut result = format!(
+            "Received {} new {} from '{}'.",
+            line_count,
+            if line_count == 1 { "line" } else { "lines" },
+            params.path
+        );
+        if !lines.is_empty() {
+            result.push('\n');
+            result.push_str(&lines.join("\n"));
+        }
+        Ok(CallToolResult::text_content(result, None))
+    }
+}
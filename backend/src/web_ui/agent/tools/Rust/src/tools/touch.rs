@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "touch",
+    description = concat!("Creates an empty file if it does not exist, or updates the access and modification times of an existing file. ",
+    "Uses the current time unless an explicit RFC 3339 `timestamp` is provided (e.g., 2025-05-01T12:00:00Z). ",
+    "Useful for build-cache style workflows or marking files as recently changed. ",
+    "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\build\\.stamp or /tmp/build/.stamp). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct TouchTool {
+    /// The **absolute path** of the file to create or update (e.g., `D:\\build\\.stamp` or `/tmp/build/.stamp`).
+    pub path: String,
+    /// Optional RFC 3339 timestamp to set as access and modification time (e.g., `2025-05-01T12:00:00Z`). Defaults to now.
+    pub timestamp: Option<String>,
+}
+
+impl TouchTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let created = context
+            .touch(Path::new(&params.path), params.timestamp.as_deref())
+            .await
+            .map_err(CallToolError::new)?;
+
+        let message = if created {
+            format!("Successfully created {}", &params.path)
+        } else {
+            format!("Successfully updated timestamps of {}", &params.path)
+        };
+        Ok(CallToolResult::text_content(message, None))
+    }
+}
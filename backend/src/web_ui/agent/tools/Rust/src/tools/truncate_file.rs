@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "truncate_file",
+    description = concat!("Shrinks or extends an existing file to exactly `size` bytes. ",
+    "Content beyond the new size is discarded; when the file grows, the new bytes are filled with zeros. ",
+    "Use a size of 0 to empty a file. The file must already exist. ",
+    "IMPORTANT: The path provided MUST be an absolute path. Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = true,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct TruncateFileTool {
+    /// The **absolute path** of the file to truncate or extend.
+    pub path: String,
+    /// The new size of the file in bytes.
+    pub size: u64,
+}
+
+impl TruncateFileTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let previous_size = context
+            .truncate_file(Path::new(&params.path), params.size)
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(
+            format!(
+                "Successfully resized {} from {} to {} bytes",
+                &params.path, previous_size, params.size
+            ),
+            None,
+        ))
+    }
+}
@@ -0,0 +1,39 @@
+use std::path::Path;
+
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "word_count",
+    description = concat!("Counts the lines, words and bytes of a file, similar to the `wc` command. ",
+    "The file is streamed, so large files can be counted without loading them into memory. ",
+    "Lines ending with LF, CRLF or CR are all counted, as is a final line without a trailing newline. ",
+    "Returns a JSON object with `lines`, `words` and `bytes` fields. ",
+    "IMPORTANT: The path MUST be an absolute path. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = true,
+    open_world_hint = false,
+    read_only_hint = true
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct WordCountTool {
+    /// The **absolute path** of the file to count.
+    pub path: String,
+}
+
+impl WordCountTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let count = context
+            .word_count(Path::new(&params.path))
+            .await
+            .map_err(CallToolError::new)?;
+        let json = serde_json::to_string(&count).map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(json, None))
+    }
+}
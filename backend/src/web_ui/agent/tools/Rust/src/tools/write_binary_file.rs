@@ -0,0 +1,58 @@
+use std::path::Path;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::{error::ServiceError, fs_service::FileSystemService};
+
+#[mcp_tool(
+    name = "write_binary_file",
+    description = concat!("Writes binary data to a file, creating the file if it doesn't exist or completely overwriting it if it does. ",
+    "The content must be base64-encoded; it is decoded and written as raw bytes, which makes this tool suitable for images, archives and other non-text files. ",
+    "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\assets\\logo.png or /app/data/blob.bin). Relative paths are not supported. ",
+    "This operation is restricted to pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(Debug, Clone, ::serde::Deserialize, ::serde::Serialize, JsonSchema)]
+pub struct WriteBinaryFileTool {
+    /// The **absolute path** of the file to be written to (e.g., `D:\\assets\\logo.png` or `/app/data/blob.bin`).
+    pub path: String,
+    /// The base64-encoded (standard alphabet, padded) bytes to write.
+    pub content: String,
+    /// If true, missing parent directories are created before writing. Defaults to false.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub create_parents: Option<bool>,
+}
+
+impl WriteBinaryFileTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let bytes = BASE64_STANDARD
+            .decode(params.content.trim())
+            .map_err(|err| CallToolError::new(ServiceError::from(err)))?;
+
+        context
+            .write_bytes(
+                Path::new(&params.path),
+                &bytes,
+                params.create_parents.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+
+        Ok(CallToolResult::text_content(
+            format!(
+                "Successfully wrote {} bytes to {}",
+                bytes.len(),
+                &params.path
+            ),
+            None,
+        ))
+    }
+}
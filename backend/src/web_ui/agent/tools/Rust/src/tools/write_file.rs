@@ -3,11 +3,12 @@ use std::path::Path;
 
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
 
-use crate::fs_service::FileSystemService;
+use crate::fs_service::{utils::encode_text, FileSystemService};
 #[mcp_tool(
     name = "write_file",
     description = concat!("Writes new content to a file, creating the file if it doesn't exist or completely overwriting it if it does. ",
-    "Use with caution, as existing file content will be lost. Handles text content with UTF-8 encoding. ",
+    "Use with caution, as existing file content will be lost. Content is written as UTF-8 unless `encoding` is set to `utf-16le` (written with a byte order mark) or `latin1`. ",
+    "To avoid overwriting changes made by someone else, pass `expected_sha256` with the hash of the content you last read; the write fails with a conflict if the file changed. Pass an empty `expected_sha256` to only create a file that does not exist yet. ",
     "IMPORTANT: The path provided MUST be an absolute path (e.g., D:\\output\\result.json or /app/data/new_file.txt). Relative paths are not supported. ",
     "This operation is restricted to pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -21,6 +22,15 @@ pub struct WriteFileTool {
     pub path: String,
     /// The string content to be written to the file.
     pub content: String,
+    /// If true, saves the previous content of an existing file to `<path>.bak` before overwriting it. Defaults to the server's `--backup` setting.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub backup: Option<bool>,
+    /// Only overwrite the file if its current content has this sha256 digest (lowercase hex). An empty string requires that the file does not exist yet.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub expected_sha256: Option<String>,
+    /// Encoding of the written file: `utf-8` (default), `utf-16le` or `latin1`.
+    #[serde(default, skip_serializing_if = "std::option::Option::is_none")]
+    pub encoding: Option<String>,
 }
 
 impl WriteFileTool {
@@ -28,10 +38,29 @@ impl WriteFileTool {
         params: Self,
         context: &FileSystemService,
     ) -> std::result::Result<CallToolResult, CallToolError> {
-        context
-            .write_file(Path::new(&params.path), &params.content)
-            .await
-            .map_err(CallToolError::new)?;
+        let content = encode_text(
+            &params.content,
+            params.encoding.as_deref().unwrap_or("utf-8"),
+        )
+        .map_err(CallToolError::new)?;
+        match &params.expected_sha256 {
+            Some(expected_sha256) => {
+                context
+                    .write_file_if_matches(
+                        Path::new(&params.path),
+                        &content,
+                        params.backup,
+                        expected_sha256,
+                    )
+                    .await
+            }
+            None => {
+                context
+                    .write_file(Path::new(&params.path), &content, params.backup)
+                    .await
+            }
+        }
+        .map_err(CallToolError::new)?;
 
         Ok(CallToolResult::text_content(
             format!("Successfully wrote to {}", &params.path),
@@ -1,12 +1,18 @@
-use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use base64::{prelude::BASE64_STANDARD, Engine};
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult, TextContent};
 use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
 
-use crate::fs_service::FileSystemService;
+use crate::{
+    error::ServiceError,
+    fs_service::{utils::format_bytes, FileSystemService, ProgressCallback},
+};
 
 #[mcp_tool(
     name = "zip_files",
     description = concat!("Creates a ZIP archive from a list of specified input files. ",
     "The resulting ZIP file is saved to the `target_zip_file` path. ",
+    "Set `append` to add the files to an existing archive instead; entries with the same name cause an error unless `skip_duplicates` is true. ",
+    "Alternatively, set `in_memory` instead of `target_zip_file` to get the archive back as base64 without writing it to disk; this is limited to small archives (8 MiB of input). ",
     "IMPORTANT: All file paths in `input_files` and the `target_zip_file` path MUST be absolute paths. Relative paths are not supported. ",
     "Both source files and the target ZIP file location must be within pre-configured allowed directories on the server."),
     destructive_hint = false,
@@ -18,17 +24,44 @@ use crate::fs_service::FileSystemService;
 pub struct ZipFilesTool {
     /// A list of **absolute paths** to the files that should be included in the ZIP archive.
     pub input_files: Vec<String>,
-    /// The **absolute path** (including filename and .zip extension) where the generated ZIP archive will be saved.
-    pub target_zip_file: String,
+    /// The **absolute path** (including filename and .zip extension) where the generated ZIP archive will be saved. Required unless `in_memory` is true.
+    pub target_zip_file: Option<String>,
+    /// Build the archive in memory and return it base64-encoded instead of saving it to `target_zip_file`. Defaults to false.
+    pub in_memory: Option<bool>,
+    /// Add the files to `target_zip_file` if it already exists, instead of failing. Defaults to false.
+    pub append: Option<bool>,
+    /// When appending, skip files whose name already exists in the archive instead of failing. Defaults to false.
+    pub skip_duplicates: Option<bool>,
+    /// An optional token to receive `notifications/progress` updates with the number of files processed so far.
+    pub progress_token: Option<String>,
 }
 
 impl ZipFilesTool {
     pub async fn run_tool(
         params: Self,
         context: &FileSystemService,
+        progress: Option<ProgressCallback>,
     ) -> std::result::Result<CallToolResult, CallToolError> {
+        let target_zip_file = zip_target(params.target_zip_file, params.in_memory)?;
+        let Some(target_zip_file) = target_zip_file else {
+            let bytes = context
+                .zip_files_in_memory(
+                    &params.input_files,
+                    params.skip_duplicates.unwrap_or(false),
+                    progress,
+                )
+                .await
+                .map_err(CallToolError::new)?;
+            return Ok(in_memory_zip_result(&bytes));
+        };
         let result_content = context
-            .zip_files(params.input_files, params.target_zip_file)
+            .zip_files(
+                params.input_files,
+                target_zip_file,
+                params.append.unwrap_or(false),
+                params.skip_duplicates.unwrap_or(false),
+                progress,
+            )
             .await
             .map_err(CallToolError::new)?;
         //TODO: return resource?
@@ -49,15 +82,18 @@ pub struct UnzipFileTool {
     pub zip_file: String,
     /// The **absolute path** to the target directory where the contents of the ZIP file will be extracted. This directory will be created if it doesn't exist.
     pub target_path: String,
+    /// An optional token to receive `notifications/progress` updates with the number of files processed so far.
+    pub progress_token: Option<String>,
 }
 
 impl UnzipFileTool {
     pub async fn run_tool(
         params: Self,
         context: &FileSystemService,
+        progress: Option<ProgressCallback>,
     ) -> std::result::Result<CallToolResult, CallToolError> {
         let result_content = context
-            .unzip_file(&params.zip_file, &params.target_path)
+            .unzip_file(&params.zip_file, &params.target_path, progress)
             .await
             .map_err(CallToolError::new)?;
         //TODO: return resource?
@@ -69,6 +105,8 @@ impl UnzipFileTool {
     name = "zip_directory",
     description = concat!("Creates a ZIP archive from the contents of an entire directory, optionally filtering by a glob pattern. ",
     "Includes files and subdirectories. The resulting ZIP file is saved to `target_zip_file`. ",
+    "Entry names are relative to `input_directory`; set `root_prefix` (e.g., `project`) to store them under a top-level folder so they extract into it. ",
+    "Alternatively, set `in_memory` instead of `target_zip_file` to get the archive back as base64 without writing it to disk; this is limited to small archives (8 MiB of input). ",
     "IMPORTANT: The `input_directory` and `target_zip_file` paths MUST be absolute paths. Relative paths are not supported. ",
     "Both the source directory and the target ZIP file location must be within pre-configured allowed directories on the server.")
 )]
@@ -76,23 +114,87 @@ impl UnzipFileTool {
 pub struct ZipDirectoryTool {
     /// The **absolute path** to the directory whose contents will be zipped.
     pub input_directory: String,
-    /// An optional glob pattern (e.g., `*.log`, `**/*.txt`) to filter which files and subdirectories are included. Defaults to `**/*` (all contents) if omitted or null.
+    /// An optional glob pattern (e.g., `*.log`, `**/*.txt`, `*.{rs,toml}`) to filter which files and subdirectories are included. Defaults to `**/*` (all contents) if omitted or null.
     pub pattern: Option<String>,
-    /// The **absolute path** (including filename and .zip extension) where the generated ZIP archive will be saved.
-    pub target_zip_file: String,
+    /// The **absolute path** (including filename and .zip extension) where the generated ZIP archive will be saved. Required unless `in_memory` is true.
+    pub target_zip_file: Option<String>,
+    /// Build the archive in memory and return it base64-encoded instead of saving it to `target_zip_file`. Defaults to false.
+    pub in_memory: Option<bool>,
+    /// A folder path (e.g., `project`) prepended to every entry name, so that the archive extracts into that folder.
+    pub root_prefix: Option<String>,
+    /// An optional token to receive `notifications/progress` updates with the number of files processed so far.
+    pub progress_token: Option<String>,
 }
 
 impl ZipDirectoryTool {
     pub async fn run_tool(
         params: Self,
         context: &FileSystemService,
+        progress: Option<ProgressCallback>,
     ) -> std::result::Result<CallToolResult, CallToolError> {
         let pattern = params.pattern.unwrap_or("**/*".to_string());
+        let target_zip_file = zip_target(params.target_zip_file, params.in_memory)?;
+        let Some(target_zip_file) = target_zip_file else {
+            let bytes = context
+                .zip_directory_in_memory(
+                    &params.input_directory,
+                    &pattern,
+                    params.root_prefix.as_deref(),
+                    progress,
+                )
+                .await
+                .map_err(CallToolError::new)?;
+            return Ok(in_memory_zip_result(&bytes));
+        };
         let result_content = context
-            .zip_directory(params.input_directory, pattern, params.target_zip_file)
+            .zip_directory(
+                params.input_directory,
+                pattern,
+                target_zip_file,
+                params.root_prefix.as_deref(),
+                progress,
+            )
             .await
             .map_err(CallToolError::new)?;
         //TODO: return resource?
         Ok(CallToolResult::text_content(result_content, None))
     }
 }
+
+/// Checks that exactly one of `target_zip_file` and `in_memory` is given, returning the target
+/// file, or `None` when the archive is to be built in memory.
+fn zip_target(
+    target_zip_file: Option<String>,
+    in_memory: Option<bool>,
+) -> Result<Option<String>, CallToolError> {
+    match (target_zip_file, in_memory.unwrap_or(false)) {
+        (Some(target_zip_file), false) => Ok(Some(target_zip_file)),
+        (None, true) => Ok(None),
+        (Some(_), true) => Err(CallToolError::new(ServiceError::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "`target_zip_file` can't be used together with `in_memory`.",
+        )))),
+        (None, false) => Err(CallToolError::new(ServiceError::from(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "Either `target_zip_file` or `in_memory` must be given.",
+        )))),
+    }
+}
+
+fn in_memory_zip_result(bytes: &[u8]) -> CallToolResult {
+    CallToolResult {
+        content: vec![
+            TextContent::new(
+                format!(
+                    "Successfully compressed into an in-memory archive ({}), returned base64-encoded.",
+                    format_bytes(bytes.len() as u64)
+                ),
+                None,
+            )
+            .into(),
+            TextContent::new(BASE64_STANDARD.encode(bytes), None).into(),
+        ],
+        is_error: None,
+        meta: None,
+    }
+}
@@ -0,0 +1,86 @@
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::macros::{mcp_tool, JsonSchema};
+
+use crate::fs_service::FileSystemService;
+
+#[mcp_tool(
+    name = "zstd_compress",
+    description = concat!("Compresses a single file with zstd into `target_file` (usually ending in .zst). ",
+    "Use `level` to trade speed for ratio: from 1 (fastest) to 22 (smallest), defaulting to 3. ",
+    "The file is streamed through the encoder in bounded buffers. The input file is kept. ",
+    "Fails if `target_file` already exists, unless `overwrite` is true. ",
+    "IMPORTANT: The `input_file` and `target_file` paths MUST be absolute paths. Relative paths are not supported. ",
+    "Both paths must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ZstdCompressTool {
+    /// The **absolute path** of the file to compress.
+    pub input_file: String,
+    /// The **absolute path** (usually ending in `.zst`) where the compressed file will be saved.
+    pub target_file: String,
+    /// Compression level from 1 (fastest) to 22 (smallest). Defaults to 3.
+    pub level: Option<i32>,
+    /// Replace `target_file` if it already exists. Defaults to false.
+    pub overwrite: Option<bool>,
+}
+
+impl ZstdCompressTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let result_content = context
+            .zstd_compress_file(
+                &params.input_file,
+                &params.target_file,
+                params.level,
+                params.overwrite.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(result_content, None))
+    }
+}
+
+#[mcp_tool(
+    name = "zstd_decompress",
+    description = concat!("Decompresses a zstd (.zst) file into `target_file`. ",
+    "The file is streamed through the decoder in bounded buffers. The compressed file is kept. ",
+    "Fails if `target_file` already exists, unless `overwrite` is true. ",
+    "IMPORTANT: The `input_file` and `target_file` paths MUST be absolute paths. Relative paths are not supported. ",
+    "Both paths must be within pre-configured allowed directories on the server."),
+    destructive_hint = false,
+    idempotent_hint = false,
+    open_world_hint = false,
+    read_only_hint = false
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ZstdDecompressTool {
+    /// The **absolute path** of the zstd file to decompress.
+    pub input_file: String,
+    /// The **absolute path** where the decompressed file will be saved.
+    pub target_file: String,
+    /// Replace `target_file` if it already exists. Defaults to false.
+    pub overwrite: Option<bool>,
+}
+
+impl ZstdDecompressTool {
+    pub async fn run_tool(
+        params: Self,
+        context: &FileSystemService,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        let result_content = context
+            .zstd_decompress_file(
+                &params.input_file,
+                &params.target_file,
+                params.overwrite.unwrap_or(false),
+            )
+            .await
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(result_content, None))
+    }
+}
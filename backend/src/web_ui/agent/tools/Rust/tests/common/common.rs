@@ -11,6 +11,9 @@ use rust_mcp_filesystem::{
 };
 use tempfile::TempDir;
 
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+
 pub fn get_temp_dir() -> PathBuf {
     let temp_dir = TempDir::new().unwrap().path().canonicalize().unwrap();
     fs::create_dir_all(&temp_dir).unwrap();
@@ -59,6 +62,15 @@ pub fn create_temp_file_info(content: &[u8]) -> (PathBuf, FileInfo) {
         accessed: metadata.accessed().ok(),
         is_directory: metadata.is_dir(),
         is_file: metadata.is_file(),
+        is_symlink: false,
+        symlink_target: None,
+        mime_type: None,
+        line_ending: None,
+        encoding: None,
+        #[cfg(unix)]
+        uid: metadata.uid(),
+        #[cfg(unix)]
+        gid: metadata.gid(),
         metadata,
     };
     (dir, file_info)
@@ -75,6 +87,15 @@ pub fn create_temp_dir() -> (TempDir, FileInfo) {
         accessed: metadata.accessed().ok(),
         is_directory: metadata.is_dir(),
         is_file: metadata.is_file(),
+        is_symlink: false,
+        symlink_target: None,
+        mime_type: None,
+        line_ending: None,
+        encoding: None,
+        #[cfg(unix)]
+        uid: metadata.uid(),
+        #[cfg(unix)]
+        gid: metadata.gid(),
         metadata,
     };
     (dir, file_info)
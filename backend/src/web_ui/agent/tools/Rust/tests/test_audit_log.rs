@@ -0,0 +1,81 @@
+#[path = "common/common.rs"]
+pub mod common;
+
+use common::{get_temp_dir, parse_args, setup_service};
+use rust_mcp_filesystem::{audit_log::AuditLog, handler::MyServerHandler};
+use std::fs;
+
+#[tokio::test]
+async fn test_audit_log_writes_one_json_line_per_operation() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let log_path = temp_dir.join("audit.log");
+    let file_path = temp_dir.join("dir1").join("test.txt");
+
+    let audit_log = AuditLog::open(&log_path).unwrap();
+
+    let valid_path = service.validate_path(&file_path).unwrap();
+    service
+        .write_file(&valid_path, &"content".to_string(), None)
+        .await
+        .unwrap();
+    audit_log
+        .record("write_file", std::slice::from_ref(&valid_path), None)
+        .unwrap();
+
+    let error = service
+        .read_file(&temp_dir.join("dir1").join("missing.txt"))
+        .await
+        .unwrap_err();
+    audit_log
+        .record("read_file", &[], Some(error.to_string()))
+        .unwrap();
+
+    audit_log.close().await.unwrap();
+
+    let content = fs::read_to_string(&log_path).unwrap();
+    let lines: Vec<serde_json::Value> = content
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+
+    assert_eq!(lines[0]["tool"], "write_file");
+    assert_eq!(lines[0]["success"], true);
+    assert_eq!(
+        lines[0]["paths"][0],
+        valid_path.display().to_string().as_str()
+    );
+    assert!(lines[0]["error"].is_null());
+    assert!(chrono::DateTime::parse_from_rfc3339(lines[0]["timestamp"].as_str().unwrap()).is_ok());
+
+    assert_eq!(lines[1]["tool"], "read_file");
+    assert_eq!(lines[1]["success"], false);
+    assert!(!lines[1]["error"].as_str().unwrap().is_empty());
+
+    // Records after closing are refused, and closing again is harmless
+    assert!(audit_log.record("read_file", &[], None).is_err());
+    audit_log.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_handler_audit_log_flushes_on_close() {
+    let temp_dir = get_temp_dir();
+    let log_path = temp_dir.join("audit.log");
+    let args = [
+        "mcp-server",
+        "--audit-log",
+        log_path.to_str().unwrap(),
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+    let audit_log = handler.audit_log().unwrap();
+
+    for _ in 0..100 {
+        assert_eq!(handler.audit("read_file", &[], None), None);
+    }
+    audit_log.close().await.unwrap();
+    assert_eq!(fs::read_to_string(&log_path).unwrap().lines().count(), 100);
+
+    // A record that can't be written is reported instead of failing
+    assert!(handler.audit("read_file", &[], None).is_some());
+}
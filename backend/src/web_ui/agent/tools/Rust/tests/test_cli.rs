@@ -1,7 +1,7 @@
 #[path = "common/common.rs"]
 pub mod common;
 
-use common::parse_args;
+use common::{create_temp_file, get_temp_dir, parse_args};
 
 #[test]
 fn test_parse_with_single_directory() {
@@ -76,3 +76,166 @@ fn test_invalid_flag() {
         assert_eq!(e.kind(), clap::error::ErrorKind::UnknownArgument);
     }
 }
+
+#[test]
+fn test_parse_with_backup_flag() {
+    let args = ["mcp-server", "--backup", "/path/to/dir"];
+    let result = parse_args(&args).unwrap();
+    assert!(result.backup);
+    assert!(!result.allow_write);
+}
+
+#[test]
+fn test_parse_with_deny_flags() {
+    let args = [
+        "mcp-server",
+        "--deny",
+        "/path/to/dir/.git",
+        "--deny",
+        "**/node_modules",
+        "/path/to/dir",
+    ];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.allowed_directories, vec!["/path/to/dir"]);
+    assert_eq!(result.deny, vec!["/path/to/dir/.git", "**/node_modules"]);
+}
+
+#[test]
+fn test_parse_with_access_suffixes() {
+    let args = ["mcp-server", "/data:ro", "/out:rw"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.allowed_directories, vec!["/data:ro", "/out:rw"]);
+}
+
+#[test]
+fn test_parse_with_audit_log() {
+    let args = [
+        "mcp-server",
+        "--audit-log",
+        "/var/log/mcp-audit.log",
+        "/dir1",
+    ];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.audit_log.as_deref(), Some("/var/log/mcp-audit.log"));
+}
+
+#[test]
+fn test_parse_with_trash_dir() {
+    let args = ["mcp-server", "--trash-dir", "/dir1/.trash", "/dir1"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.trash_dir.as_deref(), Some("/dir1/.trash"));
+}
+
+#[test]
+fn test_parse_with_max_extract_size() {
+    let args = ["mcp-server", "--max-extract-size", "1048576", "/dir1"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.max_extract_size, Some(1048576));
+}
+
+#[test]
+fn test_parse_with_max_concurrent_operations() {
+    let args = ["mcp-server", "--max-concurrent-operations", "16", "/dir1"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.max_concurrent_operations, Some(16));
+}
+
+#[test]
+fn test_parse_with_rate_limit() {
+    let args = ["mcp-server", "--rate-limit", "2.5", "/dir1"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.rate_limit, Some(2.5));
+}
+
+#[test]
+fn test_parse_with_config_only() {
+    let args = ["mcp-server", "--config", "/etc/mcp-filesystem.toml"];
+    let result = parse_args(&args).unwrap();
+    assert_eq!(result.config.as_deref(), Some("/etc/mcp-filesystem.toml"));
+    assert!(result.allowed_directories.is_empty());
+}
+
+#[test]
+fn test_config_file_merged_with_flags() {
+    let temp_dir = get_temp_dir();
+    let config = create_temp_file(
+        &temp_dir,
+        "config.toml",
+        r#"
+allowed-directories = ["/data", "/projects:ro"]
+allow-write = true
+deny = ["**/.git"]
+trash-dir = "/data/.trash"
+max-extract-size = 1048576
+max-concurrent-operations = 8
+"#,
+    );
+    let args = [
+        "mcp-server",
+        "--config",
+        config.to_str().unwrap(),
+        "--trash-dir",
+        "/data/.bin",
+        "--deny",
+        "**/node_modules",
+        "/extra",
+    ];
+    let result = parse_args(&args).unwrap().with_config_file().unwrap();
+    assert_eq!(
+        result.allowed_directories,
+        vec!["/data", "/projects:ro", "/extra"]
+    );
+    assert!(result.allow_write);
+    assert!(!result.backup);
+    assert_eq!(result.deny, vec!["**/.git", "**/node_modules"]);
+    assert_eq!(result.trash_dir.as_deref(), Some("/data/.bin"));
+    assert_eq!(result.max_extract_size, Some(1048576));
+    assert_eq!(result.max_concurrent_operations, Some(8));
+}
+
+#[test]
+fn test_config_file_switches_overridden_by_cli() {
+    let temp_dir = get_temp_dir();
+    let config = create_temp_file(
+        &temp_dir,
+        "config.toml",
+        "allowed-directories = [\"/data\"]\nallow-write = true\nbackup = true\n",
+    );
+    let config = config.to_str().unwrap();
+
+    let args = ["mcp-server", "--config", config];
+    let result = parse_args(&args).unwrap().with_config_file().unwrap();
+    assert!(result.allow_write);
+    assert!(result.backup);
+
+    let args = [
+        "mcp-server",
+        "--config",
+        config,
+        "--no-allow-write",
+        "--no-backup",
+    ];
+    let result = parse_args(&args).unwrap().with_config_file().unwrap();
+    assert!(!result.allow_write);
+    assert!(!result.backup);
+
+    // The last of two conflicting flags wins
+    let args = ["mcp-server", "--no-allow-write", "-w", "/data"];
+    let result = parse_args(&args).unwrap();
+    assert!(result.allow_write);
+    assert!(!result.no_allow_write);
+}
+
+#[test]
+fn test_config_file_parse_error() {
+    let temp_dir = get_temp_dir();
+    let config = create_temp_file(&temp_dir, "config.toml", "allowed-dirs = [\"/data\"]\n");
+    let args = ["mcp-server", "--config", config.to_str().unwrap()];
+    let err = parse_args(&args)
+        .unwrap()
+        .with_config_file()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid config file"));
+    assert!(err.contains("allowed-dirs"));
+}
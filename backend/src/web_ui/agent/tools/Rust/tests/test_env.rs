@@ -0,0 +1,35 @@
+#[path = "common/common.rs"]
+pub mod common;
+
+use common::parse_args;
+use rust_mcp_filesystem::cli::{split_allowed_directories, ALLOWED_DIRS_ENV};
+
+// Kept in its own test binary since it modifies the process environment.
+#[test]
+fn test_allowed_directories_from_env() {
+    std::env::set_var(ALLOWED_DIRS_ENV, "/data:/projects:ro::/out:rw");
+
+    let result = parse_args(&["mcp-server"])
+        .unwrap()
+        .with_env_allowed_directories();
+    assert_eq!(
+        result.allowed_directories,
+        vec!["/data", "/projects:ro", "/out:rw"]
+    );
+
+    // Positional directories take precedence over the environment variable
+    let result = parse_args(&["mcp-server", "/cli"])
+        .unwrap()
+        .with_env_allowed_directories();
+    assert_eq!(result.allowed_directories, vec!["/cli"]);
+
+    std::env::remove_var(ALLOWED_DIRS_ENV);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_split_allowed_directories() {
+    assert_eq!(split_allowed_directories("/a"), vec!["/a"]);
+    assert_eq!(split_allowed_directories("/a:rw:/b"), vec!["/a:rw", "/b"]);
+    assert!(split_allowed_directories("").is_empty());
+}
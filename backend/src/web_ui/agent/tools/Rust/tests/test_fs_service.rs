@@ -8,20 +8,29 @@ use common::create_temp_file_info;
 use common::get_temp_dir;
 use common::setup_service;
 use dirs::home_dir;
-use rust_mcp_filesystem::error::ServiceError;
+use rust_mcp_filesystem::error::{ServiceError, ServiceResult};
 use rust_mcp_filesystem::fs_service::file_info::FileInfo;
+use rust_mcp_filesystem::fs_service::indentation::IndentStyle;
+use rust_mcp_filesystem::fs_service::line_endings::{LineEnding, LineEndingCounts};
 use rust_mcp_filesystem::fs_service::utils::*;
-use rust_mcp_filesystem::fs_service::FileSystemService;
-use rust_mcp_filesystem::tools::EditOperation;
+use rust_mcp_filesystem::fs_service::{
+    AccessLevel, BundleDirectoryOptions, CopyDirectoryOptions, EditOptions, FileEdit,
+    FileSystemService, ListDirectoryOptions, MoveStatus, PathStatus, ProgressCallback,
+    ReplaceInFilesOptions, SearchOptions,
+};
+use rust_mcp_filesystem::tools::{DeleteLinesOperation, EditOperation, InsertOperation};
+use std::collections::HashMap;
 use std::fs::{self, File};
 use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
 use tokio::fs as tokio_fs;
 use tokio_util::compat::TokioAsyncReadCompatExt;
+use tokio_util::sync::CancellationToken;
 
 #[cfg(unix)]
-use std::os::unix::fs::PermissionsExt;
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
 
 #[test]
 fn test_try_new_success() {
@@ -35,9 +44,153 @@ fn test_try_new_success() {
 }
 
 #[test]
-#[should_panic(expected = "Error: /does/not/exist is not a directory")]
 fn test_try_new_invalid_directory() {
-    let _ = FileSystemService::try_new(&["/does/not/exist".to_string()]);
+    let result = FileSystemService::try_new(&["/does/not/exist".to_string()]);
+    let err = result.err().unwrap().to_string();
+    assert!(err.contains("/does/not/exist is not a directory"));
+}
+
+#[test]
+fn test_try_new_reports_all_invalid_directories() {
+    let temp_dir = get_temp_dir();
+    let valid_dir = temp_dir.to_str().unwrap().to_string();
+    let not_a_dir = temp_dir.join("file.txt");
+    File::create(&not_a_dir).unwrap();
+
+    let result = FileSystemService::try_new(&[
+        "/does/not/exist".to_string(),
+        valid_dir,
+        not_a_dir.to_str().unwrap().to_string(),
+        "/also/missing".to_string(),
+    ]);
+    let err = result.err().unwrap().to_string();
+    assert!(err.contains("/does/not/exist is not a directory"));
+    assert!(err.contains(&format!("{} is not a directory", not_a_dir.display())));
+    assert!(err.contains("/also/missing is not a directory"));
+}
+
+#[test]
+fn test_try_new_glob_pattern() {
+    let temp_dir = get_temp_dir();
+    let projects = temp_dir.join("projects");
+    fs::create_dir_all(projects.join("alpha").join("data")).unwrap();
+    fs::create_dir_all(projects.join("beta").join("data")).unwrap();
+    fs::create_dir_all(projects.join("gamma")).unwrap();
+
+    let pattern = projects.join("*").join("data");
+    let service = FileSystemService::try_new(&[pattern.to_str().unwrap().to_string()]).unwrap();
+    assert_eq!(
+        service.allowed_directories(),
+        &vec![
+            projects.join("alpha").join("data"),
+            projects.join("beta").join("data")
+        ]
+    );
+}
+
+#[test]
+fn test_try_new_glob_pattern_no_match() {
+    let temp_dir = get_temp_dir();
+    let pattern = temp_dir.join("missing-*").join("data");
+    let result = FileSystemService::try_new(&[pattern.to_str().unwrap().to_string()]);
+    let err = result.err().unwrap().to_string();
+    assert!(err.contains("no directories match the pattern"));
+}
+
+#[tokio::test]
+async fn test_per_directory_access() {
+    let temp_dir = get_temp_dir();
+    let data_dir = temp_dir.join("data");
+    let out_dir = temp_dir.join("out");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::create_dir_all(&out_dir).unwrap();
+    let data_file = create_temp_file(&data_dir, "input.txt", "input");
+
+    let service = FileSystemService::try_new(&[
+        format!("{}:ro", data_dir.display()),
+        format!("{}:rw", out_dir.display()),
+    ])
+    .unwrap();
+    assert_eq!(
+        service.allowed_directories(),
+        &vec![data_dir.clone(), out_dir.clone()]
+    );
+    assert_eq!(
+        service.access_level(&data_file).unwrap(),
+        AccessLevel::ReadOnly
+    );
+    assert_eq!(
+        service.access_level(&out_dir.join("output.txt")).unwrap(),
+        AccessLevel::ReadWrite
+    );
+
+    // Writes succeed in the `rw` directory
+    let output_file = out_dir.join("output.txt");
+    service
+        .write_file(&output_file, &"output".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&output_file).unwrap(), "output");
+
+    // Reads succeed but writes fail in the `ro` directory
+    assert_eq!(service.read_file(&data_file).await.unwrap(), "input");
+    let result = service
+        .write_file(&data_file, &"changed".to_string(), None)
+        .await;
+    assert!(result.unwrap_err().to_string().contains("read-only"));
+    assert!(service
+        .create_directory(&data_dir.join("sub"))
+        .await
+        .is_err());
+    assert!(service
+        .move_file(&data_file, &out_dir.join("moved.txt"), None, None)
+        .await
+        .is_err());
+    assert_eq!(fs::read_to_string(&data_file).unwrap(), "input");
+}
+
+#[tokio::test]
+async fn test_per_directory_access_overrides_default() {
+    let temp_dir = get_temp_dir();
+    let shared_dir = temp_dir.join("shared");
+    let out_dir = temp_dir.join("out");
+    fs::create_dir_all(&shared_dir).unwrap();
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let service = FileSystemService::try_new(&[
+        shared_dir.to_str().unwrap().to_string(),
+        format!("{}:rw", out_dir.display()),
+    ])
+    .unwrap()
+    .with_default_access(AccessLevel::ReadOnly);
+
+    assert!(service.has_writable_directories());
+    assert!(service.validate_write_path(&out_dir.join("a.txt")).is_ok());
+    assert!(service
+        .validate_write_path(&shared_dir.join("a.txt"))
+        .is_err());
+    assert!(service.validate_path(&shared_dir.join("a.txt")).is_ok());
+}
+
+#[test]
+fn test_error_codes_for_access_denied_and_read_only() {
+    let temp_dir = get_temp_dir();
+    let shared_dir = temp_dir.join("shared");
+    fs::create_dir_all(&shared_dir).unwrap();
+    let service = FileSystemService::try_new(&[format!("{}:ro", shared_dir.display())]).unwrap();
+
+    let denied = service
+        .validate_path(&temp_dir.join("other").join("a.txt"))
+        .unwrap_err();
+    assert_eq!(denied.code(), "ACCESS_DENIED");
+    assert!(denied.to_string().starts_with("Access denied - "));
+
+    let read_only = service
+        .validate_write_path(&shared_dir.join("a.txt"))
+        .unwrap_err();
+    assert!(matches!(read_only, ServiceError::ReadOnlyDirectory(_)));
+    assert_eq!(read_only.code(), "READ_ONLY");
+    assert_eq!(ServiceError::NoWriteAccess.code(), "READ_ONLY");
 }
 
 #[test]
@@ -63,7 +216,173 @@ async fn test_validate_path_denied() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let outside_path = temp_dir.join("dir2").join("test.txt");
     let result = service.validate_path(&outside_path);
-    assert!(matches!(result, Err(ServiceError::FromString(_))));
+    assert!(matches!(
+        result,
+        Err(ServiceError::OutsideAllowedDirectories { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_validate_path_denied_subdirectory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let secrets = dir_path.join("secrets");
+    fs::create_dir_all(&secrets).unwrap();
+    let service = service
+        .with_denied(&[secrets.to_str().unwrap().to_string()])
+        .unwrap();
+
+    let secret_file = create_temp_file(&secrets, "key.pem", "secret");
+    let sibling_file = create_temp_file(&dir_path, "notes.txt", "notes");
+
+    assert!(matches!(
+        service.validate_path(&secret_file),
+        Err(ServiceError::AccessDenied(_))
+    ));
+    assert!(service.validate_path(&secrets).is_err());
+    assert!(service.validate_path(&secrets.join("new.txt")).is_err());
+    assert!(service.validate_path(&sibling_file).is_ok());
+    assert!(service.validate_path(&dir_path.join("secrets-old")).is_ok());
+}
+
+#[tokio::test]
+async fn test_validate_path_denied_glob() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("repo").join(".git")).unwrap();
+    let service = service.with_denied(&["**/.git".to_string()]).unwrap();
+
+    let git_file = create_temp_file(&dir_path.join("repo").join(".git"), "HEAD", "ref");
+    let source_file = create_temp_file(&dir_path.join("repo"), "main.rs", "fn main() {}");
+
+    assert!(service.validate_path(&git_file).is_err());
+    assert!(service.validate_path(&source_file).is_ok());
+    assert!(service
+        .validate_path(&dir_path.join("repo").join(".gitignore"))
+        .is_ok());
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_validate_path_long_nested_path() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let mut long_dir = temp_dir.join("dir1");
+    for index in 0..12 {
+        long_dir = long_dir.join(format!("nested_directory_level_{:02}", index));
+    }
+    fs::create_dir_all(&long_dir).unwrap();
+    let file_path = create_temp_file(&long_dir, "test.txt", "content");
+    assert!(file_path.as_os_str().len() > 260);
+
+    assert!(service.validate_path(&file_path).is_ok());
+    assert!(service.validate_path(&long_dir.join("new.txt")).is_ok());
+}
+
+#[cfg(windows)]
+#[tokio::test]
+async fn test_validate_path_verbatim_allowed_dir() {
+    let temp_dir = get_temp_dir();
+    let dir_path = dunce::simplified(&temp_dir).join("dir1");
+    fs::create_dir_all(&dir_path).unwrap();
+    let verbatim_dir = format!(r"\\?\{}", dir_path.display());
+
+    let service = FileSystemService::try_new(&[verbatim_dir]).unwrap();
+    assert_eq!(service.allowed_directories(), &vec![dir_path.clone()]);
+
+    let file_path = create_temp_file(&dir_path, "test.txt", "content");
+    assert!(service.validate_path(&file_path).is_ok());
+    let verbatim_file = PathBuf::from(format!(r"\\?\{}", file_path.display()));
+    assert!(service.validate_path(&verbatim_file).is_ok());
+    assert!(service
+        .validate_path(&dunce::simplified(&temp_dir).join("dir2").join("test.txt"))
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_validate_path_parent_dir_escape_denied() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    // Neither the target file nor the `secret` directory exist, so canonicalization fails
+    let escape_path = temp_dir
+        .join("dir1")
+        .join("..")
+        .join("secret")
+        .join("new.txt");
+    let result = service.validate_path(&escape_path);
+    assert!(matches!(
+        result,
+        Err(ServiceError::OutsideAllowedDirectories { .. })
+    ));
+
+    let nested_escape = temp_dir
+        .join("dir1")
+        .join("missing")
+        .join("..")
+        .join("..")
+        .join("new.txt");
+    let result = service.validate_path(&nested_escape);
+    assert!(matches!(
+        result,
+        Err(ServiceError::OutsideAllowedDirectories { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_validate_path_parent_dir_within_allowed() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let inner_path = temp_dir
+        .join("dir1")
+        .join("missing")
+        .join("..")
+        .join("new.txt");
+    assert!(service.validate_path(&inner_path).is_ok());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_validate_path_symlink_outside_allowed() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let outside = temp_dir.join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    create_temp_file(&outside, "secret.txt", "secret");
+    let link = temp_dir.join("dir1").join("escape");
+    std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+    let err = service.validate_path(&link.join("secret.txt")).unwrap_err();
+    assert!(matches!(
+        err,
+        ServiceError::SymlinkOutsideAllowedDirectories { .. }
+    ));
+    assert!(err
+        .to_string()
+        .contains("a symlink target path is outside allowed directories"));
+}
+
+#[tokio::test]
+async fn test_missing_path_is_not_found() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let missing = temp_dir.join("dir1").join("missing.txt");
+
+    let err = service.read_file(&missing).await.unwrap_err();
+    assert!(matches!(err, ServiceError::NotFound(_)));
+    assert_eq!(err.code(), "NOT_FOUND");
+    assert!(err.to_string().contains(missing.to_str().unwrap()));
+
+    let err = service
+        .get_file_stats(&missing, None, None)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, ServiceError::NotFound(_)));
+
+    // A path outside the allowed directories is denied whether or not it exists
+    let err = service
+        .read_file(&temp_dir.join("missing.txt"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        ServiceError::OutsideAllowedDirectories { .. }
+    ));
+    assert_eq!(err.code(), "ACCESS_DENIED");
 }
 
 #[test]
@@ -99,7 +418,10 @@ fn test_contains_symlink_with_symlink() {
 async fn test_get_file_stats() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "test.txt", "content");
-    let result = service.get_file_stats(&file_path).await.unwrap();
+    let result = service
+        .get_file_stats(&file_path, None, None)
+        .await
+        .unwrap();
     assert_eq!(result.size, 7); // "content" is 7 bytes
     assert!(result.is_file);
     assert!(!result.is_directory);
@@ -108,6 +430,161 @@ async fn test_get_file_stats() {
     assert!(result.accessed.is_some());
 }
 
+#[cfg(unix)]
+#[tokio::test]
+async fn test_get_file_stats_owner() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "owned.txt", "content");
+    let current_uid = uzers::get_current_uid();
+
+    let result = service
+        .get_file_stats(&file_path, None, None)
+        .await
+        .unwrap();
+    assert_eq!(result.uid, current_uid);
+    assert!(result
+        .to_string()
+        .contains(&format!("owner: {}", current_uid)));
+    assert!(result.to_string().contains("group: "));
+}
+
+#[tokio::test]
+async fn test_detect_text_format() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+
+    let lf = create_temp_file(&dir_path, "lf.txt", "one\ntwo\n");
+    let crlf = create_temp_file(&dir_path, "crlf.txt", "one\r\ntwo\r\n");
+    let mixed = create_temp_file(&dir_path, "mixed.txt", "one\r\ntwo\nthree");
+    let single_line = create_temp_file(&dir_path, "single.txt", "one");
+    let latin1 = dir_path.join("latin1.txt");
+    fs::write(&latin1, b"caf\xe9 cr\xe8me br\xfbl\xe9e\r\n").unwrap();
+
+    let format = |path: PathBuf| {
+        let service = &service;
+        async move { service.detect_text_format(&path).await.unwrap() }
+    };
+    assert_eq!(format(lf).await, ("UTF-8", Some(LineEnding::Lf)));
+    assert_eq!(format(crlf).await, ("UTF-8", Some(LineEnding::Crlf)));
+    assert_eq!(format(mixed).await, ("UTF-8", Some(LineEnding::Mixed)));
+    assert_eq!(format(single_line).await, ("UTF-8", None));
+    assert_eq!(
+        format(latin1).await,
+        ("windows-1252", Some(LineEnding::Crlf))
+    );
+}
+
+#[test]
+fn test_line_ending_classification() {
+    let style = |text: &str| LineEndingCounts::count(text).style();
+    assert_eq!(style("one\ntwo\n"), Some(LineEnding::Lf));
+    assert_eq!(style("one\r\ntwo\r\n"), Some(LineEnding::Crlf));
+    assert_eq!(style("one\rtwo\r"), Some(LineEnding::Cr));
+    assert_eq!(style("one\r\ntwo\nthree\r"), Some(LineEnding::Mixed));
+    assert_eq!(style("no line break"), None);
+    assert_eq!(
+        LineEndingCounts::count("a\r\nb\nc\rd\r\n"),
+        LineEndingCounts {
+            lf: 1,
+            crlf: 2,
+            cr: 1
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_get_file_stats_detect_mime() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+
+    // PNG signature without a matching extension, so detection must come from the content
+    let png_path = dir_path.join("image.bin");
+    fs::write(
+        &png_path,
+        [
+            0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0x0D,
+        ],
+    )
+    .unwrap();
+    let png_info = service
+        .get_file_stats(&png_path, Some(true), None)
+        .await
+        .unwrap();
+    assert_eq!(png_info.mime_type.as_deref(), Some("image/png"));
+    assert!(png_info.to_string().contains("mimeType: image/png"));
+
+    let text_path = create_temp_file(&dir_path, "notes.txt", "plain text");
+    let text_info = service
+        .get_file_stats(&text_path, Some(true), None)
+        .await
+        .unwrap();
+    assert_eq!(text_info.mime_type.as_deref(), Some("text/plain"));
+
+    let not_requested = service
+        .get_file_stats(&text_path, None, None)
+        .await
+        .unwrap();
+    assert!(not_requested.mime_type.is_none());
+    assert!(!not_requested.to_string().contains("mimeType"));
+}
+
+// Symlink test is platform-dependent , it require administrator privileges on some systems
+#[cfg(unix)]
+#[tokio::test]
+async fn test_get_file_stats_symlink() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let target_path = create_temp_file(&dir_path, "target.txt", "content");
+    let link_path = dir_path.join("link.txt");
+    std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+
+    let link_info = service
+        .get_file_stats(&link_path, None, None)
+        .await
+        .unwrap();
+    assert!(link_info.is_symlink);
+    assert_eq!(link_info.symlink_target, Some(target_path.clone()));
+    assert!(link_info.is_file);
+    assert!(link_info
+        .to_string()
+        .contains(&format!("symlinkTarget: {}", target_path.display())));
+
+    let target_info = service
+        .get_file_stats(&target_path, None, None)
+        .await
+        .unwrap();
+    assert!(!target_info.is_symlink);
+    assert!(target_info.symlink_target.is_none());
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_get_file_stats_without_following_symlinks() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let target_path = create_temp_file(&dir_path, "target.txt", "a longer file content");
+    let link_path = dir_path.join("link.txt");
+    std::os::unix::fs::symlink(&target_path, &link_path).unwrap();
+
+    let followed = service
+        .get_file_stats(&link_path, None, Some(true))
+        .await
+        .unwrap();
+    assert!(followed.is_file);
+    assert_eq!(followed.size, 21);
+
+    let not_followed = service
+        .get_file_stats(&link_path, None, Some(false))
+        .await
+        .unwrap();
+    assert!(not_followed.is_symlink);
+    assert!(!not_followed.is_file);
+    assert!(!not_followed.is_directory);
+    // A symlink's own size is the length of its target path
+    assert_eq!(not_followed.size, target_path.as_os_str().len() as u64);
+    assert_eq!(not_followed.symlink_target, Some(target_path));
+}
+
 #[tokio::test]
 async fn test_zip_directory() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
@@ -121,6 +598,8 @@ async fn test_zip_directory() {
             dir_path.to_str().unwrap().to_string(),
             "*.txt".to_string(),
             zip_path.to_str().unwrap().to_string(),
+            None,
+            None,
         )
         .await
         .unwrap();
@@ -130,769 +609,3978 @@ async fn test_zip_directory() {
 }
 
 #[tokio::test]
-async fn test_zip_directory_already_exists() {
+async fn test_zip_directory_entry_names_use_forward_slashes() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let dir_path = temp_dir.join("dir1");
-    let zip_path = create_temp_file(&dir_path, "output.zip", "dummy");
-    let result = service
+    let source_dir = dir_path.join("source");
+    let nested_dir = source_dir.join("nested").join("deeper");
+    fs::create_dir_all(&nested_dir).unwrap();
+    create_temp_file(&source_dir, "top.txt", "top");
+    create_temp_file(&nested_dir, "inner.txt", "inner");
+    let zip_path = dir_path.join("output.zip");
+
+    service
         .zip_directory(
-            dir_path.to_str().unwrap().to_string(),
+            source_dir.to_str().unwrap().to_string(),
             "*.txt".to_string(),
             zip_path.to_str().unwrap().to_string(),
+            None,
+            None,
         )
-        .await;
-    assert!(matches!(
-        result,
-        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::AlreadyExists
-    ));
+        .await
+        .unwrap();
+
+    let zip = async_zip::tokio::read::fs::ZipFileReader::new(&zip_path)
+        .await
+        .unwrap();
+    let mut names: Vec<String> = zip
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| entry.filename().as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["nested/deeper/inner.txt", "top.txt"]);
 }
 
 #[tokio::test]
-async fn test_zip_files() {
+async fn test_zip_directory_root_prefix() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let dir_path = temp_dir.join("dir1");
-
-    let file1 = create_temp_file(dir_path.as_path(), "file1.txt", "content1");
-    let file2 = create_temp_file(dir_path.as_path(), "file2.txt", "content2");
+    let source_dir = dir_path.join("source");
+    fs::create_dir_all(source_dir.join("nested")).unwrap();
+    create_temp_file(&source_dir, "top.txt", "top");
+    create_temp_file(&source_dir.join("nested"), "inner.txt", "inner");
     let zip_path = dir_path.join("output.zip");
-    let result = service
-        .zip_files(
-            vec![
-                file1.to_str().unwrap().to_string(),
-                file2.to_str().unwrap().to_string(),
-            ],
+
+    let invalid = service
+        .zip_directory(
+            source_dir.to_str().unwrap().to_string(),
+            "*.txt".to_string(),
+            zip_path.to_str().unwrap().to_string(),
+            Some("../escape"),
+            None,
+        )
+        .await;
+    assert!(invalid.is_err());
+
+    service
+        .zip_directory(
+            source_dir.to_str().unwrap().to_string(),
+            "*.txt".to_string(),
             zip_path.to_str().unwrap().to_string(),
+            Some("/project/"),
+            None,
         )
         .await
         .unwrap();
-    assert!(zip_path.exists());
-    assert!(result.contains("Successfully compressed 2 files"));
-    assert!(result.contains("output.zip"));
-}
 
-#[tokio::test]
-async fn test_zip_files_empty_input() {
-    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let zip_path = temp_dir.join("output.zip");
-    let result = service
-        .zip_files(vec![], zip_path.to_str().unwrap().to_string())
-        .await;
-    assert!(matches!(
-        result,
-        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::InvalidInput
-    ));
+    let zip = async_zip::tokio::read::fs::ZipFileReader::new(&zip_path)
+        .await
+        .unwrap();
+    let mut names: Vec<String> = zip
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| entry.filename().as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["project/nested/inner.txt", "project/top.txt"]);
+
+    let extract_dir = dir_path.join("extracted");
+    service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("project/nested/inner.txt")).unwrap(),
+        "inner"
+    );
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("project/top.txt")).unwrap(),
+        "top"
+    );
 }
 
 #[tokio::test]
-async fn test_unzip_file() {
+async fn test_zip_directory_keeps_empty_directories() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let dir_path = temp_dir.join("dir1");
-    let file1 = create_temp_file(&dir_path, "file1.txt", "content1");
+    let source_dir = dir_path.join("source");
+    fs::create_dir_all(source_dir.join("empty")).unwrap();
+    fs::create_dir_all(source_dir.join("nested").join("also_empty")).unwrap();
+    create_temp_file(&source_dir, "file.txt", "content");
     let zip_path = dir_path.join("output.zip");
+
     service
-        .zip_files(
-            vec![file1.to_str().unwrap().to_string()],
+        .zip_directory(
+            source_dir.to_str().unwrap().to_string(),
+            "**/*".to_string(),
             zip_path.to_str().unwrap().to_string(),
+            None,
+            None,
         )
         .await
         .unwrap();
+
     let extract_dir = dir_path.join("extracted");
-    let result = service
-        .unzip_file(zip_path.to_str().unwrap(), extract_dir.to_str().unwrap())
+    service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
         .await
         .unwrap();
-    assert!(extract_dir.join("file1.txt").exists());
-    assert!(result.contains("Successfully extracted 1 file"));
+
+    assert!(extract_dir.join("empty").is_dir());
+    assert!(extract_dir.join("nested").join("also_empty").is_dir());
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("file.txt")).unwrap(),
+        "content"
+    );
 }
 
-#[tokio::test]
-async fn test_unzip_file_non_existent() {
+#[test]
+fn test_format_system_time_rfc3339_round_trip() {
+    let time = SystemTime::UNIX_EPOCH + std::time::Duration::new(1_744_468_245, 123_456_789);
+    let formatted = format_system_time_rfc3339(time);
+    assert!(formatted.starts_with("2025-04-1"));
+    assert_eq!(parse_rfc3339(&formatted).unwrap(), time);
+}
+
+#[test]
+fn test_is_probably_binary() {
+    let temp_dir = get_temp_dir();
+    let text = create_temp_file(
+        &temp_dir,
+        "notes.txt",
+        "plain text\nwith unicode: héllo ✓\n",
+    );
+    assert!(!is_probably_binary(&text).unwrap());
+
+    let with_nul = temp_dir.join("data.bin");
+    fs::write(&with_nul, b"header\0\0\x01\x02 and some text").unwrap();
+    assert!(is_probably_binary(&with_nul).unwrap());
+
+    let invalid_utf8 = temp_dir.join("noise.bin");
+    fs::write(&invalid_utf8, [0xff, 0xfe, 0xfd, b'a', 0xc3, 0x28, 0xff]).unwrap();
+    assert!(is_probably_binary(&invalid_utf8).unwrap());
+
+    let empty = create_temp_file(&temp_dir, "empty.txt", "");
+    assert!(!is_probably_binary(&empty).unwrap());
+}
+
+#[test]
+fn test_zip_entry_name() {
+    let base = Path::new("/data/source");
+    let path = base.join("nested").join("file.txt");
+    assert_eq!(zip_entry_name(&path, base).unwrap(), "nested/file.txt");
+    assert!(zip_entry_name(Path::new("/other/file.txt"), base).is_err());
+}
+
+#[tokio::test]
+async fn test_zip_directory_already_exists() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let temp_dir = temp_dir.join("dir1");
-    let zip_path = temp_dir.join("non_existent.zip");
-    let extract_dir = temp_dir.join("extracted");
+    let dir_path = temp_dir.join("dir1");
+    let zip_path = create_temp_file(&dir_path, "output.zip", "dummy");
     let result = service
-        .unzip_file(zip_path.to_str().unwrap(), extract_dir.to_str().unwrap())
+        .zip_directory(
+            dir_path.to_str().unwrap().to_string(),
+            "*.txt".to_string(),
+            zip_path.to_str().unwrap().to_string(),
+            None,
+            None,
+        )
         .await;
-
     assert!(matches!(
         result,
-        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::NotFound
+        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::AlreadyExists
     ));
 }
 
 #[tokio::test]
-async fn test_read_file() {
+async fn test_zip_files() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "test.txt", "content");
-    let content = service.read_file(&file_path).await.unwrap();
-    assert_eq!(content, "content");
+    let dir_path = temp_dir.join("dir1");
+
+    let file1 = create_temp_file(dir_path.as_path(), "file1.txt", "content1");
+    let file2 = create_temp_file(dir_path.as_path(), "file2.txt", "content2");
+    let zip_path = dir_path.join("output.zip");
+    let result = service
+        .zip_files(
+            vec![
+                file1.to_str().unwrap().to_string(),
+                file2.to_str().unwrap().to_string(),
+            ],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(zip_path.exists());
+    assert!(result.contains("Successfully compressed 2 files"));
+    assert!(result.contains("output.zip"));
 }
 
 #[tokio::test]
-async fn test_create_directory() {
+async fn test_zip_files_in_memory() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let new_dir = temp_dir.join("dir1").join("new_dir");
-    let result = service.create_directory(&new_dir).await;
+    let dir_path = temp_dir.join("dir1");
 
-    assert!(result.is_ok());
-    assert!(new_dir.is_dir());
-}
+    let file1 = create_temp_file(dir_path.as_path(), "file1.txt", "content1");
+    let file2 = create_temp_file(dir_path.as_path(), "file2.txt", "content2");
+    let bytes = service
+        .zip_files_in_memory(
+            &[
+                file1.to_str().unwrap().to_string(),
+                file2.to_str().unwrap().to_string(),
+            ],
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(fs::read_dir(&dir_path).unwrap().count(), 2);
 
-#[tokio::test]
-async fn test_move_file() {
-    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let src_path = create_temp_file(temp_dir.join("dir1").as_path(), "src.txt", "content");
-    let dest_path = temp_dir.join("dir1").join("dest.txt");
-    let result = service.move_file(&src_path, &dest_path).await;
-    assert!(result.is_ok());
-    assert!(!src_path.exists());
-    assert!(dest_path.exists());
+    let zip = async_zip::base::read::mem::ZipFileReader::new(bytes)
+        .await
+        .unwrap();
+    let mut extracted = vec![];
+    for index in 0..zip.file().entries().len() {
+        let name = zip.file().entries()[index]
+            .filename()
+            .as_str()
+            .unwrap()
+            .to_string();
+        let mut content = String::new();
+        zip.reader_with_entry(index)
+            .await
+            .unwrap()
+            .read_to_string_checked(&mut content)
+            .await
+            .unwrap();
+        extracted.push((name, content));
+    }
+    assert_eq!(
+        extracted,
+        vec![
+            ("file1.txt".to_string(), "content1".to_string()),
+            ("file2.txt".to_string(), "content2".to_string()),
+        ]
+    );
 }
 
 #[tokio::test]
-async fn test_list_directory() {
+async fn test_zip_files_reports_progress() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let dir_path = temp_dir.join("dir1");
-    create_temp_file(&dir_path, "file1.txt", "content1");
-    create_temp_file(&dir_path, "file2.txt", "content2");
-    let entries = service.list_directory(&dir_path).await.unwrap();
-    let names: Vec<_> = entries
-        .into_iter()
-        .map(|e| e.file_name().to_str().unwrap().to_string())
+    let input_files: Vec<String> = (1..=3)
+        .map(|i| {
+            let file = create_temp_file(&dir_path, &format!("file{i}.txt"), "content");
+            file.to_str().unwrap().to_string()
+        })
         .collect();
-    assert_eq!(names.len(), 2);
-    assert!(names.contains(&"file1.txt".to_string()));
-    assert!(names.contains(&"file2.txt".to_string()));
-}
+    let zip_path = dir_path.join("output.zip");
 
-#[tokio::test]
-async fn test_write_file() {
-    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = temp_dir.join("dir1").join("test.txt");
-    let content = "new content".to_string();
-    let result = service.write_file(&file_path, &content).await;
-    assert!(result.is_ok());
-    assert_eq!(tokio_fs::read_to_string(&file_path).await.unwrap(), content);
+    let calls = Arc::new(Mutex::new(vec![]));
+    let recorded = calls.clone();
+    let progress: ProgressCallback = Arc::new(move |processed, total| {
+        recorded.lock().unwrap().push((processed, total));
+    });
+    service
+        .zip_files(
+            input_files,
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            Some(progress.clone()),
+        )
+        .await
+        .unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![(1, 3), (2, 3), (3, 3)]);
+
+    calls.lock().unwrap().clear();
+    let extract_dir = dir_path.join("extracted");
+    service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            Some(progress),
+        )
+        .await
+        .unwrap();
+    assert_eq!(*calls.lock().unwrap(), vec![(1, 3), (2, 3), (3, 3)]);
 }
 
-#[test]
-fn test_search_files() {
+#[tokio::test]
+async fn test_zip_files_append() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let dir_path = temp_dir.join("dir1");
-    create_temp_file(&dir_path, "test1.txt", "content");
-    create_temp_file(&dir_path, "test2.doc", "content");
+    let file1 = create_temp_file(&dir_path, "file1.txt", "content1");
+    let file2 = create_temp_file(&dir_path, "file2.txt", "content2");
+    let zip_path = dir_path.join("output.zip");
+    let zip_file = zip_path.to_str().unwrap().to_string();
+
+    service
+        .zip_files(
+            vec![file1.to_str().unwrap().to_string()],
+            zip_file.clone(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Without `append`, an existing archive is an error
+    let result = service
+        .zip_files(
+            vec![file2.to_str().unwrap().to_string()],
+            zip_file.clone(),
+            false,
+            false,
+            None,
+        )
+        .await;
+    assert!(result.is_err());
+
     let result = service
-        .search_files(&dir_path, "*.txt".to_string(), vec![])
+        .zip_files(
+            vec![file2.to_str().unwrap().to_string()],
+            zip_file.clone(),
+            true,
+            false,
+            None,
+        )
+        .await
         .unwrap();
-    let names: Vec<_> = result
-        .into_iter()
-        .map(|e| e.file_name().to_str().unwrap().to_string())
-        .collect();
-    assert_eq!(names, vec!["test1.txt"]);
-}
+    assert!(result.contains("Successfully appended 1 file"));
 
-#[test]
-fn test_search_files_with_exclude() {
-    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let dir_path = temp_dir.join("dir1");
-    create_temp_file(&dir_path, "test1.txt", "content");
-    create_temp_file(&dir_path, "test2.txt", "content");
+    // Duplicates fail unless they are skipped
     let result = service
-        .search_files(
-            &dir_path,
-            "*.txt".to_string(),
-            vec!["test2.txt".to_string()],
+        .zip_files(
+            vec![file1.to_str().unwrap().to_string()],
+            zip_file.clone(),
+            true,
+            false,
+            None,
+        )
+        .await;
+    assert!(result.is_err());
+    let result = service
+        .zip_files(
+            vec![file1.to_str().unwrap().to_string()],
+            zip_file.clone(),
+            true,
+            true,
+            None,
         )
+        .await
         .unwrap();
-    let names: Vec<_> = result
-        .into_iter()
-        .map(|e| e.file_name().to_str().unwrap().to_string())
-        .collect();
-    assert_eq!(names, vec!["test1.txt"]);
+    assert!(result.contains("Skipped file already in the archive: file1.txt"));
+
+    let extract_dir = dir_path.join("extracted");
+    service
+        .unzip_file(&zip_file, extract_dir.to_str().unwrap(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("file1.txt")).unwrap(),
+        "content1"
+    );
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("file2.txt")).unwrap(),
+        "content2"
+    );
+    assert_eq!(fs::read_dir(&extract_dir).unwrap().count(), 2);
 }
 
-#[test]
-fn test_create_unified_diff() {
-    let (_, service) = setup_service(vec![]);
-    let original = "line1\nline2\nline3".to_string();
-    let new = "line1\nline4\nline3".to_string();
-    let diff = service.create_unified_diff(&original, &new, Some("test.txt".to_string()));
-    assert!(diff.contains("Index: test.txt"));
-    assert!(diff.contains("--- test.txt\toriginal"));
-    assert!(diff.contains("+++ test.txt\tmodified"));
-    assert!(diff.contains("-line2"));
-    assert!(diff.contains("+line4"));
+#[tokio::test]
+async fn test_zip_files_empty_input() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let zip_path = temp_dir.join("output.zip");
+    let result = service
+        .zip_files(
+            vec![],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::InvalidInput
+    ));
 }
 
 #[tokio::test]
-async fn test_apply_file_edits() {
+async fn test_unzip_file() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = create_temp_file(
-        temp_dir.join("dir1").as_path(),
-        "test.txt",
-        "line1\nline2\nline3",
-    );
-    let edits = vec![EditOperation {
-        old_text: "line2".to_string(),
-        new_text: "line4".to_string(),
-    }];
+    let dir_path = temp_dir.join("dir1");
+    let file1 = create_temp_file(&dir_path, "file1.txt", "content1");
+    let zip_path = dir_path.join("output.zip");
+    service
+        .zip_files(
+            vec![file1.to_str().unwrap().to_string()],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+    let extract_dir = dir_path.join("extracted");
     let result = service
-        .apply_file_edits(&file_path, edits, Some(false), None)
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
         .await
         .unwrap();
-    assert!(result.contains("Index:"));
-    assert!(result.contains("-line2"));
-    assert!(result.contains("+line4"));
-    let new_content = tokio_fs::read_to_string(&file_path).await.unwrap();
-    assert_eq!(new_content, "line1\nline4\nline3");
+    assert!(extract_dir.join("file1.txt").exists());
+    assert!(result.contains("Successfully extracted 1 file"));
 }
 
+#[cfg(unix)]
 #[tokio::test]
-async fn test_apply_file_edits_dry_run() {
+async fn test_zip_round_trip_preserves_mode_and_mtime() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = create_temp_file(
-        temp_dir.join("dir1").as_path(),
-        "test.txt",
-        "line1\nline2\nline3",
-    );
-    let edits = vec![EditOperation {
-        old_text: "line2".to_string(),
-        new_text: "line4".to_string(),
-    }];
-    let result = service
-        .apply_file_edits(&file_path, edits, Some(true), None)
+    let dir_path = temp_dir.join("dir1");
+    let script = create_temp_file(&dir_path, "run.sh", "#!/bin/sh\necho hello\n");
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    let modified = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+    filetime::set_file_mtime(&script, filetime::FileTime::from_system_time(modified)).unwrap();
+
+    let zip_path = dir_path.join("scripts.zip");
+    service
+        .zip_files(
+            vec![script.to_str().unwrap().to_string()],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
         .await
         .unwrap();
-    assert!(result.contains("Index:"));
-    assert!(result.contains("-line2"));
-    assert!(result.contains("+line4"));
-    let content = tokio_fs::read_to_string(&file_path).await.unwrap();
-    assert_eq!(content, "line1\nline2\nline3"); // Unchanged due to dry run
+
+    let extract_dir = dir_path.join("extracted");
+    service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await
+        .unwrap();
+
+    let metadata = fs::metadata(extract_dir.join("run.sh")).unwrap();
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o755);
+    assert_eq!(metadata.modified().unwrap(), modified);
 }
 
 #[tokio::test]
-async fn test_apply_file_edits_no_match() {
+async fn test_unzip_file_rejects_archive_over_max_extract_size() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = create_temp_file(
-        temp_dir.join("dir1").as_path(),
+    let service = service.with_max_extract_size(Some(1000));
+    let dir_path = temp_dir.join("dir1");
+    let file = create_temp_file(&dir_path, "large.txt", &"a".repeat(5000));
+    let zip_path = dir_path.join("large.zip");
+    service
+        .zip_files(
+            vec![file.to_str().unwrap().to_string()],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let extract_dir = dir_path.join("extracted");
+    let result = service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::ExtractSizeExceeded {
+            size: 5000,
+            limit: 1000
+        })
+    ));
+    assert!(!extract_dir.exists());
+}
+
+#[tokio::test]
+async fn test_unzip_file_rejects_entry_larger_than_recorded() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let file = create_temp_file(&dir_path, "large.txt", &"a".repeat(5000));
+    let zip_path = dir_path.join("understated.zip");
+    service
+        .zip_files(
+            vec![file.to_str().unwrap().to_string()],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    // Rewrite the uncompressed size recorded in the central directory from 5000 to 10 bytes
+    let mut bytes = fs::read(&zip_path).unwrap();
+    let central_header = bytes
+        .windows(4)
+        .position(|window| window == [0x50, 0x4b, 0x01, 0x02])
+        .unwrap();
+    let size_field = central_header + 24;
+    assert_eq!(
+        u32::from_le_bytes(bytes[size_field..size_field + 4].try_into().unwrap()),
+        5000
+    );
+    bytes[size_field..size_field + 4].copy_from_slice(&10u32.to_le_bytes());
+    fs::write(&zip_path, bytes).unwrap();
+
+    let extract_dir = dir_path.join("extracted");
+    let result = service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::ExtractSizeExceeded {
+            size: 11,
+            limit: 10
+        })
+    ));
+    assert!(!extract_dir.exists());
+}
+
+#[tokio::test]
+async fn test_unzip_file_rejects_high_compression_ratio() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let file = create_temp_file(&dir_path, "zeros.bin", &"\0".repeat(4 * 1024 * 1024));
+    let zip_path = dir_path.join("bomb.zip");
+    service
+        .zip_files(
+            vec![file.to_str().unwrap().to_string()],
+            zip_path.to_str().unwrap().to_string(),
+            false,
+            false,
+            None,
+        )
+        .await
+        .unwrap();
+
+    let extract_dir = dir_path.join("extracted");
+    let result = service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await;
+    assert!(matches!(
+        result,
+        Err(ServiceError::CompressionRatioExceeded { ref entry, .. }) if entry == "zeros.bin"
+    ));
+    assert!(!extract_dir.exists());
+}
+
+#[tokio::test]
+async fn test_unzip_file_non_existent() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let temp_dir = temp_dir.join("dir1");
+    let zip_path = temp_dir.join("non_existent.zip");
+    let extract_dir = temp_dir.join("extracted");
+    let result = service
+        .unzip_file(
+            zip_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            None,
+        )
+        .await;
+
+    assert!(matches!(
+        result,
+        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::NotFound
+    ));
+}
+
+#[tokio::test]
+async fn test_tail_follow_delivers_appended_lines_in_order() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let log_path = create_temp_file(&dir_path, "app.log", "existing line\n");
+    let cancel = CancellationToken::new();
+    let mut lines: Vec<String> = vec![];
+
+    let follow = service.tail_follow(
+        &log_path,
+        Duration::from_millis(10),
+        cancel.clone(),
+        |line| lines.push(line),
+    );
+    let write_lines = async {
+        let append = |content: &str| {
+            let mut file = fs::OpenOptions::new().append(true).open(&log_path).unwrap();
+            file.write_all(content.as_bytes()).unwrap();
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        append("first\nsecond\r\nthi");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        append("rd\n");
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Rotate the log: the followed path now refers to a new file
+        fs::rename(&log_path, dir_path.join("app.log.1")).unwrap();
+        fs::write(&log_path, "after rotation\n").unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cancel.cancel();
+    };
+    let (line_count, _) = tokio::join!(follow, write_lines);
+
+    assert_eq!(line_count.unwrap(), 4);
+    assert_eq!(lines, vec!["first", "second", "third", "after rotation"]);
+}
+
+#[tokio::test]
+async fn test_read_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "test.txt", "content");
+    let content = service.read_file(&file_path).await.unwrap();
+    assert_eq!(content, "content");
+}
+
+#[tokio::test]
+async fn test_create_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let new_dir = temp_dir.join("dir1").join("new_dir");
+    let result = service.create_directory(&new_dir).await;
+
+    assert!(result.is_ok());
+    assert!(new_dir.is_dir());
+}
+
+#[tokio::test]
+async fn test_missing_directories() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let new_dir = dir_path.join("a").join("b").join("c");
+
+    let missing = service.missing_directories(&new_dir).await.unwrap();
+    assert_eq!(
+        missing,
+        vec![
+            dir_path.join("a"),
+            dir_path.join("a").join("b"),
+            dir_path.join("a").join("b").join("c"),
+        ]
+    );
+    assert!(!dir_path.join("a").exists());
+
+    service.create_directory(&new_dir).await.unwrap();
+    assert!(service
+        .missing_directories(&new_dir)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let file = create_temp_file(&dir_path, "file.txt", "content");
+    assert!(service
+        .missing_directories(&file.join("sub"))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_move_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let src_path = create_temp_file(temp_dir.join("dir1").as_path(), "src.txt", "content");
+    let dest_path = temp_dir.join("dir1").join("dest.txt");
+    let result = service.move_file(&src_path, &dest_path, None, None).await;
+    assert!(result.is_ok());
+    assert!(!src_path.exists());
+    assert!(dest_path.exists());
+}
+
+#[tokio::test]
+async fn test_batch_move_rolls_back_on_failure() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let a = create_temp_file(&dir_path, "a.txt", "a");
+    let b = create_temp_file(&dir_path, "b.txt", "b");
+    let c = create_temp_file(&dir_path, "c.txt", "c");
+    let taken = create_temp_file(&dir_path, "taken.txt", "existing");
+    let path = |p: &Path| p.to_str().unwrap().to_string();
+    let moves = vec![
+        (path(&a), path(&dir_path.join("a2.txt"))),
+        (path(&b), path(&taken)),
+        (path(&c), path(&dir_path.join("c2.txt"))),
+    ];
+
+    let outcomes = service.batch_move(&moves).await.unwrap();
+
+    assert_eq!(outcomes[0].status, MoveStatus::RolledBack);
+    assert!(matches!(outcomes[1].status, MoveStatus::Failed(_)));
+    assert_eq!(outcomes[2].status, MoveStatus::Skipped);
+    assert_eq!(fs::read_to_string(&a).unwrap(), "a");
+    assert!(!dir_path.join("a2.txt").exists());
+    assert_eq!(fs::read_to_string(&b).unwrap(), "b");
+    assert_eq!(fs::read_to_string(&taken).unwrap(), "existing");
+    assert!(c.exists());
+    assert!(!dir_path.join("c2.txt").exists());
+}
+
+#[tokio::test]
+async fn test_batch_move_validates_before_moving() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let a = create_temp_file(&dir_path, "a.txt", "a");
+    let moves = vec![
+        (
+            a.to_str().unwrap().to_string(),
+            dir_path.join("a2.txt").to_str().unwrap().to_string(),
+        ),
+        (
+            dir_path.join("missing.txt").to_str().unwrap().to_string(),
+            dir_path.join("b.txt").to_str().unwrap().to_string(),
+        ),
+    ];
+
+    assert!(service.batch_move(&moves).await.is_err());
+    assert!(a.exists());
+}
+
+#[tokio::test]
+async fn test_bundle_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("src")).unwrap();
+    fs::create_dir_all(dir_path.join("target")).unwrap();
+    create_temp_file(&dir_path, "a.txt", "alpha");
+    create_temp_file(&dir_path.join("src"), "main.rs", "fn main() {}\n");
+    create_temp_file(&dir_path.join("target"), "out.txt", "build output");
+    fs::write(dir_path.join("image.bin"), [0u8, 1, 2, 3]).unwrap();
+
+    let options = BundleDirectoryOptions {
+        exclude_patterns: vec!["target".to_string()],
+        ..Default::default()
+    };
+    let (bundle, omitted) = service.bundle_directory(&dir_path, &options).await.unwrap();
+    assert_eq!(
+        bundle,
+        "== a.txt ==\nalpha\n== src/main.rs ==\nfn main() {}\n"
+    );
+    assert!(omitted.is_empty());
+
+    let options = BundleDirectoryOptions {
+        max_size: Some(20),
+        ..Default::default()
+    };
+    let (bundle, omitted) = service.bundle_directory(&dir_path, &options).await.unwrap();
+    assert_eq!(bundle, "== a.txt ==\nalpha\n");
+    assert_eq!(omitted, vec!["src/main.rs", "target/out.txt"]);
+}
+
+#[tokio::test]
+async fn test_copy_directory_with_filters() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let source = temp_dir.join("dir1").join("project");
+    fs::create_dir_all(source.join("src").join("nested")).unwrap();
+    fs::create_dir_all(source.join("target")).unwrap();
+    create_temp_file(&source, "README.md", "readme");
+    create_temp_file(&source.join("src"), "lib.rs", "lib");
+    create_temp_file(&source.join("src").join("nested"), "mod.rs", "nested");
+    create_temp_file(&source.join("src"), "notes.txt", "notes");
+    create_temp_file(&source.join("target"), "build.rs", "build");
+    let destination = temp_dir.join("dir1").join("copy");
+    fs::create_dir_all(destination.join("src")).unwrap();
+    create_temp_file(&destination.join("src"), "lib.rs", "existing");
+
+    let options = CopyDirectoryOptions {
+        include_patterns: vec!["**/*.rs".to_string()],
+        exclude_patterns: vec!["target".to_string()],
+        ..Default::default()
+    };
+    let (copied, skipped) = service
+        .copy_directory(&source, &destination, &options)
+        .await
+        .unwrap();
+    assert_eq!(copied, vec!["src/nested/mod.rs"]);
+    assert_eq!(skipped, vec!["src/lib.rs"]);
+    assert_eq!(
+        fs::read_to_string(destination.join("src/nested/mod.rs")).unwrap(),
+        "nested"
+    );
+    assert_eq!(
+        fs::read_to_string(destination.join("src/lib.rs")).unwrap(),
+        "existing"
+    );
+    assert!(!destination.join("README.md").exists());
+    assert!(!destination.join("src/notes.txt").exists());
+    assert!(!destination.join("target").exists());
+
+    let options = CopyDirectoryOptions {
+        overwrite: true,
+        ..options
+    };
+    let (copied, _) = service
+        .copy_directory(&source, &destination, &options)
+        .await
+        .unwrap();
+    assert_eq!(copied, vec!["src/lib.rs", "src/nested/mod.rs"]);
+    assert_eq!(
+        fs::read_to_string(destination.join("src/lib.rs")).unwrap(),
+        "lib"
+    );
+
+    let inside = source.join("backup");
+    assert!(service
+        .copy_directory(&source, &inside, &options)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_replace_in_files() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let a = create_temp_file(&dir_path, "a.txt", "use OLD_NAME;\nOLD_NAME();\n");
+    fs::create_dir_all(dir_path.join("nested")).unwrap();
+    let b = create_temp_file(&dir_path.join("nested"), "b.txt", "call OLD_NAME\n");
+    let skipped = create_temp_file(&dir_path, "c.md", "OLD_NAME\n");
+    let options = ReplaceInFilesOptions {
+        include_patterns: vec!["**/*.txt".to_string()],
+        dry_run: true,
+        ..Default::default()
+    };
+
+    let (diff, changed) = service
+        .replace_in_files(&dir_path, "OLD_NAME", "new_name", &options)
+        .await
+        .unwrap();
+    assert_eq!(changed, 2);
+    assert!(diff.contains("+call new_name"));
+    assert!(fs::read_to_string(&a).unwrap().contains("OLD_NAME"));
+
+    let options = ReplaceInFilesOptions {
+        dry_run: false,
+        ..options
+    };
+    let (_, changed) = service
+        .replace_in_files(&dir_path, "OLD_NAME", "new_name", &options)
+        .await
+        .unwrap();
+    assert_eq!(changed, 2);
+    assert_eq!(
+        fs::read_to_string(&a).unwrap(),
+        "use new_name;\nnew_name();\n"
+    );
+    assert_eq!(fs::read_to_string(&b).unwrap(), "call new_name\n");
+    assert_eq!(fs::read_to_string(&skipped).unwrap(), "OLD_NAME\n");
+}
+
+#[tokio::test]
+async fn test_replace_in_files_checks_write_access_first() {
+    let temp_dir = get_temp_dir();
+    let root = temp_dir.join("project");
+    let read_only_dir = root.join("vendor");
+    fs::create_dir_all(&read_only_dir).unwrap();
+    let writable = create_temp_file(&root, "a.txt", "OLD_NAME\n");
+    let read_only = create_temp_file(&read_only_dir, "b.txt", "OLD_NAME\n");
+    let service = FileSystemService::try_new(&[
+        format!("{}:rw", root.display()),
+        format!("{}:ro", read_only_dir.display()),
+    ])
+    .unwrap();
+
+    let result = service
+        .replace_in_files(
+            &root,
+            "OLD_NAME",
+            "new_name",
+            &ReplaceInFilesOptions::default(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&writable).unwrap(), "OLD_NAME\n");
+    assert_eq!(fs::read_to_string(&read_only).unwrap(), "OLD_NAME\n");
+
+    // A dry run only reads, so it still previews both files
+    let options = ReplaceInFilesOptions {
+        dry_run: true,
+        ..Default::default()
+    };
+    let (_, changed) = service
+        .replace_in_files(&root, "OLD_NAME", "new_name", &options)
+        .await
+        .unwrap();
+    assert_eq!(changed, 2);
+}
+
+#[test]
+fn test_largest_files() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let root = temp_dir.join("dir1");
+    fs::create_dir_all(root.join("nested")).unwrap();
+    create_temp_file(&root, "small.txt", &"a".repeat(10));
+    let medium = create_temp_file(&root.join("nested"), "medium.log", &"b".repeat(500));
+    let large = create_temp_file(&root, "large.bin", &"c".repeat(2000));
+    create_temp_file(&root, "tiny.log", "d");
+
+    let files = service.largest_files(&root, 2, None).unwrap();
+    assert_eq!(files, vec![(large, 2000), (medium.clone(), 500)]);
+
+    let logs = service.largest_files(&root, 5, Some("**/*.log")).unwrap();
+    assert_eq!(logs.len(), 2);
+    assert_eq!(logs[0], (medium, 500));
+}
+
+#[test]
+fn test_find_empty_dirs() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let root = temp_dir.join("dir1");
+    fs::create_dir_all(root.join("empty").join("nested")).unwrap();
+    fs::create_dir_all(root.join("full").join("also_empty")).unwrap();
+    create_temp_file(&root.join("full"), "keep.txt", "content");
+
+    let empty_dirs = service.find_empty_dirs(&root, false).unwrap();
+    let position = |dir: PathBuf| empty_dirs.iter().position(|found| *found == dir);
+    assert_eq!(empty_dirs.len(), 3);
+    assert!(position(root.join("full").join("also_empty")).is_some());
+    // Nested directories are listed before their parents
+    assert!(position(root.join("empty").join("nested")) < position(root.join("empty")));
+    assert!(position(root.join("full")).is_none());
+    assert!(root.join("empty").join("nested").exists());
+}
+
+#[test]
+fn test_find_empty_dirs_prune() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let root = temp_dir.join("dir1");
+    fs::create_dir_all(root.join("empty").join("nested")).unwrap();
+    fs::create_dir_all(root.join("full")).unwrap();
+    create_temp_file(&root.join("full"), "keep.txt", "content");
+
+    let removed = service.find_empty_dirs(&root, true).unwrap();
+    assert_eq!(removed.len(), 2);
+    assert!(!root.join("empty").exists());
+    assert!(root.join("full").join("keep.txt").exists());
+    assert!(root.exists());
+}
+
+#[tokio::test]
+async fn test_move_file_dry_run() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let src_path = create_temp_file(temp_dir.join("dir1").as_path(), "src.txt", "content");
+    let dest_path = temp_dir.join("dir1").join("dest.txt");
+
+    let result = service
+        .move_file(&src_path, &dest_path, Some(true), None)
+        .await;
+    assert!(result.is_ok());
+    assert!(src_path.exists());
+    assert!(!dest_path.exists());
+
+    // Validation still applies during a dry run
+    let missing = temp_dir.join("dir1").join("missing.txt");
+    assert!(service
+        .move_file(&missing, &dest_path, Some(true), None)
+        .await
+        .is_err());
+    let outside = temp_dir.join("dir2").join("dest.txt");
+    assert!(service
+        .move_file(&src_path, &outside, Some(true), None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_move_file_on_conflict() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let dest_path = create_temp_file(&dir_path, "report.txt", "existing");
+
+    // `error` is the default
+    let src_path = create_temp_file(&dir_path, "src.txt", "new");
+    let result = service.move_file(&src_path, &dest_path, None, None).await;
+    assert!(result.is_err());
+    let result = service
+        .move_file(&src_path, &dest_path, None, Some("error"))
+        .await;
+    assert!(result.is_err());
+    assert!(src_path.exists());
+    assert_eq!(fs::read_to_string(&dest_path).unwrap(), "existing");
+
+    // `rename` picks the next free name
+    let final_path = service
+        .move_file(&src_path, &dest_path, None, Some("rename"))
+        .await
+        .unwrap();
+    assert_eq!(final_path, dir_path.join("report (1).txt"));
+    assert_eq!(fs::read_to_string(&final_path).unwrap(), "new");
+    let src_path = create_temp_file(&dir_path, "src.txt", "newer");
+    let final_path = service
+        .move_file(&src_path, &dest_path, None, Some("rename"))
+        .await
+        .unwrap();
+    assert_eq!(final_path, dir_path.join("report (2).txt"));
+    assert_eq!(fs::read_to_string(&dest_path).unwrap(), "existing");
+
+    // `overwrite` replaces the destination
+    let src_path = create_temp_file(&dir_path, "src.txt", "replacement");
+    let final_path = service
+        .move_file(&src_path, &dest_path, None, Some("overwrite"))
+        .await
+        .unwrap();
+    assert_eq!(final_path, dest_path);
+    assert!(!src_path.exists());
+    assert_eq!(fs::read_to_string(&dest_path).unwrap(), "replacement");
+
+    let src_path = create_temp_file(&dir_path, "src.txt", "content");
+    let result = service
+        .move_file(&src_path, &dest_path, None, Some("merge"))
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_merge_move_directories() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let src_dir = temp_dir.join("dir1").join("src");
+    let dest_dir = temp_dir.join("dir1").join("dest");
+    fs::create_dir_all(src_dir.join("sub")).unwrap();
+    fs::create_dir_all(dest_dir.join("sub")).unwrap();
+    create_temp_file(&src_dir, "new.txt", "new");
+    create_temp_file(&src_dir.join("sub"), "shared.txt", "from src");
+    create_temp_file(&dest_dir.join("sub"), "shared.txt", "from dest");
+
+    // `error` leaves both directories untouched
+    let result = service.merge_move(&src_dir, &dest_dir, None, None).await;
+    assert!(result.is_err());
+    assert!(src_dir.join("new.txt").exists());
+    assert!(!dest_dir.join("new.txt").exists());
+
+    // `rename` keeps the existing file and moves the new one next to it
+    let final_path = service
+        .merge_move(&src_dir, &dest_dir, None, Some("rename"))
+        .await
+        .unwrap();
+    assert_eq!(final_path, dest_dir);
+    assert!(!src_dir.exists());
+    assert_eq!(fs::read_to_string(dest_dir.join("new.txt")).unwrap(), "new");
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("sub").join("shared.txt")).unwrap(),
+        "from dest"
+    );
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("sub").join("shared (1).txt")).unwrap(),
+        "from src"
+    );
+
+    // `overwrite` replaces the existing file
+    fs::create_dir_all(src_dir.join("sub")).unwrap();
+    create_temp_file(&src_dir.join("sub"), "shared.txt", "replacement");
+    service
+        .merge_move(&src_dir, &dest_dir, None, Some("overwrite"))
+        .await
+        .unwrap();
+    assert!(!src_dir.exists());
+    assert_eq!(
+        fs::read_to_string(dest_dir.join("sub").join("shared.txt")).unwrap(),
+        "replacement"
+    );
+
+    // A directory cannot be merged into one of its own subdirectories
+    let result = service
+        .merge_move(&dest_dir, &dest_dir.join("sub"), None, Some("rename"))
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_move_file_overwrite_keeps_source_inside_destination() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let parent = temp_dir.join("dir1").join("a");
+    let child = parent.join("b");
+    fs::create_dir_all(&child).unwrap();
+    let file_path = create_temp_file(&child, "keep.txt", "content");
+
+    // Moving a directory onto its parent would delete the source along with the destination
+    let result = service
+        .move_file(&child, &parent, None, Some("overwrite"))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "content");
+
+    // Moving a directory onto itself is rejected as well
+    let result = service
+        .move_file(&child, &child, None, Some("overwrite"))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "content");
+}
+
+#[tokio::test]
+async fn test_move_file_overwrite_restores_destination_on_failure() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let parent = temp_dir.join("dir1").join("a");
+    let child = parent.join("b");
+    fs::create_dir_all(&child).unwrap();
+    let kept = create_temp_file(&child, "kept.txt", "existing");
+
+    // The destination is set aside, then the move fails because a directory cannot be
+    // moved into itself; the destination must be put back
+    let result = service
+        .move_file(&parent, &child, None, Some("overwrite"))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&kept).unwrap(), "existing");
+    assert_eq!(fs::read_dir(&parent).unwrap().count(), 1);
+}
+
+#[tokio::test]
+async fn test_list_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    create_temp_file(&dir_path, "file1.txt", "content1");
+    create_temp_file(&dir_path, "file2.txt", "content2");
+    let entries = service
+        .list_directory(&dir_path, &ListDirectoryOptions::default())
+        .await
+        .unwrap()
+        .entries;
+    let names: Vec<_> = entries
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names.len(), 2);
+    assert!(names.contains(&"file1.txt".to_string()));
+    assert!(names.contains(&"file2.txt".to_string()));
+}
+
+#[tokio::test]
+async fn test_list_directory_pagination() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    for index in 0..25 {
+        create_temp_file(&dir_path, &format!("file{:02}.txt", index), "content");
+    }
+
+    let mut names = Vec::new();
+    let mut offset = Some(0);
+    let mut pages = 0;
+    while let Some(current) = offset {
+        let page = service
+            .list_directory(
+                &dir_path,
+                &ListDirectoryOptions {
+                    offset: Some(current),
+                    limit: Some(10),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        pages += 1;
+        names.extend(
+            page.entries
+                .iter()
+                .map(|e| e.file_name().to_str().unwrap().to_string()),
+        );
+        offset = page.next_offset;
+    }
+
+    assert_eq!(pages, 3);
+    let expected: Vec<_> = (0..25)
+        .map(|index| format!("file{:02}.txt", index))
+        .collect();
+    assert_eq!(names, expected);
+}
+
+#[tokio::test]
+async fn test_list_directory_sorting() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    create_temp_file(&dir_path, "b.txt", &"x".repeat(300));
+    create_temp_file(&dir_path, "a.txt", &"x".repeat(10));
+    create_temp_file(&dir_path, "c.txt", &"x".repeat(2000));
+    fs::create_dir_all(dir_path.join("z_dir")).unwrap();
+
+    let names = |page: rust_mcp_filesystem::fs_service::DirectoryPage| {
+        page.entries
+            .iter()
+            .map(|e| e.file_name().to_str().unwrap().to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let by_name = service
+        .list_directory(
+            &dir_path,
+            &ListDirectoryOptions {
+                sort_by: Some("name".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(by_name), vec!["a.txt", "b.txt", "c.txt", "z_dir"]);
+
+    let by_size_desc = service
+        .list_directory(
+            &dir_path,
+            &ListDirectoryOptions {
+                sort_by: Some("size".to_string()),
+                reverse: true,
+                directories_first: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        names(by_size_desc),
+        vec!["z_dir", "c.txt", "b.txt", "a.txt"]
+    );
+
+    let invalid = service
+        .list_directory(
+            &dir_path,
+            &ListDirectoryOptions {
+                sort_by: Some("color".to_string()),
+                ..Default::default()
+            },
+        )
+        .await;
+    assert!(matches!(invalid, Err(ServiceError::FromString(_))));
+}
+
+#[tokio::test]
+async fn test_write_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("test.txt");
+    let content = "new content".to_string();
+    let result = service.write_file(&file_path, &content, None).await;
+    assert!(result.is_ok());
+    assert_eq!(tokio_fs::read_to_string(&file_path).await.unwrap(), content);
+}
+
+#[test]
+fn test_search_files() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    create_temp_file(&dir_path, "test1.txt", "content");
+    create_temp_file(&dir_path, "test2.doc", "content");
+    let result = service
+        .search_files(&dir_path, &["*.txt".to_string()], &SearchOptions::default())
+        .unwrap();
+    let names: Vec<_> = result
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["test1.txt"]);
+}
+
+#[test]
+fn test_search_files_with_exclude() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    create_temp_file(&dir_path, "test1.txt", "content");
+    create_temp_file(&dir_path, "test2.txt", "content");
+    let result = service
+        .search_files(
+            &dir_path,
+            &["*.txt".to_string()],
+            &SearchOptions {
+                exclude_patterns: vec!["test2.txt".to_string()],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+    let names: Vec<_> = result
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["test1.txt"]);
+}
+
+#[test]
+fn test_search_all_directories() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string(), "dir2".to_string()]);
+    let first = create_temp_file(&temp_dir.join("dir1"), "notes.md", "one");
+    fs::create_dir_all(temp_dir.join("dir2").join("docs")).unwrap();
+    let second = create_temp_file(&temp_dir.join("dir2").join("docs"), "todo.md", "two");
+    create_temp_file(&temp_dir.join("dir2"), "ignored.txt", "three");
+
+    let result = service
+        .search_all_directories(&["*.md".to_string()], &SearchOptions::default())
+        .unwrap();
+    let mut paths: Vec<_> = result.into_iter().map(|e| e.path().to_path_buf()).collect();
+    paths.sort();
+    assert_eq!(paths, vec![first, second]);
+}
+
+#[test]
+fn test_search_files_respect_gitignore() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("target")).unwrap();
+    fs::create_dir_all(dir_path.join("src")).unwrap();
+    create_temp_file(&dir_path, ".gitignore", "target/\n");
+    create_temp_file(&dir_path.join("target"), "build.rs", "");
+    create_temp_file(&dir_path.join("src"), "main.rs", "");
+
+    let names = |respect_gitignore| {
+        let options = SearchOptions {
+            respect_gitignore,
+            ..Default::default()
+        };
+        let mut names: Vec<_> = service
+            .search_files(&dir_path, &["*.rs".to_string()], &options)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.file_name().to_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(false), vec!["build.rs", "main.rs"]);
+    assert_eq!(names(true), vec!["main.rs"]);
+}
+
+#[tokio::test]
+async fn test_list_directory_respect_gitignore() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("node_modules")).unwrap();
+    create_temp_file(&dir_path, ".gitignore", "node_modules\n");
+    create_temp_file(&dir_path, "index.js", "");
+
+    let names = |page: rust_mcp_filesystem::fs_service::DirectoryPage| {
+        let mut names: Vec<_> = page
+            .entries
+            .iter()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    let all = service
+        .list_directory(&dir_path, &ListDirectoryOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(names(all), vec![".gitignore", "index.js", "node_modules"]);
+
+    let options = ListDirectoryOptions {
+        respect_gitignore: true,
+        ..Default::default()
+    };
+    let filtered = service.list_directory(&dir_path, &options).await.unwrap();
+    assert_eq!(names(filtered), vec![".gitignore", "index.js"]);
+}
+
+#[tokio::test]
+async fn test_skip_hidden_entries() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join(".git")).unwrap();
+    create_temp_file(&dir_path.join(".git"), "config.txt", "");
+    create_temp_file(&dir_path, ".env.txt", "");
+    create_temp_file(&dir_path, "main.txt", "");
+
+    let list = |skip_hidden| {
+        let options = ListDirectoryOptions {
+            skip_hidden,
+            ..Default::default()
+        };
+        let service = &service;
+        let dir_path = &dir_path;
+        async move {
+            let mut names: Vec<_> = service
+                .list_directory(dir_path, &options)
+                .await
+                .unwrap()
+                .entries
+                .iter()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names
+        }
+    };
+    assert_eq!(list(false).await, vec![".env.txt", ".git", "main.txt"]);
+    assert_eq!(list(true).await, vec!["main.txt"]);
+
+    let search = |skip_hidden| {
+        let options = SearchOptions {
+            skip_hidden,
+            ..Default::default()
+        };
+        let mut names: Vec<_> = service
+            .search_files(&dir_path, &["*.txt".to_string()], &options)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(search(false), vec![".env.txt", "config.txt", "main.txt"]);
+    // Files inside hidden directories are skipped too
+    assert_eq!(search(true), vec!["main.txt"]);
+
+    // A hidden root is still searched
+    let hidden_root = dir_path.join(".git");
+    let options = SearchOptions {
+        skip_hidden: true,
+        ..Default::default()
+    };
+    let result = service
+        .search_files(&hidden_root, &["*.txt".to_string()], &options)
+        .unwrap();
+    assert_eq!(result.len(), 1);
+}
+
+#[test]
+fn test_search_files_case_sensitivity() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("docs")).unwrap();
+    create_temp_file(&dir_path, "README", "");
+    create_temp_file(&dir_path.join("docs"), "readme", "");
+    create_temp_file(&dir_path.join("docs"), "README.old", "");
+
+    let names = |case_sensitive, exclude_patterns: Vec<&str>| {
+        let options = SearchOptions {
+            case_sensitive,
+            exclude_patterns: exclude_patterns.into_iter().map(String::from).collect(),
+            ..Default::default()
+        };
+        let mut names: Vec<_> = service
+            .search_files(&dir_path, &["README*".to_string()], &options)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.file_name().to_str().unwrap().to_string())
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(false, vec![]), vec!["README", "README.old", "readme"]);
+    assert_eq!(names(true, vec![]), vec!["README", "README.old"]);
+
+    // Exclude patterns follow the same case sensitivity
+    assert_eq!(names(false, vec!["*.OLD"]), vec!["README", "readme"]);
+    assert_eq!(names(true, vec!["*.OLD"]), vec!["README", "README.old"]);
+}
+
+#[test]
+fn test_search_files_multiple_patterns() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("src")).unwrap();
+    create_temp_file(&dir_path, "Cargo.toml", "");
+    create_temp_file(&dir_path.join("src"), "lib.rs", "");
+    create_temp_file(&dir_path, "README.md", "");
+
+    let mut names: Vec<_> = service
+        .search_files(
+            &dir_path,
+            &["*.rs".to_string(), "*.toml".to_string()],
+            &SearchOptions::default(),
+        )
+        .unwrap()
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["Cargo.toml", "lib.rs"]);
+}
+
+#[test]
+fn test_expand_braces() {
+    assert_eq!(expand_braces("*.{txt,md}"), vec!["*.txt", "*.md"]);
+    assert_eq!(
+        expand_braces("{src,tests}/*.{rs,toml}"),
+        vec!["src/*.rs", "src/*.toml", "tests/*.rs", "tests/*.toml"]
+    );
+    assert_eq!(expand_braces("a{b,{c,d}}"), vec!["ab", "ac", "ad"]);
+    assert_eq!(expand_braces("*.txt"), vec!["*.txt"]);
+    assert_eq!(expand_braces("{single}.txt"), vec!["{single}.txt"]);
+    assert_eq!(expand_braces("{a,b"), vec!["{a,b"]);
+}
+
+#[test]
+fn test_search_files_brace_expansion() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    create_temp_file(&dir_path, "notes.txt", "");
+    create_temp_file(&dir_path, "README.md", "");
+    create_temp_file(&dir_path, "main.rs", "");
+    create_temp_file(&dir_path, "draft.md", "");
+
+    let options = SearchOptions {
+        exclude_patterns: vec!["{draft,tmp}*".to_string()],
+        ..Default::default()
+    };
+    let mut names: Vec<_> = service
+        .search_files(&dir_path, &["*.{txt,md}".to_string()], &options)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["README.md", "notes.txt"]);
+}
+
+#[tokio::test]
+async fn test_zip_directory_brace_expansion() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let source_dir = dir_path.join("source");
+    fs::create_dir_all(&source_dir).unwrap();
+    create_temp_file(&source_dir, "notes.txt", "");
+    create_temp_file(&source_dir, "README.md", "");
+    create_temp_file(&source_dir, "main.rs", "");
+
+    let bytes = service
+        .zip_directory_in_memory(source_dir.to_str().unwrap(), "*.{txt,md}", None, None)
+        .await
+        .unwrap();
+    let zip = async_zip::base::read::mem::ZipFileReader::new(bytes)
+        .await
+        .unwrap();
+    let mut names: Vec<String> = zip
+        .file()
+        .entries()
+        .iter()
+        .map(|entry| entry.filename().as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["README.md", "notes.txt"]);
+}
+
+#[test]
+fn test_search_files_matches_per_pattern_globs() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let names = [
+        "main.rs",
+        "Mod.RS",
+        "notes.TXT",
+        "data.json",
+        "metadata.csv",
+        "skip.rs",
+    ];
+    for dir in 0..8 {
+        let sub_dir = dir_path.join(format!("dir{dir}")).join("nested");
+        fs::create_dir_all(&sub_dir).unwrap();
+        for index in 0..25 {
+            let name = names[index % names.len()];
+            create_temp_file(&sub_dir, &format!("{index}_{name}"), "");
+        }
+    }
+
+    let patterns: Vec<String> = ["*.rs", "mod*", "data", "**/*.txt"]
+        .iter()
+        .map(|pattern| pattern.to_string())
+        .collect();
+    let exclude_patterns: Vec<String> = ["skip", "dir3/*"]
+        .iter()
+        .map(|pattern| pattern.to_string())
+        .collect();
+    let options = SearchOptions {
+        exclude_patterns: exclude_patterns.clone(),
+        ..Default::default()
+    };
+    let mut found: Vec<PathBuf> = service
+        .search_files(&dir_path, &patterns, &options)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.into_path())
+        .collect();
+    found.sort();
+
+    // Every pattern tested on its own with `glob::Pattern`
+    let match_options = glob::MatchOptions {
+        case_sensitive: false,
+        ..glob::MatchOptions::new()
+    };
+    let to_pattern = |pattern: &String, partial: &str| {
+        let pattern = if pattern.contains('*') {
+            pattern.clone()
+        } else {
+            partial.replace("{}", pattern)
+        };
+        glob::Pattern::new(&pattern).unwrap()
+    };
+    let excludes: Vec<_> = exclude_patterns
+        .iter()
+        .map(|pattern| to_pattern(pattern, "*{}*"))
+        .collect();
+    let includes: Vec<_> = patterns
+        .iter()
+        .map(|pattern| to_pattern(pattern, "**/*{}*"))
+        .collect();
+    let mut expected: Vec<PathBuf> = walkdir::WalkDir::new(&dir_path)
+        .into_iter()
+        .filter_entry(|entry| {
+            let relative_path = entry.path().strip_prefix(&dir_path).unwrap();
+            !excludes
+                .iter()
+                .any(|glob| glob.matches_with(relative_path.to_str().unwrap(), match_options))
+        })
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path() != dir_path)
+        .filter(|entry| {
+            let file_name = entry.file_name().to_str().unwrap();
+            includes
+                .iter()
+                .any(|glob| glob.matches_with(file_name, match_options))
+        })
+        .map(|entry| entry.into_path())
+        .collect();
+    expected.sort();
+
+    assert_eq!(found.len(), 147);
+    assert_eq!(found, expected);
+}
+
+#[test]
+fn test_search_files_max_depth() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("nested")).unwrap();
+    create_temp_file(&dir_path, "top.log", "");
+    create_temp_file(&dir_path.join("nested"), "deep.log", "");
+
+    let options = SearchOptions {
+        max_depth: Some(1),
+        ..Default::default()
+    };
+    let names: Vec<_> = service
+        .search_files(&dir_path, &["*.log".to_string()], &options)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    assert_eq!(names, vec!["top.log"]);
+}
+
+#[test]
+fn test_search_files_type_filter() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("config").join("config.d")).unwrap();
+    create_temp_file(&dir_path, "config.toml", "");
+
+    let options = SearchOptions {
+        type_filter: Some("dir".to_string()),
+        ..Default::default()
+    };
+    let mut names: Vec<_> = service
+        .search_files(&dir_path, &["config*".to_string()], &options)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["config", "config.d"]);
+
+    let options = SearchOptions {
+        type_filter: Some("symlink".to_string()),
+        ..Default::default()
+    };
+    let invalid = service.search_files(&dir_path, &["config*".to_string()], &options);
+    assert!(matches!(invalid, Err(ServiceError::FromString(_))));
+}
+
+#[test]
+fn test_create_unified_diff() {
+    let (_, service) = setup_service(vec![]);
+    let original = "line1\nline2\nline3".to_string();
+    let new = "line1\nline4\nline3".to_string();
+    let diff = service.create_unified_diff(&original, &new, Some("test.txt".to_string()), None);
+    assert!(diff.contains("Index: test.txt"));
+    assert!(diff.contains("--- test.txt\toriginal"));
+    assert!(diff.contains("+++ test.txt\tmodified"));
+    assert!(diff.contains("-line2"));
+    assert!(diff.contains("+line4"));
+}
+
+#[tokio::test]
+async fn test_apply_file_edits() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
+        "test.txt",
+        "line1\nline2\nline3",
+    );
+    let edits = vec![EditOperation {
+        old_text: "line2".to_string(),
+        new_text: "line4".to_string(),
+        ..Default::default()
+    }];
+    let result = service
+        .apply_file_edits(
+            &file_path,
+            edits,
+            Some(false),
+            None,
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert!(result.contains("Index:"));
+    assert!(result.contains("-line2"));
+    assert!(result.contains("+line4"));
+    let new_content = tokio_fs::read_to_string(&file_path).await.unwrap();
+    assert_eq!(new_content, "line1\nline4\nline3");
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_dry_run() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
+        "test.txt",
+        "line1\nline2\nline3",
+    );
+    let edits = vec![EditOperation {
+        old_text: "line2".to_string(),
+        new_text: "line4".to_string(),
+        ..Default::default()
+    }];
+    let result = service
+        .apply_file_edits(&file_path, edits, Some(true), None, &EditOptions::default())
+        .await
+        .unwrap();
+    assert!(result.contains("Index:"));
+    assert!(result.contains("-line2"));
+    assert!(result.contains("+line4"));
+    let content = tokio_fs::read_to_string(&file_path).await.unwrap();
+    assert_eq!(content, "line1\nline2\nline3"); // Unchanged due to dry run
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_no_match() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
         "test.txt",
         "line1\nline2\nline3",
     );
     let edits = vec![EditOperation {
-        old_text: "non_existent".to_string(),
-        new_text: "line4".to_string(),
+        old_text: "non_existent".to_string(),
+        new_text: "line4".to_string(),
+        ..Default::default()
+    }];
+    let result = service
+        .apply_file_edits(
+            &file_path,
+            edits,
+            Some(false),
+            None,
+            &EditOptions::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(ServiceError::RpcError(_))));
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_reports_closest_match() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
+        "test.txt",
+        "alpha\nbeta\nlet total = count + 1;\nreturn total;\n",
+    );
+    let edits = vec![EditOperation {
+        old_text: "let total = count + 2;\nreturn total;".to_string(),
+        new_text: "return count;".to_string(),
+        ..Default::default()
+    }];
+    let error = service
+        .apply_file_edits(
+            &file_path,
+            edits,
+            Some(false),
+            None,
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Closest match at line 3"));
+    assert!(error.contains("-let total = count + 2;"));
+    assert!(error.contains("+let total = count + 1;"));
+}
+
+#[test]
+fn test_format_system_time() {
+    let now = SystemTime::now();
+    let formatted = format_system_time(now);
+    // Check that the output matches the expected format (e.g., "Sat Apr 12 2025 14:30:45 +00:00")
+    assert!(formatted.contains("202")); // Year should appear
+    assert!(formatted.contains(":")); // Time should have colons
+    assert!(formatted.contains("+") || formatted.contains("-")); // Timezone offset
+}
+
+#[cfg(unix)]
+#[test]
+fn test_format_permissions_unix() {
+    use rust_mcp_filesystem::fs_service::utils::format_permissions;
+
+    let temp_dir = get_temp_dir();
+    let file_path = temp_dir.join("test.txt");
+    File::create(&file_path).unwrap();
+
+    // Set specific permissions (e.g., rw-r--r--)
+    fs::set_permissions(&file_path, fs::Permissions::from_mode(0o644)).unwrap();
+    let metadata = fs::metadata(&file_path).unwrap();
+    let formatted = format_permissions(&metadata);
+    assert_eq!(formatted, "0644");
+
+    // Test directory permissions
+    let dir_metadata = fs::metadata(temp_dir).unwrap();
+    let dir_formatted = format_permissions(&dir_metadata);
+    assert!(dir_formatted.starts_with("0")); // Should be octal
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_set_permissions_unix() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "secret.txt", "content");
+
+    let result = service
+        .set_permissions(&file_path, Some("0600"), None)
+        .await
+        .unwrap();
+    assert_eq!(result, "0600");
+    let mode = fs::metadata(&file_path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let result = service
+        .set_permissions(&file_path, None, Some(true))
+        .await
+        .unwrap();
+    assert_eq!(result, "0400");
+
+    let invalid = service
+        .set_permissions(&file_path, Some("0999"), None)
+        .await;
+    assert!(matches!(invalid, Err(ServiceError::FromString(_))));
+}
+
+#[cfg(windows)]
+#[test]
+fn test_format_permissions_windows() {
+    let temp_dir = get_temp_dir();
+    let file_path = temp_dir.join("test.txt");
+    let mut file = File::create(&file_path).unwrap();
+    file.write_all(b"test").unwrap();
+    file.flush().unwrap();
+
+    // Set read-only
+    let mut perms = fs::metadata(&file_path).unwrap().permissions();
+    perms.set_readonly(true);
+    fs::set_permissions(&file_path, perms).unwrap();
+
+    let metadata = fs::metadata(&file_path).unwrap();
+    let formatted = format_permissions(&metadata);
+    assert_eq!(formatted, "-r"); // Regular file, read-only
+
+    // Test directory
+    let dir_metadata = fs::metadata(temp_dir).unwrap();
+    let dir_formatted = format_permissions(&dir_metadata);
+    assert_eq!(dir_formatted, "dw"); // Directory, typically writable
+}
+
+#[test]
+fn test_normalize_path() {
+    let temp_dir = get_temp_dir();
+    let file_path = temp_dir.join("test.txt");
+    File::create(&file_path).unwrap();
+
+    let normalized = normalize_path(&file_path);
+    assert_eq!(normalized, file_path);
+
+    // Test non-existent path
+    let non_existent = Path::new("/does/not/exist");
+    let normalized_non_existent = normalize_path(non_existent);
+    assert_eq!(normalized_non_existent, non_existent.to_path_buf());
+}
+
+#[test]
+fn test_clean_path() {
+    assert_eq!(clean_path(Path::new("/a/./b/../c")), PathBuf::from("/a/c"));
+    assert_eq!(clean_path(Path::new("/a/../../b")), PathBuf::from("/b"));
+    assert_eq!(clean_path(Path::new("a/../../b")), PathBuf::from("../b"));
+    assert_eq!(clean_path(Path::new("a/..")), PathBuf::from("."));
+}
+
+#[test]
+fn test_expand_home() {
+    // Test with ~ path
+    let home_path = PathBuf::from("~/test");
+    let expanded = expand_home(home_path.clone());
+    if let Some(home) = home_dir() {
+        assert_eq!(expanded, home.join("test"));
+    } else {
+        assert_eq!(expanded, home_path); // No home dir, return original
+    }
+
+    // Test non-~ path
+    let regular_path = PathBuf::from("/absolute/path");
+    let expanded_regular = expand_home(regular_path.clone());
+    assert_eq!(expanded_regular, regular_path);
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(500), "500 bytes");
+    assert_eq!(format_bytes(1024), "1.00 KB");
+    assert_eq!(format_bytes(1500), "1.46 KB");
+    assert_eq!(format_bytes(1024 * 1024), "1.00 MB");
+    assert_eq!(format_bytes(1024 * 1024 * 1024), "1.00 GB");
+    assert_eq!(format_bytes(1024 * 1024 * 1024 * 1024), "1.00 TB");
+    assert_eq!(format_bytes(1500 * 1024 * 1024), "1.46 GB");
+}
+
+#[tokio::test]
+async fn test_write_zip_entry() {
+    let temp_dir = get_temp_dir();
+    let input_path = temp_dir.join("input.txt");
+    let zip_path = temp_dir.join("output.zip");
+
+    // Create a test file
+    let content = b"Hello, zip!";
+    let mut input_file = File::create(&input_path).unwrap();
+    input_file.write_all(content).unwrap();
+    input_file.flush().unwrap();
+
+    // Create zip file
+    let zip_file = tokio::fs::File::create(&zip_path).await.unwrap();
+    let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+
+    // Write zip entry
+    let result = write_zip_entry("test.txt", &input_path, &mut zip_writer).await;
+    assert!(result.is_ok());
+
+    // Close the zip writer
+    zip_writer.close().await.unwrap();
+
+    // Verify the zip file exists and has content
+    let zip_metadata = fs::metadata(&zip_path).unwrap();
+    assert!(zip_metadata.len() > 0);
+}
+
+#[tokio::test]
+async fn test_unzip_file_rejects_entries_outside_target() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let input_path = create_temp_file(&dir_path, "payload.txt", "payload");
+
+    for entry_name in ["../evil.txt", "nested/../../evil.txt", "/tmp/evil.txt"] {
+        let zip_path = dir_path.join("malicious.zip");
+        let zip_file = tokio::fs::File::create(&zip_path).await.unwrap();
+        let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+        write_zip_entry("safe.txt", &input_path, &mut zip_writer)
+            .await
+            .unwrap();
+        write_zip_entry(entry_name, &input_path, &mut zip_writer)
+            .await
+            .unwrap();
+        zip_writer.close().await.unwrap();
+
+        let target_dir = dir_path.join("out");
+        let err = service
+            .unzip_file(
+                zip_path.to_str().unwrap(),
+                target_dir.to_str().unwrap(),
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "ACCESS_DENIED", "{}", entry_name);
+        assert!(!dir_path.join("evil.txt").exists());
+        assert!(!target_dir.exists());
+    }
+}
+
+#[tokio::test]
+async fn test_write_zip_entry_non_existent_file() {
+    let temp_dir = get_temp_dir();
+    let zip_path = temp_dir.join("output.zip");
+    let non_existent_path = temp_dir.join("does_not_exist.txt");
+
+    let zip_file = tokio::fs::File::create(&zip_path).await.unwrap();
+    let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+
+    let result = write_zip_entry("test.txt", &non_existent_path, &mut zip_writer).await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_file_info_for_regular_file() {
+    let (_dir, file_info) = create_temp_file_info(b"Hello, world!");
+    assert_eq!(file_info.size, 13); // "Hello, world!" is 13 bytes
+    assert!(file_info.is_file);
+    assert!(!file_info.is_directory);
+    assert!(file_info.created.is_some());
+    assert!(file_info.modified.is_some());
+    assert!(file_info.accessed.is_some());
+}
+
+#[test]
+fn test_file_info_for_directory() {
+    let (_dir, file_info) = create_temp_dir();
+    assert!(file_info.is_directory);
+    assert!(!file_info.is_file);
+    assert!(file_info.created.is_some());
+    assert!(file_info.modified.is_some());
+    assert!(file_info.accessed.is_some());
+}
+
+#[test]
+fn test_display_format_for_file() {
+    let (_dir, file_info) = create_temp_file_info(b"Test content");
+    let display_output = file_info.to_string();
+
+    // Since permissions and exact times may vary, we just checking the key parts
+    assert!(display_output.contains("size: 12"));
+    assert!(display_output.contains("isDirectory: false"));
+    assert!(display_output.contains("isFile: true"));
+    assert!(display_output.contains("created:"));
+    assert!(display_output.contains("modified:"));
+    assert!(display_output.contains("accessed:"));
+    assert!(display_output.contains("permissions:"));
+}
+
+#[test]
+fn test_display_format_for_empty_timestamps() {
+    // Create a FileInfo with no timestamps
+    let metadata = fs::metadata(".").unwrap();
+    let file_info = FileInfo {
+        size: 123,
+        created: None,
+        modified: None,
+        accessed: None,
+        is_directory: false,
+        is_file: true,
+        is_symlink: false,
+        symlink_target: None,
+        mime_type: None,
+        line_ending: None,
+        encoding: None,
+        #[cfg(unix)]
+        uid: metadata.uid(),
+        #[cfg(unix)]
+        gid: metadata.gid(),
+        metadata: metadata.clone(),
+    };
+
+    let display_output = file_info.to_string();
+
+    // Only key parts
+    assert!(display_output.contains("size: 123"));
+    assert!(display_output.contains("created: \n"));
+    assert!(display_output.contains("modified: \n"));
+    assert!(display_output.contains("accessed: \n"));
+    assert!(display_output.contains("isDirectory: false"));
+    assert!(display_output.contains("isFile: true"));
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_mixed_indentation() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
+        "test_indent.txt",
+        r#"
+            // some descriptions
+			const categories = [
+				{
+					title: 'Подготовка и исследование',
+					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];
+		// some other descriptions
+        "#,
+    );
+    // different indentation
+    let edits = vec![EditOperation {
+        old_text: r#"const categories = [
+				{
+					title: 'Подготовка и исследование',
+						keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];"#
+        .to_string(),
+        new_text: r#"const categories = [
+				{
+					title: 'Подготовка и исследование',
+					description: 'Анализ требований и подготовка к разработке',
+					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];"#
+        .to_string(),
+        ..Default::default()
+    }];
+
+    let out_file = temp_dir.join("dir1").join("out_indent.txt");
+
+    let result = service
+        .apply_file_edits(
+            &file_path,
+            edits,
+            Some(false),
+            Some(out_file.as_path()),
+            &EditOptions::default(),
+        )
+        .await;
+
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_mixed_indentation_2() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        temp_dir.join("dir1").as_path(),
+        "test_indent.txt",
+        r#"
+            // some descriptions
+			const categories = [
+				{
+					title: 'Подготовка и исследование',
+					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];
+		// some other descriptions
+        "#,
+    );
+    // different indentation
+    let edits = vec![EditOperation {
+        old_text: r#"const categories = [
+				{
+					title: 'Подготовка и исследование',
+			keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];"#
+        .to_string(),
+        new_text: r#"const categories = [
+				{
+					title: 'Подготовка и исследование',
+					description: 'Анализ требований и подготовка к разработке',
+					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
+					tasks: [] as any[]
+				},
+			];"#
+        .to_string(),
+        ..Default::default()
+    }];
+
+    let out_file = temp_dir.join("dir1").join("out_indent.txt");
+
+    let result = service
+        .apply_file_edits(
+            &file_path,
+            edits,
+            Some(false),
+            Some(out_file.as_path()),
+            &EditOptions::default(),
+        )
+        .await;
+    assert!(result.is_ok());
+}
+
+#[tokio::test]
+async fn test_exact_match() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "tets_file1.txt",
+        "hello world\n",
+    );
+
+    let edit = EditOperation {
+        old_text: "hello world".to_string(),
+        new_text: "hello universe".to_string(),
+        ..Default::default()
+    };
+
+    let result = service
+        .apply_file_edits(
+            file.as_path(),
+            vec![edit],
+            Some(false),
+            None,
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap();
+
+    let modified_content = fs::read_to_string(file.as_path()).unwrap();
+    assert_eq!(modified_content, "hello universe\n");
+    assert!(result.contains("-hello world\n+hello universe"));
+}
+
+#[tokio::test]
+async fn test_exact_match_edit2() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file1.txt",
+        "hello world\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "hello world\n".into(),
+        new_text: "hello Rust\n".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await;
+
+    assert!(result.is_ok());
+    let updated_content = fs::read_to_string(&file).unwrap();
+    assert_eq!(updated_content, "hello Rust\n");
+}
+
+#[tokio::test]
+async fn test_line_by_line_match_with_indent() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file2.rs",
+        "    let x = 42;\n    println!(\"{}\");\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "let x = 42;\nprintln!(\"{}\");\n".into(),
+        new_text: "let x = 43;\nprintln!(\"x = {}\", x)".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await;
+
+    assert!(result.is_ok());
+
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("let x = 43;"));
+    assert!(content.contains("println!(\"x = {}\", x)"));
+}
+
+#[tokio::test]
+async fn test_dry_run_mode() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file4.sh",
+        "echo hello\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "echo hello\n".into(),
+        new_text: "echo world\n".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(true), None, &EditOptions::default())
+        .await;
+    assert!(result.is_ok());
+
+    let content = fs::read_to_string(&file).unwrap();
+    assert_eq!(content, "echo hello\n"); // Should not be modified
+}
+
+#[tokio::test]
+async fn test_save_to_different_path() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let orig_file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file5.txt",
+        "foo = 1\n",
+    );
+
+    let save_to = temp_dir.as_path().join("dir1").join("saved_output.txt");
+
+    let edits = vec![EditOperation {
+        old_text: "foo = 1\n".into(),
+        new_text: "foo = 2\n".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(
+            &orig_file,
+            edits,
+            Some(false),
+            Some(&save_to),
+            &EditOptions::default(),
+        )
+        .await;
+
+    assert!(result.is_ok());
+
+    let original_content = fs::read_to_string(&orig_file).unwrap();
+    let saved_content = fs::read_to_string(&save_to).unwrap();
+    assert_eq!(original_content, "foo = 1\n");
+    assert_eq!(saved_content, "foo = 2\n");
+}
+
+#[tokio::test]
+async fn test_diff_backtick_formatting() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file6.md",
+        "```\nhello\n```\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "```\nhello\n```".into(),
+        new_text: "```\nworld\n```".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(true), None, &EditOptions::default())
+        .await;
+    assert!(result.is_ok());
+
+    let diff = result.unwrap();
+    assert!(diff.contains("diff"));
+    assert!(diff.starts_with("```")); // Should start with fenced backticks
+}
+
+#[tokio::test]
+async fn test_no_edits_provided() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file7.toml",
+        "enabled = true\n",
+    );
+
+    let result = service
+        .apply_file_edits(&file, vec![], Some(false), None, &EditOptions::default())
+        .await;
+    assert!(result.is_ok());
+
+    let content = fs::read_to_string(&file).unwrap();
+    assert_eq!(content, "enabled = true\n");
+}
+
+#[tokio::test]
+async fn test_preserve_windows_line_endings() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "test_file.txt",
+        "line1\r\nline2\r\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "line1\nline2".into(), // normalized format
+        new_text: "updated1\nupdated2".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await;
+    assert!(result.is_ok());
+
+    let output = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(output, "updated1\r\nupdated2\r\n"); // Line endings preserved!
+}
+
+#[tokio::test]
+async fn test_preserve_mixed_line_endings() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "mixed.txt",
+        "alpha\r\nbeta\ngamma\r\ndelta\nepsilon\r\nzeta",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "gamma".into(),
+        new_text: "gamma1\ngamma2".into(),
+        ..Default::default()
+    }];
+    service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await
+        .unwrap();
+
+    // Untouched lines keep their endings, new lines get the dominant CRLF
+    let output = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(
+        output,
+        "alpha\r\nbeta\ngamma1\r\ngamma2\r\ndelta\nepsilon\r\nzeta"
+    );
+}
+
+#[tokio::test]
+async fn test_preserve_unix_line_endings() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "unix_line_file.txt",
+        "line1\nline2\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "line1\nline2".into(),
+        new_text: "updated1\nupdated2".into(),
+        ..Default::default()
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await;
+
+    assert!(result.is_ok());
+
+    let updated = std::fs::read_to_string(&file).unwrap();
+    assert_eq!(updated, "updated1\nupdated2\n"); // Still uses \n endings
+}
+
+#[tokio::test]
+async fn test_render_template() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let template = create_temp_file(
+        &dir_path,
+        "template.txt",
+        "name = \"{{name}}\"\nversion = \"{{ version }}\"\n",
+    );
+    let output = dir_path.join("output.txt");
+    let variables = HashMap::from([
+        ("name".to_string(), "demo".to_string()),
+        ("version".to_string(), "1.0.0".to_string()),
+    ]);
+
+    let rendered = service
+        .render_template(&template, &variables, &output, None)
+        .await
+        .unwrap();
+
+    assert_eq!(rendered, "name = \"demo\"\nversion = \"1.0.0\"\n");
+    assert_eq!(fs::read_to_string(&output).unwrap(), rendered);
+}
+
+#[tokio::test]
+async fn test_render_template_missing_variable() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let template = create_temp_file(&dir_path, "template.txt", "Hello {{name}}!");
+    let output = dir_path.join("output.txt");
+
+    let result = service
+        .render_template(&template, &HashMap::new(), &output, None)
+        .await;
+
+    assert!(matches!(result, Err(ServiceError::FromString(ref msg)) if msg.contains("name")));
+    assert!(!output.exists());
+}
+
+#[tokio::test]
+async fn test_render_template_default_value_dry_run() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let template = create_temp_file(
+        &dir_path,
+        "template.txt",
+        "Hello {{name|world}}, port {{port|8080}}",
+    );
+    let output = dir_path.join("output.txt");
+    let variables = HashMap::from([("port".to_string(), "3000".to_string())]);
+
+    let rendered = service
+        .render_template(&template, &variables, &output, Some(true))
+        .await
+        .unwrap();
+
+    assert_eq!(rendered, "Hello world, port 3000");
+    assert!(!output.exists());
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_regex_capture_groups() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "regex_file.rs",
+        "let a = foo(1, 2);\nlet b = foo(3, 4);\nlet c = bar(5, 6);\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: r"foo\((\d+), (\d+)\)".into(),
+        new_text: "foo($2, $1)".into(),
+        is_regex: Some(true),
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await
+        .unwrap();
+
+    assert!(result.contains("+let a = foo(2, 1);"));
+    let content = fs::read_to_string(&file).unwrap();
+    assert_eq!(
+        content,
+        "let a = foo(2, 1);\nlet b = foo(4, 3);\nlet c = bar(5, 6);\n"
+    );
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_invalid_regex() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "regex_file.txt",
+        "hello world\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "hello (world".into(),
+        new_text: "hello".into(),
+        is_regex: Some(true),
+    }];
+
+    let result = service
+        .apply_file_edits(&file, edits, Some(false), None, &EditOptions::default())
+        .await;
+
+    assert!(matches!(result, Err(ServiceError::RegexError(_))));
+    assert_eq!(fs::read_to_string(&file).unwrap(), "hello world\n");
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_skip_unmatched() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "partial.txt",
+        "alpha\nbeta\ngamma\n",
+    );
+
+    let edits = vec![
+        EditOperation {
+            old_text: "alpha".into(),
+            new_text: "ALPHA".into(),
+            ..Default::default()
+        },
+        EditOperation {
+            old_text: "delta".into(),
+            new_text: "DELTA".into(),
+            ..Default::default()
+        },
+        EditOperation {
+            old_text: "gamma".into(),
+            new_text: "GAMMA".into(),
+            ..Default::default()
+        },
+    ];
+
+    let result = service
+        .apply_file_edits(
+            &file,
+            edits,
+            Some(false),
+            None,
+            &EditOptions {
+                skip_unmatched: true,
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(result.contains("2/3 edits applied, 1 had no match"));
+    assert!(result.contains("- delta"));
+    let content = fs::read_to_string(&file).unwrap();
+    assert_eq!(content, "ALPHA\nbeta\nGAMMA\n");
+}
+
+#[tokio::test]
+async fn test_apply_multi_file_edits_rolls_back_on_failure() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir = temp_dir.join("dir1");
+    let first = create_temp_file(&dir, "first.rs", "fn old_name() {}\n");
+    let second = create_temp_file(&dir, "second.rs", "fn main() {\n    other();\n}\n");
+    let edit = |old: &str, new: &str| EditOperation {
+        old_text: old.to_string(),
+        new_text: new.to_string(),
+        ..Default::default()
+    };
+
+    let result = service
+        .apply_multi_file_edits(
+            vec![
+                (
+                    first.to_str().unwrap().to_string(),
+                    vec![edit("old_name", "new_name")],
+                ),
+                (
+                    second.to_str().unwrap().to_string(),
+                    vec![edit("old_name();", "new_name();")],
+                ),
+            ],
+            Some(false),
+            &EditOptions::default(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&first).unwrap(), "fn old_name() {}\n");
+    assert_eq!(
+        fs::read_to_string(&second).unwrap(),
+        "fn main() {\n    other();\n}\n"
+    );
+
+    let diff = service
+        .apply_multi_file_edits(
+            vec![
+                (
+                    first.to_str().unwrap().to_string(),
+                    vec![edit("old_name", "new_name")],
+                ),
+                (
+                    second.to_str().unwrap().to_string(),
+                    vec![edit("other();", "new_name();")],
+                ),
+            ],
+            Some(false),
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert!(diff.contains("+fn new_name() {}"));
+    assert!(diff.contains("+    new_name();"));
+    assert_eq!(fs::read_to_string(&first).unwrap(), "fn new_name() {}\n");
+
+    // A failed dry run leaves the files alone, without rewriting them
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    File::options()
+        .write(true)
+        .open(&first)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let result = service
+        .apply_multi_file_edits(
+            vec![
+                (
+                    first.to_str().unwrap().to_string(),
+                    vec![edit("new_name", "newer_name")],
+                ),
+                (
+                    second.to_str().unwrap().to_string(),
+                    vec![edit("missing", "text")],
+                ),
+            ],
+            Some(true),
+            &EditOptions::default(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::metadata(&first).unwrap().modified().unwrap(), modified);
+    assert_eq!(fs::read_to_string(&first).unwrap(), "fn new_name() {}\n");
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_strict_span() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let original = "fn main() {\n    run();\n}\n";
+    let file = create_temp_file(&temp_dir.join("dir1"), "main.rs", original);
+    let strict = EditOptions {
+        strict_span: true,
+        ..Default::default()
+    };
+
+    // Matched up to whitespace, which rewrites the file without its final line break
+    let edits = vec![EditOperation {
+        old_text: "fn main() {\n  run();".into(),
+        new_text: "fn main() {\n  start();".into(),
+        ..Default::default()
     }];
     let result = service
-        .apply_file_edits(&file_path, edits, Some(false), None)
+        .apply_file_edits(&file, edits.clone(), Some(false), None, &strict)
         .await;
-    assert!(matches!(result, Err(ServiceError::RpcError(_))));
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("outside of the expected lines 1-2"), "{err}");
+    assert_eq!(fs::read_to_string(&file).unwrap(), original);
+
+    service
+        .apply_file_edits(&file, edits, Some(true), None, &EditOptions::default())
+        .await
+        .unwrap();
+
+    let edits = vec![EditOperation {
+        old_text: "run();".into(),
+        new_text: "start();".into(),
+        ..Default::default()
+    }];
+    service
+        .apply_file_edits(&file, edits, Some(false), None, &strict)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&file).unwrap(),
+        "fn main() {\n    start();\n}\n"
+    );
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_fuzzy_threshold() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let original = "fn compute(a: i32) -> i32 {\n    // add one to the input value\n    a + 1\n}\n";
+    let file = create_temp_file(&temp_dir.as_path().join("dir1"), "fuzzy.rs", original);
+    let edits = || {
+        vec![EditOperation {
+            old_text: "fn compute(a: i32) -> i32 {\n    // increment the input value\n    a + 1\n}"
+                .to_string(),
+            new_text: "fn compute(a: i32) -> i32 {\n    a + 2\n}".to_string(),
+            ..Default::default()
+        }]
+    };
+    let options = |threshold| EditOptions {
+        fuzzy_threshold: Some(threshold),
+        ..Default::default()
+    };
+
+    let result = service
+        .apply_file_edits(&file, edits(), Some(false), None, &options(0.95))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&file).unwrap(), original);
+
+    let result = service
+        .apply_file_edits(&file, edits(), Some(false), None, &options(0.8))
+        .await
+        .unwrap();
+    assert!(result.contains("Fuzzy match at line 1"));
+    let content = fs::read_to_string(&file).unwrap();
+    assert!(content.contains("a + 2"));
+    assert!(!content.contains("input value"));
+
+    let result = service
+        .apply_file_edits(&file, edits(), Some(false), None, &options(1.5))
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_indentation_report_mixed_tabs_and_spaces() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "indent.py",
+        "def main():\n    a = 1\n\tb = 2\n    if a:\n        c = 3\n      d = 4\n",
+    );
+
+    let report = service.indentation_report(&file).await.unwrap();
+
+    assert_eq!(report.style, IndentStyle::Spaces);
+    assert_eq!(report.width, 4);
+    assert_eq!(report.indented_lines, 5);
+    let deviating: Vec<usize> = report.deviations.iter().map(|d| d.line).collect();
+    assert_eq!(deviating, vec![3, 6]);
+    assert_eq!(report.consistency(), 60.0);
+
+    let output = report.to_string();
+    assert!(output.contains("line 3: uses tabs where spaces are expected"));
+    assert!(output.contains("line 6: indentation of 6 spaces is not a multiple of 4"));
+}
+
+#[tokio::test]
+async fn test_write_atomic_keeps_target_until_rename() {
+    let temp_dir = get_temp_dir();
+    let target = create_temp_file(&temp_dir, "atomic.txt", "old content");
+
+    // Simulate an interruption after the temporary file is written but before the rename
+    let temp_path = write_temp_sibling(&target, b"new content").await.unwrap();
+    assert_eq!(temp_path.parent(), target.parent());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "old content");
+    assert_eq!(fs::read_to_string(&temp_path).unwrap(), "new content");
+    fs::remove_file(&temp_path).unwrap();
+
+    write_atomic(&target, "new content").await.unwrap();
+    assert_eq!(fs::read_to_string(&target).unwrap(), "new content");
+    assert_eq!(fs::read_dir(&temp_dir).unwrap().count(), 1);
 }
 
 #[test]
-fn test_format_system_time() {
-    let now = SystemTime::now();
-    let formatted = format_system_time(now);
-    // Check that the output matches the expected format (e.g., "Sat Apr 12 2025 14:30:45 +00:00")
-    assert!(formatted.contains("202")); // Year should appear
-    assert!(formatted.contains(":")); // Time should have colons
-    assert!(formatted.contains("+") || formatted.contains("-")); // Timezone offset
+fn test_sanitize_filename_traversal_and_separators() {
+    assert_eq!(sanitize_filename("../../etc/passwd"), "etc_passwd");
+    assert_eq!(
+        sanitize_filename("..\\..\\windows\\system.ini"),
+        "windows_system.ini"
+    );
+    assert_eq!(sanitize_filename(".."), "unnamed");
+    assert_eq!(sanitize_filename("report:final?.txt"), "report_final_.txt");
 }
 
-#[cfg(unix)]
 #[test]
-fn test_format_permissions_unix() {
-    use rust_mcp_filesystem::fs_service::utils::format_permissions;
+fn test_sanitize_filename_null_bytes_and_reserved_names() {
+    assert_eq!(sanitize_filename("bad\0name.txt"), "badname.txt");
+    assert_eq!(sanitize_filename("CON"), "_CON");
+    assert_eq!(sanitize_filename("nul.txt"), "_nul.txt");
+    assert_eq!(sanitize_filename("console.txt"), "console.txt");
+    assert_eq!(sanitize_filename("trailing. "), "trailing");
+}
+
+#[test]
+fn test_sanitize_filename_truncates_long_names() {
+    let long_name = format!("{}.txt", "a".repeat(300));
+    let sanitized = sanitize_filename(&long_name);
+    assert_eq!(sanitized.len(), 255);
+    assert!(sanitized.ends_with(".txt"));
+}
+
+#[test]
+fn test_sanitize_filename_in_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let result = service
+        .sanitize_filename("../secret.txt", Some(&dir_path))
+        .unwrap();
+    assert_eq!(result, dir_path.join("secret.txt"));
+
+    let outside = temp_dir.join("dir2");
+    assert!(service.sanitize_filename("a.txt", Some(&outside)).is_err());
+}
+
+#[tokio::test]
+async fn test_apply_file_edits_with_backup() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file = create_temp_file(
+        &temp_dir.as_path().join("dir1"),
+        "config.toml",
+        "enabled = true\n",
+    );
+
+    let edits = vec![EditOperation {
+        old_text: "enabled = true".into(),
+        new_text: "enabled = false".into(),
+        ..Default::default()
+    }];
+
+    service
+        .apply_file_edits(
+            &file,
+            edits,
+            Some(false),
+            None,
+            &EditOptions {
+                backup: Some(true),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
 
+    let backup = temp_dir.join("dir1").join("config.toml.bak");
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "enabled = true\n");
+    assert_eq!(fs::read_to_string(&file).unwrap(), "enabled = false\n");
+}
+
+#[tokio::test]
+async fn test_write_file_with_default_backup() {
     let temp_dir = get_temp_dir();
-    let file_path = temp_dir.join("test.txt");
-    File::create(&file_path).unwrap();
+    let service = FileSystemService::try_new(&[temp_dir.to_str().unwrap().to_string()])
+        .unwrap()
+        .with_backup(true);
+    let file = create_temp_file(&temp_dir, "notes.txt", "original");
 
-    // Set specific permissions (e.g., rw-r--r--)
-    fs::set_permissions(&file_path, fs::Permissions::from_mode(0o644)).unwrap();
-    let metadata = fs::metadata(&file_path).unwrap();
-    let formatted = format_permissions(&metadata);
-    assert_eq!(formatted, "0644");
+    service
+        .write_file(&file, &"updated".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(temp_dir.join("notes.txt.bak")).unwrap(),
+        "original"
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), "updated");
 
-    // Test directory permissions
-    let dir_metadata = fs::metadata(temp_dir).unwrap();
-    let dir_formatted = format_permissions(&dir_metadata);
-    assert!(dir_formatted.starts_with("0")); // Should be octal
+    // A call can opt out of the server default, and new files have nothing to back up
+    let new_file = temp_dir.join("new.txt");
+    service
+        .write_file(&new_file, &"content".to_string(), Some(false))
+        .await
+        .unwrap();
+    assert!(!temp_dir.join("new.txt.bak").exists());
 }
 
-#[cfg(windows)]
 #[test]
-fn test_format_permissions_windows() {
-    let temp_dir = get_temp_dir();
-    let file_path = temp_dir.join("test.txt");
-    let mut file = File::create(&file_path).unwrap();
-    file.write_all(b"test").unwrap();
-    file.flush().unwrap();
+fn test_create_unified_diff_context_lines() {
+    let (_, service) = setup_service(vec![]);
+    let original = "a\nb\nc\nd\ne\nf\ng\n";
+    let new = "a\nb\nc\nD\ne\nf\ng\n";
 
-    // Set read-only
-    let mut perms = fs::metadata(&file_path).unwrap().permissions();
-    perms.set_readonly(true);
-    fs::set_permissions(&file_path, perms).unwrap();
+    let no_context = service.create_unified_diff(original, new, None, Some(0));
+    assert!(no_context.contains("@@ -4 +4 @@"));
+    assert!(!no_context.contains(" c\n"));
+    assert!(!no_context.contains(" e\n"));
 
-    let metadata = fs::metadata(&file_path).unwrap();
-    let formatted = format_permissions(&metadata);
-    assert_eq!(formatted, "-r"); // Regular file, read-only
+    let one_line = service.create_unified_diff(original, new, None, Some(1));
+    assert!(one_line.contains(" c\n-d\n+D\n e\n"));
+    assert!(!one_line.contains(" b\n"));
 
-    // Test directory
-    let dir_metadata = fs::metadata(temp_dir).unwrap();
-    let dir_formatted = format_permissions(&dir_metadata);
-    assert_eq!(dir_formatted, "dw"); // Directory, typically writable
+    let full_context = service.create_unified_diff(original, new, None, Some(10));
+    assert!(full_context.contains(" a\n"));
+    assert!(full_context.contains(" g\n"));
+}
+
+#[tokio::test]
+async fn test_read_file_with_encoding_invalid_utf8() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("latin1.txt");
+    fs::write(&file_path, b"caf\xe9 au lait").unwrap();
+
+    let strict = service.read_file_with_encoding(&file_path, None).await;
+    assert!(matches!(
+        strict,
+        Err(ServiceError::IoError(ref e)) if e.kind() == std::io::ErrorKind::InvalidData
+    ));
+
+    let lossy = service
+        .read_file_with_encoding(&file_path, Some("lossy"))
+        .await
+        .unwrap();
+    assert_eq!(lossy, "caf\u{FFFD} au lait");
+
+    let base64 = service
+        .read_file_with_encoding(&file_path, Some("base64"))
+        .await
+        .unwrap();
+    assert_eq!(base64, "Y2Fm6SBhdSBsYWl0");
+
+    let unsupported = service
+        .read_file_with_encoding(&file_path, Some("utf-16"))
+        .await;
+    assert!(matches!(unsupported, Err(ServiceError::FromString(_))));
+}
+
+#[tokio::test]
+async fn test_read_file_with_hash_matches_independent_digest() {
+    use sha2::{Digest, Sha256, Sha512};
+
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "hashed.txt", "hello hash\n");
+
+    let decoded = service
+        .read_file_decoded(&file_path, None, Some("sha256"))
+        .await
+        .unwrap();
+    assert_eq!(decoded.content, "hello hash\n");
+    assert_eq!(
+        decoded.digest.unwrap(),
+        format!("{:x}", Sha256::digest(b"hello hash\n"))
+    );
+
+    let decoded = service
+        .read_file_decoded(&file_path, None, Some("SHA512"))
+        .await
+        .unwrap();
+    assert_eq!(
+        decoded.digest.unwrap(),
+        format!("{:x}", Sha512::digest(b"hello hash\n"))
+    );
+
+    let unsupported = service
+        .read_file_decoded(&file_path, None, Some("md5"))
+        .await;
+    assert!(matches!(unsupported, Err(ServiceError::FromString(_))));
+}
+
+#[tokio::test]
+async fn test_write_file_if_matches_with_current_hash() {
+    use sha2::{Digest, Sha256};
+
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "shared.txt", "version 1");
+    let expected = format!("{:x}", Sha256::digest(b"version 1"));
+
+    service
+        .write_file_if_matches(&file_path, &"version 2".to_string(), None, &expected)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "version 2");
+}
+
+#[tokio::test]
+async fn test_write_file_if_matches_conflict() {
+    use sha2::{Digest, Sha256};
+
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        &temp_dir.join("dir1"),
+        "shared.txt",
+        "changed by another agent",
+    );
+    let stale = format!("{:x}", Sha256::digest(b"version 1"));
+
+    let result = service
+        .write_file_if_matches(&file_path, &"version 2".to_string(), None, &stale)
+        .await;
+    assert!(matches!(result, Err(ServiceError::WriteConflict { .. })));
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "changed by another agent"
+    );
+}
+
+#[tokio::test]
+async fn test_write_file_if_matches_new_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("new.txt");
+
+    let result = service
+        .write_file_if_matches(&file_path, &"created".to_string(), None, "abc123")
+        .await;
+    assert!(matches!(result, Err(ServiceError::WriteConflict { .. })));
+    assert!(!file_path.exists());
+
+    service
+        .write_file_if_matches(&file_path, &"created".to_string(), None, "")
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "created");
+
+    // The file exists now, so creating it again conflicts
+    let result = service
+        .write_file_if_matches(&file_path, &"again".to_string(), None, "")
+        .await;
+    assert!(matches!(result, Err(ServiceError::WriteConflict { .. })));
 }
 
+#[cfg(unix)]
 #[test]
-fn test_normalize_path() {
+fn test_copy_preserving_metadata_keeps_mode_and_mtime() {
+    use std::os::unix::fs::PermissionsExt;
+
     let temp_dir = get_temp_dir();
-    let file_path = temp_dir.join("test.txt");
-    File::create(&file_path).unwrap();
+    let src_dir = temp_dir.join("src");
+    fs::create_dir_all(src_dir.join("nested")).unwrap();
+    let file = create_temp_file(&src_dir.join("nested"), "script.sh", "#!/bin/sh\n");
+    fs::set_permissions(&file, fs::Permissions::from_mode(0o750)).unwrap();
+    let mtime = filetime::FileTime::from_unix_time(1_600_000_000, 0);
+    filetime::set_file_mtime(&file, mtime).unwrap();
+    filetime::set_file_mtime(&src_dir, mtime).unwrap();
 
-    let normalized = normalize_path(&file_path);
-    assert_eq!(normalized, file_path);
+    let dest_dir = temp_dir.join("dest");
+    copy_preserving_metadata(&src_dir, &dest_dir).unwrap();
 
-    // Test non-existent path
-    let non_existent = Path::new("/does/not/exist");
-    let normalized_non_existent = normalize_path(non_existent);
-    assert_eq!(normalized_non_existent, non_existent.to_path_buf());
+    let copied = fs::metadata(dest_dir.join("nested").join("script.sh")).unwrap();
+    assert_eq!(copied.permissions().mode() & 0o777, 0o750);
+    assert_eq!(
+        filetime::FileTime::from_last_modification_time(&copied),
+        mtime
+    );
+    let copied_dir = fs::metadata(&dest_dir).unwrap();
+    assert_eq!(
+        filetime::FileTime::from_last_modification_time(&copied_dir),
+        mtime
+    );
 }
 
-#[test]
-fn test_expand_home() {
-    // Test with ~ path
-    let home_path = PathBuf::from("~/test");
-    let expanded = expand_home(home_path.clone());
-    if let Some(home) = home_dir() {
-        assert_eq!(expanded, home.join("test"));
-    } else {
-        assert_eq!(expanded, home_path); // No home dir, return original
-    }
+#[tokio::test]
+async fn test_path_status_existing_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "present.txt", "here");
+    let status = service.path_status(&file_path).await.unwrap();
+    assert_eq!(
+        status,
+        PathStatus {
+            exists: true,
+            is_file: true,
+            is_dir: false,
+            is_symlink: false,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_path_status_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let status = service.path_status(&temp_dir.join("dir1")).await.unwrap();
+    assert!(status.exists && status.is_dir && !status.is_file);
+}
+
+#[tokio::test]
+async fn test_path_status_missing_path() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let status = service
+        .path_status(&temp_dir.join("dir1").join("missing.txt"))
+        .await
+        .unwrap();
+    assert_eq!(status, PathStatus::default());
+
+    // Paths outside the allowed directories are still rejected
+    let outside = service.path_status(&temp_dir.join("missing.txt")).await;
+    assert!(outside.is_err());
+}
+
+#[tokio::test]
+async fn test_disk_space() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let space = service.disk_space(&temp_dir.join("dir1")).await.unwrap();
+    assert!(space.available > 0);
+    assert!(space.available <= space.total);
+    assert!(space.used <= space.total);
 
-    // Test non-~ path
-    let regular_path = PathBuf::from("/absolute/path");
-    let expanded_regular = expand_home(regular_path.clone());
-    assert_eq!(expanded_regular, regular_path);
+    let outside = service.disk_space(Path::new("/")).await;
+    assert!(outside.is_err());
 }
 
-#[test]
-fn test_format_bytes() {
-    assert_eq!(format_bytes(500), "500 bytes");
-    assert_eq!(format_bytes(1024), "1.00 KB");
-    assert_eq!(format_bytes(1500), "1.46 KB");
-    assert_eq!(format_bytes(1024 * 1024), "1.00 MB");
-    assert_eq!(format_bytes(1024 * 1024 * 1024), "1.00 GB");
-    assert_eq!(format_bytes(1024 * 1024 * 1024 * 1024), "1.00 TB");
-    assert_eq!(format_bytes(1500 * 1024 * 1024), "1.46 GB");
+#[tokio::test]
+async fn test_read_file_auto_encoding_utf16le_bom() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("utf16.txt");
+    let mut bytes = vec![0xFF, 0xFE];
+    bytes.extend("héllo wörld".encode_utf16().flat_map(u16::to_le_bytes));
+    fs::write(&file_path, bytes).unwrap();
+
+    let decoded = service
+        .read_file_decoded(&file_path, Some("auto"), None)
+        .await
+        .unwrap();
+    assert_eq!(decoded.content, "héllo wörld");
+    assert_eq!(decoded.detected_encoding, Some("UTF-16LE"));
 }
 
 #[tokio::test]
-async fn test_write_zip_entry() {
-    let temp_dir = get_temp_dir();
-    let input_path = temp_dir.join("input.txt");
-    let zip_path = temp_dir.join("output.zip");
+async fn test_read_file_auto_encoding_windows_1252() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("cp1252.txt");
+    fs::write(
+        &file_path,
+        b"Le caf\xe9 est tr\xe8s bon, \x93d\xe9j\xe0 vu\x94 \x96 na\xefve fa\xe7ade.",
+    )
+    .unwrap();
 
-    // Create a test file
-    let content = b"Hello, zip!";
-    let mut input_file = File::create(&input_path).unwrap();
-    input_file.write_all(content).unwrap();
-    input_file.flush().unwrap();
+    let decoded = service
+        .read_file_decoded(&file_path, Some("auto"), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        decoded.content,
+        "Le café est très bon, \u{201C}déjà vu\u{201D} \u{2013} naïve façade."
+    );
+    assert_eq!(decoded.detected_encoding, Some("windows-1252"));
 
-    // Create zip file
-    let zip_file = tokio::fs::File::create(&zip_path).await.unwrap();
-    let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+    // The default stays strict UTF-8
+    let strict = service.read_file_with_encoding(&file_path, None).await;
+    assert!(strict.is_err());
+}
 
-    // Write zip entry
-    let result = write_zip_entry("test.txt", &input_path, &mut zip_writer).await;
-    assert!(result.is_ok());
+#[cfg(unix)]
+#[tokio::test]
+async fn test_real_path_follows_symlink_chain() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir1 = temp_dir.join("dir1");
+    fs::create_dir_all(dir1.join("data").join("nested")).unwrap();
+    let target = create_temp_file(&dir1.join("data").join("nested"), "target.txt", "real");
+    std::os::unix::fs::symlink(&target, dir1.join("first")).unwrap();
+    std::os::unix::fs::symlink(dir1.join("first"), dir1.join("second")).unwrap();
 
-    // Close the zip writer
-    zip_writer.close().await.unwrap();
+    let resolved = service
+        .real_path(&dir1.join("data").join("..").join("second"))
+        .await
+        .unwrap();
+    assert_eq!(resolved, target);
+}
 
-    // Verify the zip file exists and has content
-    let zip_metadata = fs::metadata(&zip_path).unwrap();
-    assert!(zip_metadata.len() > 0);
+#[cfg(unix)]
+#[tokio::test]
+async fn test_real_path_rejects_symlink_escape() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let outside = create_temp_file(&temp_dir, "secret.txt", "outside");
+    let link = temp_dir.join("dir1").join("escape");
+    std::os::unix::fs::symlink(&outside, &link).unwrap();
+
+    let result = service.real_path(&link).await;
+    assert!(result.is_err());
 }
 
 #[tokio::test]
-async fn test_write_zip_entry_non_existent_file() {
-    let temp_dir = get_temp_dir();
-    let zip_path = temp_dir.join("output.zip");
-    let non_existent_path = temp_dir.join("does_not_exist.txt");
+async fn test_read_file_lines_range() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(
+        &temp_dir.join("dir1"),
+        "lines.txt",
+        "one\r\ntwo\r\nthree\r\nfour\r\nfive\r\n",
+    );
 
-    let zip_file = tokio::fs::File::create(&zip_path).await.unwrap();
-    let mut zip_writer = ZipFileWriter::new(zip_file.compat());
+    let lines = service
+        .read_file_lines(&file_path, None, Some(2), Some(4))
+        .await
+        .unwrap();
+    assert_eq!(lines, "two\r\nthree\r\nfour");
 
-    let result = write_zip_entry("test.txt", &non_existent_path, &mut zip_writer).await;
-    assert!(result.is_err());
+    let invalid = service
+        .read_file_lines(&file_path, None, Some(4), Some(2))
+        .await;
+    assert!(matches!(invalid, Err(ServiceError::FromString(_))));
 }
 
-#[test]
-fn test_file_info_for_regular_file() {
-    let (_dir, file_info) = create_temp_file_info(b"Hello, world!");
-    assert_eq!(file_info.size, 13); // "Hello, world!" is 13 bytes
-    assert!(file_info.is_file);
-    assert!(!file_info.is_directory);
-    assert!(file_info.created.is_some());
-    assert!(file_info.modified.is_some());
-    assert!(file_info.accessed.is_some());
+#[tokio::test]
+async fn test_read_file_lines_past_end() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "short.txt", "a\nb\nc");
+
+    let tail = service
+        .read_file_lines(&file_path, None, Some(2), Some(10))
+        .await
+        .unwrap();
+    assert_eq!(tail, "b\nc");
+
+    let beyond = service
+        .read_file_lines(&file_path, None, Some(7), None)
+        .await
+        .unwrap();
+    assert_eq!(beyond, "");
 }
 
-#[test]
-fn test_file_info_for_directory() {
-    let (_dir, file_info) = create_temp_dir();
-    assert!(file_info.is_directory);
-    assert!(!file_info.is_file);
-    assert!(file_info.created.is_some());
-    assert!(file_info.modified.is_some());
-    assert!(file_info.accessed.is_some());
+async fn insert_into(content: &str, line: u64, text: &str) -> ServiceResult<String> {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "insert.rs", content);
+    let insert = InsertOperation {
+        line,
+        text: text.to_string(),
+    };
+    service
+        .apply_edits(
+            &file_path,
+            vec![FileEdit::from(insert)],
+            None,
+            None,
+            &EditOptions::default(),
+        )
+        .await?;
+    Ok(fs::read_to_string(&file_path).unwrap())
 }
 
-#[test]
-fn test_display_format_for_file() {
-    let (_dir, file_info) = create_temp_file_info(b"Test content");
-    let display_output = file_info.to_string();
+#[tokio::test]
+async fn test_insert_at_first_line() {
+    let result = insert_into("fn main() {}\r\n", 1, "use std::fs;\nuse std::io;\n").await;
+    assert_eq!(
+        result.unwrap(),
+        "use std::fs;\r\nuse std::io;\r\nfn main() {}\r\n"
+    );
+}
 
-    // Since permissions and exact times may vary, we just checking the key parts
-    assert!(display_output.contains("size: 12"));
-    assert!(display_output.contains("isDirectory: false"));
-    assert!(display_output.contains("isFile: true"));
-    assert!(display_output.contains("created:"));
-    assert!(display_output.contains("modified:"));
-    assert!(display_output.contains("accessed:"));
-    assert!(display_output.contains("permissions:"));
+#[tokio::test]
+async fn test_insert_mid_file_keeps_indentation() {
+    let result = insert_into("fn main() {\n    a();\n}\n", 3, "    b();").await;
+    assert_eq!(result.unwrap(), "fn main() {\n    a();\n    b();\n}\n");
 }
 
-#[test]
-fn test_display_format_for_empty_timestamps() {
-    // Create a FileInfo with no timestamps
-    let metadata = fs::metadata(".").unwrap();
-    let file_info = FileInfo {
-        size: 123,
-        created: None,
-        modified: None,
-        accessed: None,
-        is_directory: false,
-        is_file: true,
-        metadata: metadata.clone(),
-    };
+#[tokio::test]
+async fn test_insert_after_last_line() {
+    let result = insert_into("one\ntwo\n", 3, "three").await;
+    assert_eq!(result.unwrap(), "one\ntwo\nthree\n");
 
-    let display_output = file_info.to_string();
+    let past_end = insert_into("one\ntwo\n", 4, "four").await;
+    assert!(matches!(past_end, Err(ServiceError::FromString(_))));
+}
 
-    // Only key parts
-    assert!(display_output.contains("size: 123"));
-    assert!(display_output.contains("created: \n"));
-    assert!(display_output.contains("modified: \n"));
-    assert!(display_output.contains("accessed: \n"));
-    assert!(display_output.contains("isDirectory: false"));
-    assert!(display_output.contains("isFile: true"));
+#[tokio::test]
+async fn test_delete_single_line() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "delete.txt", "a\r\nb\r\nc\r\n");
+    let delete = DeleteLinesOperation { start: 2, end: 2 };
+
+    let diff = service
+        .apply_edits(
+            &file_path,
+            vec![FileEdit::from(delete)],
+            None,
+            None,
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert!(diff.contains("-b\n"));
+    assert!(!diff.contains("-a\n"));
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "a\r\nc\r\n");
 }
 
 #[tokio::test]
-async fn test_apply_file_edits_mixed_indentation() {
+async fn test_delete_line_range() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
     let file_path = create_temp_file(
-        temp_dir.join("dir1").as_path(),
-        "test_indent.txt",
-        r#"
-            // some descriptions
-			const categories = [
-				{
-					title: 'Подготовка и исследование',
-					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];
-		// some other descriptions
-        "#,
+        &temp_dir.join("dir1"),
+        "delete.rs",
+        "fn keep() {}\n// start\nfn remove() {}\n// end\nfn also_keep() {}\n",
     );
-    // different indentation
-    let edits = vec![EditOperation {
-        old_text: r#"const categories = [
-				{
-					title: 'Подготовка и исследование',
-						keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];"#
-        .to_string(),
-        new_text: r#"const categories = [
-				{
-					title: 'Подготовка и исследование',
-					description: 'Анализ требований и подготовка к разработке',
-					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];"#
-        .to_string(),
-    }];
+    let delete = DeleteLinesOperation { start: 2, end: 4 };
 
-    let out_file = temp_dir.join("dir1").join("out_indent.txt");
+    let diff = service
+        .apply_edits(
+            &file_path,
+            vec![FileEdit::from(delete)],
+            None,
+            None,
+            &EditOptions::default(),
+        )
+        .await
+        .unwrap();
+    assert!(diff.contains("-// start\n-fn remove() {}\n-// end\n"));
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "fn keep() {}\nfn also_keep() {}\n"
+    );
 
-    let result = service
-        .apply_file_edits(&file_path, edits, Some(false), Some(out_file.as_path()))
-        .await;
+    for (start, end) in [(0, 1), (2, 1), (1, 5)] {
+        let invalid = service
+            .apply_edits(
+                &file_path,
+                vec![FileEdit::from(DeleteLinesOperation { start, end })],
+                None,
+                None,
+                &EditOptions::default(),
+            )
+            .await;
+        assert!(matches!(invalid, Err(ServiceError::FromString(_))));
+    }
+}
 
-    assert!(result.is_ok());
+#[tokio::test]
+async fn test_concat_files_with_separator() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir = temp_dir.join("dir1");
+    let inputs: Vec<String> = [
+        ("a.log", "first\n"),
+        ("b.log", "second\n"),
+        ("c.log", "third"),
+    ]
+    .iter()
+    .map(|(name, content)| {
+        create_temp_file(&dir, name, content)
+            .to_str()
+            .unwrap()
+            .to_string()
+    })
+    .collect();
+    let target = dir.join("all.log");
+
+    let written = service
+        .concat_files(&inputs, &target, Some("---\n"), false)
+        .await
+        .unwrap();
+    let content = fs::read_to_string(&target).unwrap();
+    assert_eq!(content, "first\n---\nsecond\n---\nthird");
+    assert_eq!(written, content.len() as u64);
+
+    // An existing target is only replaced when overwriting is allowed
+    assert!(service
+        .concat_files(&inputs, &target, None, false)
+        .await
+        .is_err());
+    service
+        .concat_files(&inputs, &target, None, true)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&target).unwrap(), "first\nsecond\nthird");
+
+    // The target must not be one of the inputs
+    assert!(service
+        .concat_files(&inputs, Path::new(&inputs[0]), None, true)
+        .await
+        .is_err());
+
+    // ... including when it is named through a `..` segment or a symlink
+    let dotted = dir.join("nested").join("..").join("a.log");
+    fs::create_dir(dir.join("nested")).unwrap();
+    assert!(service
+        .concat_files(&inputs, &dotted, None, true)
+        .await
+        .is_err());
+    #[cfg(unix)]
+    {
+        let alias = dir.join("alias.log");
+        std::os::unix::fs::symlink(&inputs[0], &alias).unwrap();
+        assert!(service
+            .concat_files(&inputs, &alias, None, true)
+            .await
+            .is_err());
+    }
+    assert_eq!(fs::read_to_string(&inputs[0]).unwrap(), "first\n");
 }
 
 #[tokio::test]
-async fn test_apply_file_edits_mixed_indentation_2() {
+async fn test_split_file_by_size() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file_path = create_temp_file(
-        temp_dir.join("dir1").as_path(),
-        "test_indent.txt",
-        r#"
-            // some descriptions
-			const categories = [
-				{
-					title: 'Подготовка и исследование',
-					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];
-		// some other descriptions
-        "#,
-    );
-    // different indentation
-    let edits = vec![EditOperation {
-        old_text: r#"const categories = [
-				{
-					title: 'Подготовка и исследование',
-			keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];"#
-        .to_string(),
-        new_text: r#"const categories = [
-				{
-					title: 'Подготовка и исследование',
-					description: 'Анализ требований и подготовка к разработке',
-					keywords: ['изуч', 'исследов', 'анализ', 'подготов', 'планиров'],
-					tasks: [] as any[]
-				},
-			];"#
-        .to_string(),
-    }];
+    let content = "0123456789".repeat(25);
+    let file = create_temp_file(&temp_dir.join("dir1"), "data.bin", &content);
+    let output_dir = temp_dir.join("dir1").join("parts");
+
+    let parts = service
+        .split_file(&file, &output_dir, Some(100), None)
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0], output_dir.join("data.bin.part0001"));
+    assert_eq!(fs::metadata(&parts[2]).unwrap().len(), 50);
+    let joined: String = parts
+        .iter()
+        .map(|part| fs::read_to_string(part).unwrap())
+        .collect();
+    assert_eq!(joined, content);
+
+    // Existing parts are never overwritten
+    assert!(service
+        .split_file(&file, &output_dir, Some(100), None)
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn test_split_file_by_lines() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let content = "one\ntwo\nthree\nfour\nfive";
+    let file = create_temp_file(&temp_dir.join("dir1"), "lines.txt", content);
+    let output_dir = temp_dir.join("dir1");
+
+    let parts = service
+        .split_file(&file, &output_dir, None, Some(2))
+        .await
+        .unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(fs::read_to_string(&parts[1]).unwrap(), "three\nfour\n");
+    let joined: String = parts
+        .iter()
+        .map(|part| fs::read_to_string(part).unwrap())
+        .collect();
+    assert_eq!(joined, content);
+
+    assert!(service
+        .split_file(&file, &output_dir, Some(10), Some(2))
+        .await
+        .is_err());
+    assert!(service
+        .split_file(&file, &output_dir, None, Some(0))
+        .await
+        .is_err());
+}
 
-    let out_file = temp_dir.join("dir1").join("out_indent.txt");
+#[tokio::test]
+async fn test_create_temp_file_in_allowed_directory() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir = temp_dir.join("dir1");
+
+    let path = service
+        .create_temp_file(Some(&dir), Some("scratch-"), Some(".json"))
+        .await
+        .unwrap();
+    assert!(path.is_file());
+    assert_eq!(path.parent().unwrap(), dir);
+    let name = path.file_name().unwrap().to_str().unwrap();
+    assert!(name.starts_with("scratch-") && name.ends_with(".json"));
+
+    let default_path = service.create_temp_file(None, None, None).await.unwrap();
+    assert!(default_path.starts_with(&dir));
+    assert_ne!(default_path, path);
 
     let result = service
-        .apply_file_edits(&file_path, edits, Some(false), Some(out_file.as_path()))
+        .create_temp_file(Some(&dir), Some("../escape"), None)
         .await;
-    assert!(result.is_ok());
+    assert!(result.is_err());
+    let result = service
+        .create_temp_file(Some(&temp_dir.join("outside")), None, None)
+        .await;
+    assert!(result.is_err());
 }
 
 #[tokio::test]
-async fn test_exact_match() {
+async fn test_touch_creates_new_file() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("new.stamp");
 
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "tets_file1.txt",
-        "hello world\n",
+    let created = service.touch(&file_path, None).await.unwrap();
+    assert!(created);
+    assert!(file_path.is_file());
+    assert_eq!(fs::metadata(&file_path).unwrap().len(), 0);
+}
+
+#[tokio::test]
+async fn test_touch_updates_existing_file() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(temp_dir.join("dir1").as_path(), "old.txt", "content");
+
+    let created = service
+        .touch(&file_path, Some("2020-01-02T03:04:05Z"))
+        .await
+        .unwrap();
+    assert!(!created);
+    let expected = parse_rfc3339("2020-01-02T03:04:05Z").unwrap();
+    assert_eq!(
+        fs::metadata(&file_path).unwrap().modified().unwrap(),
+        expected
     );
 
-    let edit = EditOperation {
-        old_text: "hello world".to_string(),
-        new_text: "hello universe".to_string(),
-    };
+    service.touch(&file_path, None).await.unwrap();
+    assert!(fs::metadata(&file_path).unwrap().modified().unwrap() > expected);
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "content");
+
+    let invalid = service.touch(&file_path, Some("yesterday")).await;
+    assert!(matches!(invalid, Err(ServiceError::FromString(_))));
+}
+
+#[test]
+fn test_find_by_size() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::create_dir_all(dir_path.join("nested")).unwrap();
+    create_temp_file(&dir_path, "small.txt", "tiny");
+    create_temp_file(&dir_path.join("nested"), "large.bin", &"x".repeat(2048));
+    create_temp_file(&dir_path, "medium.txt", &"x".repeat(512));
 
     let result = service
-        .apply_file_edits(file.as_path(), vec![edit], Some(false), None)
-        .await
+        .find(&dir_path, Some(500), None, None, None, None)
         .unwrap();
+    let mut names: Vec<_> = result
+        .iter()
+        .map(|e| e.file_name().to_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["large.bin", "medium.txt"]);
 
-    let modified_content = fs::read_to_string(file.as_path()).unwrap();
-    assert_eq!(modified_content, "hello universe\n");
-    assert!(result.contains("-hello world\n+hello universe"));
+    let result = service
+        .find(&dir_path, Some(100), Some(1000), None, None, Some("file"))
+        .unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].path(), dir_path.join("medium.txt"));
+}
+
+#[test]
+fn test_find_by_modified_time_and_type() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let old_file = create_temp_file(&dir_path, "old.log", "old");
+    create_temp_file(&dir_path, "new.log", "new");
+    fs::create_dir_all(dir_path.join("subdir")).unwrap();
+    let old_time = parse_rfc3339("2020-01-01T00:00:00Z").unwrap();
+    File::options()
+        .write(true)
+        .open(&old_file)
+        .unwrap()
+        .set_modified(old_time)
+        .unwrap();
+
+    let recent = service
+        .find(
+            &dir_path,
+            None,
+            None,
+            Some("2024-01-01T00:00:00Z"),
+            None,
+            Some("file"),
+        )
+        .unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].file_name(), "new.log");
+
+    let old = service
+        .find(
+            &dir_path,
+            None,
+            None,
+            None,
+            Some("2021-01-01T00:00:00Z"),
+            None,
+        )
+        .unwrap();
+    assert_eq!(old.len(), 1);
+    assert_eq!(old[0].path(), old_file);
+
+    let dirs = service
+        .find(&dir_path, None, None, None, None, Some("dir"))
+        .unwrap();
+    assert_eq!(dirs.len(), 1);
+    assert_eq!(dirs[0].file_name(), "subdir");
 }
 
 #[tokio::test]
-async fn test_exact_match_edit2() {
+async fn test_compare_files() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file1.txt",
-        "hello world\n",
-    );
+    let dir_path = temp_dir.join("dir1");
+    let left = create_temp_file(&dir_path, "left.txt", "one\ntwo\nthree\n");
+    let right = create_temp_file(&dir_path, "right.txt", "one\nTWO\nthree\nfour\n");
 
-    let edits = vec![EditOperation {
-        old_text: "hello world\n".into(),
-        new_text: "hello Rust\n".into(),
-    }];
+    let diff = service.compare_files(&left, &right, None).await.unwrap();
+    assert!(diff.contains(&format!("--- {}", left.display())));
+    assert!(diff.contains(&format!("+++ {}", right.display())));
+    assert!(diff.contains("-two\n"));
+    assert!(diff.contains("+TWO\n"));
+    assert!(diff.contains("+four\n"));
+    assert!(diff.contains(" one\n"));
 
-    let result = service
-        .apply_file_edits(&file, edits, Some(false), None)
-        .await;
+    // Files are left untouched
+    assert_eq!(fs::read_to_string(&left).unwrap(), "one\ntwo\nthree\n");
+}
 
-    assert!(result.is_ok());
-    let updated_content = fs::read_to_string(&file).unwrap();
-    assert_eq!(updated_content, "hello Rust\n");
+#[tokio::test]
+async fn test_compare_files_binary() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let left = dir_path.join("left.bin");
+    let right = dir_path.join("right.bin");
+    fs::write(&left, b"abc\0def").unwrap();
+    fs::write(&right, b"abc\0xyz").unwrap();
+
+    let result = service.compare_files(&left, &right, None).await.unwrap();
+    assert!(result.starts_with("Binary files"));
+    assert!(result.ends_with("differ"));
+    let result = service.compare_files(&left, &left, None).await.unwrap();
+    assert!(result.ends_with("are identical"));
 }
 
 #[tokio::test]
-async fn test_line_by_line_match_with_indent() {
+async fn test_compare_files_missing_side() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file2.rs",
-        "    let x = 42;\n    println!(\"{}\");\n",
+    let dir_path = temp_dir.join("dir1");
+    let left = create_temp_file(&dir_path, "left.txt", "content\n");
+    let missing = dir_path.join("missing.txt");
+
+    let result = service.compare_files(&left, &missing, None).await;
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("does not exist"));
+    assert!(err.contains("missing.txt"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_write_atomic_through_symlink() {
+    let (temp_dir, _service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let real_path = create_temp_file(&dir_path, "real.txt", "old");
+    fs::set_permissions(&real_path, fs::Permissions::from_mode(0o640)).unwrap();
+    let link_path = dir_path.join("link.txt");
+    std::os::unix::fs::symlink("real.txt", &link_path).unwrap();
+
+    write_atomic(&link_path, "new").await.unwrap();
+    assert!(fs::symlink_metadata(&link_path).unwrap().is_symlink());
+    assert_eq!(fs::read_to_string(&real_path).unwrap(), "new");
+    assert_eq!(
+        fs::metadata(&real_path).unwrap().permissions().mode() & 0o777,
+        0o640
     );
 
-    let edits = vec![EditOperation {
-        old_text: "let x = 42;\nprintln!(\"{}\");\n".into(),
-        new_text: "let x = 43;\nprintln!(\"x = {}\", x)".into(),
-    }];
+    // Hard links keep sharing the content
+    let hard_link = dir_path.join("hard.txt");
+    fs::hard_link(&real_path, &hard_link).unwrap();
+    write_atomic(&real_path, "newer").await.unwrap();
+    assert_eq!(fs::read_to_string(&hard_link).unwrap(), "newer");
+}
 
-    let result = service
-        .apply_file_edits(&file, edits, Some(false), None)
-        .await;
+#[cfg(unix)]
+#[tokio::test]
+async fn test_write_file_refuses_dangling_symlink() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let outside = temp_dir.join("created-outside.txt");
+    let link_path = temp_dir.join("dir1").join("dangling.txt");
+    std::os::unix::fs::symlink(&outside, &link_path).unwrap();
 
-    assert!(result.is_ok());
+    let result = service.write_file(&link_path, "escaped", None).await;
+    assert!(result.is_err());
+    assert!(!outside.exists());
+    assert!(fs::symlink_metadata(&link_path).unwrap().is_symlink());
+}
 
-    let content = fs::read_to_string(&file).unwrap();
-    assert!(content.contains("let x = 43;"));
-    assert!(content.contains("println!(\"x = {}\", x)"));
+#[tokio::test]
+async fn test_word_count() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let file_path = create_temp_file(&dir_path, "words.txt", "hello world\n  foo bar baz\n\n");
+
+    let count = service.word_count(&file_path).await.unwrap();
+    assert_eq!(count.lines, 3);
+    assert_eq!(count.words, 5);
+    assert_eq!(count.bytes, 27);
 }
 
 #[tokio::test]
-async fn test_dry_run_mode() {
+async fn test_word_count_without_trailing_newline() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file4.sh",
-        "echo hello\n",
-    );
+    let dir_path = temp_dir.join("dir1");
+    let unix = create_temp_file(&dir_path, "unix.txt", "one two\nthree");
+    let windows = create_temp_file(&dir_path, "windows.txt", "one two\r\nthree");
+    let empty = create_temp_file(&dir_path, "empty.txt", "");
 
-    let edits = vec![EditOperation {
-        old_text: "echo hello\n".into(),
-        new_text: "echo world\n".into(),
-    }];
+    let count = service.word_count(&unix).await.unwrap();
+    assert_eq!((count.lines, count.words, count.bytes), (2, 3, 13));
 
-    let result = service
-        .apply_file_edits(&file, edits, Some(true), None)
-        .await;
-    assert!(result.is_ok());
+    let count = service.word_count(&windows).await.unwrap();
+    assert_eq!((count.lines, count.words, count.bytes), (2, 3, 14));
 
-    let content = fs::read_to_string(&file).unwrap();
-    assert_eq!(content, "echo hello\n"); // Should not be modified
+    let count = service.word_count(&empty).await.unwrap();
+    assert_eq!((count.lines, count.words, count.bytes), (0, 0, 0));
 }
 
 #[tokio::test]
-async fn test_save_to_different_path() {
+async fn test_hex_dump() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let orig_file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file5.txt",
-        "foo = 1\n",
+    let file_path = temp_dir.join("dir1").join("header.bin");
+    let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+    bytes.extend(b"Hello, world!");
+    fs::write(&file_path, &bytes).unwrap();
+
+    let dump = service.hex_dump(&file_path, 256).await.unwrap();
+    assert_eq!(
+        dump,
+        concat!(
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n",
+            "00000010  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21           |Hello, world!|\n",
+        )
     );
 
-    let save_to = temp_dir.as_path().join("dir1").join("saved_output.txt");
+    let dump = service.hex_dump(&file_path, 4).await.unwrap();
+    assert_eq!(
+        dump,
+        "00000000  89 50 4e 47                                       |.PNG|\n"
+    );
+    assert_eq!(service.hex_dump(&file_path, 0).await.unwrap(), "");
 
-    let edits = vec![EditOperation {
-        old_text: "foo = 1\n".into(),
-        new_text: "foo = 2\n".into(),
-    }];
+    let outside = temp_dir.join("dir2").join("header.bin");
+    assert!(service.hex_dump(&outside, 16).await.is_err());
+}
 
-    let result = service
-        .apply_file_edits(&orig_file, edits, Some(false), Some(&save_to))
-        .await;
+#[tokio::test]
+async fn test_truncate_file_shrink() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "shrink.txt", "0123456789");
 
-    assert!(result.is_ok());
+    let previous_size = service.truncate_file(&file_path, 4).await.unwrap();
+    assert_eq!(previous_size, 10);
+    assert_eq!(fs::metadata(&file_path).unwrap().len(), 4);
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "0123");
+}
 
-    let original_content = fs::read_to_string(&orig_file).unwrap();
-    let saved_content = fs::read_to_string(&save_to).unwrap();
-    assert_eq!(original_content, "foo = 1\n");
-    assert_eq!(saved_content, "foo = 2\n");
+#[tokio::test]
+async fn test_truncate_file_extend() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "extend.txt", "abc");
+
+    service.truncate_file(&file_path, 8).await.unwrap();
+    assert_eq!(fs::metadata(&file_path).unwrap().len(), 8);
+    assert_eq!(fs::read(&file_path).unwrap(), b"abc\0\0\0\0\0");
 }
 
 #[tokio::test]
-async fn test_diff_backtick_formatting() {
+async fn test_delete_moves_to_trash() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file6.md",
-        "```\nhello\n```\n",
+    let dir_path = temp_dir.join("dir1");
+    let trash_dir = dir_path.join(".trash");
+    let service = service
+        .with_trash_dir(Some(trash_dir.to_str().unwrap()))
+        .unwrap();
+    assert!(trash_dir.is_dir());
+
+    let file_path = create_temp_file(&dir_path, "notes.txt", "first");
+    let trash_path = service.delete(&file_path, false, None).await.unwrap();
+    assert_eq!(trash_path, Some(trash_dir.join("notes.txt")));
+    assert!(!file_path.exists());
+    assert_eq!(
+        fs::read_to_string(trash_dir.join("notes.txt")).unwrap(),
+        "first"
     );
 
-    let edits = vec![EditOperation {
-        old_text: "```\nhello\n```".into(),
-        new_text: "```\nworld\n```".into(),
-    }];
+    // A second item with the same name is renamed instead of replacing the first
+    let file_path = create_temp_file(&dir_path, "notes.txt", "second");
+    let trash_path = service.delete(&file_path, false, None).await.unwrap();
+    assert_eq!(trash_path, Some(trash_dir.join("notes (1).txt")));
+    assert!(!file_path.exists());
+    assert_eq!(
+        fs::read_to_string(trash_dir.join("notes (1).txt")).unwrap(),
+        "second"
+    );
 
-    let result = service
-        .apply_file_edits(&file, edits, Some(true), None)
-        .await;
-    assert!(result.is_ok());
+    // Per-call opt-out deletes permanently
+    let file_path = create_temp_file(&dir_path, "scratch.txt", "scratch");
+    let trash_path = service
+        .delete(&file_path, false, Some(false))
+        .await
+        .unwrap();
+    assert_eq!(trash_path, None);
+    assert!(!file_path.exists());
+    assert!(!trash_dir.join("scratch.txt").exists());
+}
 
-    let diff = result.unwrap();
-    assert!(diff.contains("diff"));
-    assert!(diff.starts_with("```")); // Should start with fenced backticks
+#[tokio::test]
+async fn test_delete_refuses_to_trash_ancestor_of_trash() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let work_dir = temp_dir.join("dir1").join("work");
+    let trash_dir = work_dir.join(".trash");
+    let service = service
+        .with_trash_dir(Some(trash_dir.to_str().unwrap()))
+        .unwrap();
+    let file_path = create_temp_file(&work_dir, "notes.txt", "content");
+
+    let err = service
+        .delete(&work_dir, true, Some(true))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("contains the trash directory"));
+    assert!(file_path.exists());
+    assert!(trash_dir.is_dir());
 }
 
 #[tokio::test]
-async fn test_no_edits_provided() {
+async fn test_delete_without_trash() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file7.toml",
-        "enabled = true\n",
-    );
+    let dir_path = temp_dir.join("dir1");
+    let sub_dir = dir_path.join("sub");
+    fs::create_dir_all(&sub_dir).unwrap();
+    create_temp_file(&sub_dir, "file.txt", "content");
 
-    let result = service
-        .apply_file_edits(&file, vec![], Some(false), None)
-        .await;
-    assert!(result.is_ok());
+    // Trash requested without a configured trash directory
+    assert!(service.delete(&sub_dir, true, Some(true)).await.is_err());
+    // Non-empty directories require `recursive`
+    assert!(service.delete(&sub_dir, false, None).await.is_err());
+    assert!(sub_dir.exists());
+    // Allowed directories cannot be deleted
+    assert!(service.delete(&dir_path, true, None).await.is_err());
 
-    let content = fs::read_to_string(&file).unwrap();
-    assert_eq!(content, "enabled = true\n");
+    assert_eq!(service.delete(&sub_dir, true, None).await.unwrap(), None);
+    assert!(!sub_dir.exists());
 }
 
 #[tokio::test]
-async fn test_preserve_windows_line_endings() {
+async fn test_gzip_round_trip() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "test_file.txt",
-        "line1\r\nline2\r\n",
-    );
+    let dir_path = temp_dir.join("dir1");
+    let content = "The quick brown fox jumps over the lazy dog.\n".repeat(200);
+    let input = create_temp_file(&dir_path, "input.txt", &content);
+    let compressed = dir_path.join("input.txt.gz");
+    let restored = dir_path.join("restored.txt");
 
-    let edits = vec![EditOperation {
-        old_text: "line1\nline2".into(), // normalized format
-        new_text: "updated1\nupdated2".into(),
-    }];
+    service
+        .gzip_file(input.to_str().unwrap(), compressed.to_str().unwrap(), false)
+        .await
+        .unwrap();
+    assert!(fs::metadata(&compressed).unwrap().len() < content.len() as u64);
+    assert_eq!(&fs::read(&compressed).unwrap()[..2], &[0x1f, 0x8b]);
+
+    service
+        .gunzip_file(
+            compressed.to_str().unwrap(),
+            restored.to_str().unwrap(),
+            false,
+        )
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&restored).unwrap(), content);
+}
+
+#[tokio::test]
+async fn test_gzip_refuses_existing_target() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let input = create_temp_file(&dir_path, "input.txt", "content");
+    let target = create_temp_file(&dir_path, "input.txt.gz", "existing");
 
     let result = service
-        .apply_file_edits(&file, edits, Some(false), None)
+        .gzip_file(input.to_str().unwrap(), target.to_str().unwrap(), false)
         .await;
-    assert!(result.is_ok());
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&target).unwrap(), "existing");
 
-    let output = std::fs::read_to_string(&file).unwrap();
-    assert_eq!(output, "updated1\r\nupdated2\r\n"); // Line endings preserved!
+    service
+        .gzip_file(input.to_str().unwrap(), target.to_str().unwrap(), true)
+        .await
+        .unwrap();
+    assert_ne!(fs::read(&target).unwrap(), b"existing");
 }
 
 #[tokio::test]
-async fn test_preserve_unix_line_endings() {
+async fn test_zstd_round_trip_and_levels() {
     let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
-    let file = create_temp_file(
-        &temp_dir.as_path().join("dir1"),
-        "unix_line_file.txt",
-        "line1\nline2\n",
-    );
+    let dir_path = temp_dir.join("dir1");
+    let content: String = (0..2000)
+        .map(|index| {
+            format!(
+                "2025-05-01T12:00:{:02}Z INFO request {} served\n",
+                index % 60,
+                index
+            )
+        })
+        .collect();
+    let input = create_temp_file(&dir_path, "app.log", &content);
+    let fast = dir_path.join("fast.log.zst");
+    let small = dir_path.join("small.log.zst");
+    let restored = dir_path.join("restored.log");
 
-    let edits = vec![EditOperation {
-        old_text: "line1\nline2".into(),
-        new_text: "updated1\nupdated2".into(),
-    }];
+    service
+        .zstd_compress_file(
+            input.to_str().unwrap(),
+            fast.to_str().unwrap(),
+            Some(1),
+            false,
+        )
+        .await
+        .unwrap();
+    service
+        .zstd_compress_file(
+            input.to_str().unwrap(),
+            small.to_str().unwrap(),
+            Some(19),
+            false,
+        )
+        .await
+        .unwrap();
+    let fast_size = fs::metadata(&fast).unwrap().len();
+    let small_size = fs::metadata(&small).unwrap().len();
+    assert!(small_size <= fast_size);
+    assert!(fast_size < content.len() as u64);
+
+    service
+        .zstd_decompress_file(small.to_str().unwrap(), restored.to_str().unwrap(), false)
+        .await
+        .unwrap();
+    assert_eq!(fs::read_to_string(&restored).unwrap(), content);
 
     let result = service
-        .apply_file_edits(&file, edits, Some(false), None)
+        .zstd_compress_file(
+            input.to_str().unwrap(),
+            fast.to_str().unwrap(),
+            Some(99),
+            true,
+        )
         .await;
-
-    assert!(result.is_ok());
-
-    let updated = std::fs::read_to_string(&file).unwrap();
-    assert_eq!(updated, "updated1\nupdated2\n"); // Still uses \n endings
+    assert!(result.is_err());
 }
@@ -0,0 +1,249 @@
+#[path = "common/common.rs"]
+pub mod common;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use common::{get_temp_dir, parse_args};
+use futures::future::join_all;
+use rust_mcp_filesystem::{
+    error::ServiceError,
+    handler::{error_code, error_result, MyServerHandler},
+};
+use rust_mcp_schema::{
+    schema_utils::CallToolError, CallToolRequest, CallToolRequestParams, LoggingLevel,
+};
+
+#[tokio::test]
+async fn test_max_concurrent_operations_queues_excess_calls() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--max-concurrent-operations",
+        "2",
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+
+    let running = AtomicUsize::new(0);
+    let max_running = AtomicUsize::new(0);
+    let (running, max_running) = (&running, &max_running);
+    let results = join_all((0..8).map(|i| {
+        handler.with_operation_permit(async move {
+            let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+            max_running.fetch_max(now_running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            Ok(i)
+        })
+    }))
+    .await;
+
+    assert_eq!(
+        results.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+        (0..8).collect::<Vec<_>>()
+    );
+    assert_eq!(max_running.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn test_max_concurrent_operations_rejects_zero() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--max-concurrent-operations",
+        "0",
+        temp_dir.to_str().unwrap(),
+    ];
+    assert!(MyServerHandler::new(&parse_args(&args).unwrap()).is_err());
+}
+
+fn read_file_request(path: &str) -> CallToolRequest {
+    let arguments = serde_json::json!({ "path": path });
+    CallToolRequest::new(CallToolRequestParams {
+        name: "read_file".to_string(),
+        arguments: arguments.as_object().cloned(),
+    })
+}
+
+#[test]
+fn test_in_memory_zip_does_not_require_write_access() {
+    let temp_dir = get_temp_dir();
+    let handler =
+        MyServerHandler::new(&parse_args(&["mcp-server", temp_dir.to_str().unwrap()]).unwrap())
+            .unwrap();
+    let dir = temp_dir.to_str().unwrap();
+    let zip_request = |name: &str, arguments: serde_json::Value| {
+        handler
+            .parse_tool_call(CallToolRequest::new(CallToolRequestParams {
+                name: name.to_string(),
+                arguments: arguments.as_object().cloned(),
+            }))
+            .unwrap()
+    };
+
+    let in_memory = zip_request(
+        "zip_files",
+        serde_json::json!({ "input_files": [dir], "in_memory": true }),
+    );
+    assert!(!in_memory.require_write_access());
+    let to_file = zip_request(
+        "zip_files",
+        serde_json::json!({ "input_files": [dir], "target_zip_file": "/tmp/out.zip" }),
+    );
+    assert!(to_file.require_write_access());
+
+    let in_memory = zip_request(
+        "zip_directory",
+        serde_json::json!({ "input_directory": dir, "in_memory": true }),
+    );
+    assert!(!in_memory.require_write_access());
+    let to_file = zip_request(
+        "zip_directory",
+        serde_json::json!({ "input_directory": dir, "target_zip_file": "/tmp/out.zip" }),
+    );
+    assert!(to_file.require_write_access());
+}
+
+#[test]
+fn test_edit_file_paths_include_save_to() {
+    let temp_dir = get_temp_dir();
+    let handler =
+        MyServerHandler::new(&parse_args(&["mcp-server", temp_dir.to_str().unwrap()]).unwrap())
+            .unwrap();
+    let arguments = serde_json::json!({
+        "path": "/srv/original.txt",
+        "edits": [],
+        "saveTo": "/srv/copy.txt",
+    });
+    let tool = handler
+        .parse_tool_call(CallToolRequest::new(CallToolRequestParams {
+            name: "edit_file".to_string(),
+            arguments: arguments.as_object().cloned(),
+        }))
+        .unwrap();
+    assert_eq!(tool.paths(), vec!["/srv/original.txt", "/srv/copy.txt"]);
+}
+
+#[test]
+fn test_disabled_tool_is_hidden_and_rejected() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--disable",
+        "read_file",
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+
+    let names: Vec<String> = handler.tools().into_iter().map(|tool| tool.name).collect();
+    assert!(!names.contains(&"read_file".to_string()));
+    assert!(names.contains(&"write_file".to_string()));
+
+    let error = handler
+        .parse_tool_call(read_file_request(temp_dir.to_str().unwrap()))
+        .unwrap_err();
+    assert!(error.to_string().contains("disabled"));
+}
+
+#[test]
+fn test_enable_exposes_only_listed_tools() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--enable",
+        "read_file",
+        "--enable",
+        "list_directory",
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+
+    let mut names: Vec<String> = handler.tools().into_iter().map(|tool| tool.name).collect();
+    names.sort();
+    assert_eq!(names, vec!["list_directory", "read_file"]);
+    assert!(handler
+        .parse_tool_call(read_file_request(temp_dir.to_str().unwrap()))
+        .is_ok());
+}
+
+#[test]
+fn test_unknown_tool_in_disable_is_rejected() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--disable",
+        "no_such_tool",
+        temp_dir.to_str().unwrap(),
+    ];
+    assert!(MyServerHandler::new(&parse_args(&args).unwrap()).is_err());
+}
+
+#[test]
+fn test_failed_tool_call_logs_warning() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--disable",
+        "read_file",
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+    let error = handler
+        .parse_tool_call(read_file_request(temp_dir.to_str().unwrap()))
+        .unwrap_err()
+        .to_string();
+
+    let messages =
+        handler.tool_call_log_messages("read_file", Some(&error), Duration::from_millis(1));
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, LoggingLevel::Warning);
+    assert_eq!(messages[0].data["tool"], "read_file");
+    assert_eq!(messages[0].data["error"], error.as_str());
+
+    let messages = handler.tool_call_log_messages("read_file", None, Duration::from_secs(10));
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0].level, LoggingLevel::Notice);
+
+    // Messages below the level set by the client are dropped
+    handler.set_log_level(LoggingLevel::Error);
+    assert!(handler
+        .tool_call_log_messages("read_file", Some(&error), Duration::from_secs(10))
+        .is_empty());
+}
+
+#[test]
+fn test_error_result_carries_error_code() {
+    let temp_dir = get_temp_dir();
+    let args = [
+        "mcp-server",
+        "--disable",
+        "read_file",
+        temp_dir.to_str().unwrap(),
+    ];
+    let handler = MyServerHandler::new(&parse_args(&args).unwrap()).unwrap();
+    let disabled = handler
+        .parse_tool_call(read_file_request(temp_dir.to_str().unwrap()))
+        .unwrap_err();
+    assert_eq!(error_code(&disabled), "TOOL_DISABLED");
+
+    let read_only = CallToolError::new(ServiceError::NoWriteAccess);
+    let result = error_result(error_code(&read_only), read_only.to_string());
+    assert_eq!(result.is_error, Some(true));
+    assert_eq!(result.meta.unwrap()["errorCode"], "READ_ONLY");
+    assert_eq!(
+        result.content[0].as_text_content().unwrap().text,
+        ServiceError::NoWriteAccess.to_string()
+    );
+
+    // Errors reduced to their code and message keep the original code
+    let not_found = CallToolError::new(ServiceError::NotFound("missing.txt".to_string()));
+    let carried = CallToolError::new(ServiceError::WithCode {
+        code: error_code(&not_found),
+        message: not_found.to_string(),
+    });
+    assert_eq!(error_code(&carried), "NOT_FOUND");
+    assert_eq!(carried.to_string(), not_found.to_string());
+}
@@ -0,0 +1,30 @@
+use std::time::Duration;
+
+use rust_mcp_filesystem::{error::ServiceError, rate_limit::RateLimiter};
+
+#[tokio::test]
+async fn test_rate_limit_rejects_bursts_and_recovers() {
+    let limiter = RateLimiter::new(20.0).unwrap();
+
+    let mut allowed = 0;
+    let error = loop {
+        match limiter.try_acquire() {
+            Ok(()) => allowed += 1,
+            Err(err) => break err,
+        }
+        assert!(allowed <= 25, "rate limit was never hit");
+    };
+    assert!(allowed >= 20);
+    assert!(matches!(error, ServiceError::RateLimited { .. }));
+    assert!(error.to_string().contains("retry"));
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(limiter.try_acquire().is_ok());
+}
+
+#[test]
+fn test_rate_limit_must_be_positive() {
+    assert!(RateLimiter::new(0.0).is_err());
+    assert!(RateLimiter::new(-1.0).is_err());
+    assert!(RateLimiter::new(0.5).is_ok());
+}
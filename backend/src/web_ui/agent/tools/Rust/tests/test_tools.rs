@@ -1,7 +1,10 @@
 #[path = "common/common.rs"]
 pub mod common;
 
+use common::create_temp_file;
 use common::setup_service;
+use rust_mcp_filesystem::fs_service::AccessLevel;
+use rust_mcp_filesystem::handler::error_code;
 use rust_mcp_filesystem::tools::*;
 use rust_mcp_schema::schema_utils::CallToolError;
 use std::fs;
@@ -12,6 +15,7 @@ async fn test_create_directory_new_directory() {
     let new_dir = temp_dir.join("dir1").join("new_dir");
     let params = CreateDirectoryTool {
         path: new_dir.to_str().unwrap().to_string(),
+        dry_run: None,
     };
 
     let result = CreateDirectoryTool::run_tool(params, &service).await;
@@ -44,6 +48,7 @@ async fn test_create_directory_existing_directory() {
     fs::create_dir_all(&existing_dir).unwrap();
     let params = CreateDirectoryTool {
         path: existing_dir.to_str().unwrap().to_string(),
+        dry_run: None,
     };
 
     let result = CreateDirectoryTool::run_tool(params, &service).await;
@@ -75,6 +80,7 @@ async fn test_create_directory_nested() {
     let nested_dir = temp_dir.join("dir1").join("nested/subdir");
     let params = CreateDirectoryTool {
         path: nested_dir.to_str().unwrap().to_string(),
+        dry_run: None,
     };
 
     let result = CreateDirectoryTool::run_tool(params, &service).await;
@@ -104,6 +110,7 @@ async fn test_create_directory_outside_allowed() {
     let outside_dir = temp_dir.join("dir2").join("forbidden");
     let params = CreateDirectoryTool {
         path: outside_dir.to_str().unwrap().to_string(),
+        dry_run: None,
     };
 
     let result = CreateDirectoryTool::run_tool(params, &service).await;
@@ -121,6 +128,7 @@ async fn test_create_directory_invalid_path() {
         path: invalid_path
             .to_str()
             .map_or("invalid\0dir".to_string(), |s| s.to_string()),
+        dry_run: None,
     };
 
     let result = CreateDirectoryTool::run_tool(params, &service).await;
@@ -128,3 +136,472 @@ async fn test_create_directory_invalid_path() {
     let err = result.unwrap_err();
     assert!(matches!(err, CallToolError { .. }));
 }
+
+#[tokio::test]
+async fn test_write_binary_file_round_trip() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("images").join("pixel.png");
+    let png_bytes: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00, 0xFF];
+    let encoded = "iVBORw0KGgoA/w==";
+
+    let params = WriteBinaryFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        content: encoded.to_string(),
+        create_parents: Some(true),
+    };
+    let result = WriteBinaryFileTool::run_tool(params, &service).await;
+    assert!(result.is_ok());
+    assert_eq!(fs::read(&file_path).unwrap(), png_bytes);
+
+    let params = ReadFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        encoding: Some("base64".to_string()),
+        include_hash: None,
+        offset: None,
+        chunk_size: None,
+        start_line: None,
+        end_line: None,
+    };
+    let call_result = ReadFileTool::run_tool(params, &service).await.unwrap();
+    match call_result.content.first().unwrap() {
+        rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) => {
+            assert_eq!(text_content.text, encoded);
+        }
+        _ => panic!("Expected TextContent result"),
+    }
+}
+
+#[tokio::test]
+async fn test_write_binary_file_invalid_base64() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("blob.bin");
+    let params = WriteBinaryFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        content: "not base64!".to_string(),
+        create_parents: None,
+    };
+    let result = WriteBinaryFileTool::run_tool(params, &service).await;
+    assert!(result.is_err());
+    assert!(!file_path.exists());
+}
+
+#[tokio::test]
+async fn test_list_directory_detailed() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::write(dir_path.join("data.txt"), "x".repeat(2048)).unwrap();
+    fs::create_dir_all(dir_path.join("nested")).unwrap();
+
+    let params = ListDirectoryTool {
+        path: dir_path.to_str().unwrap().to_string(),
+        offset: None,
+        limit: None,
+        sort_by: Some("name".to_string()),
+        reverse: None,
+        directories_first: None,
+        detailed: Some(true),
+        include_hidden: None,
+    };
+    let call_result = ListDirectoryTool::run_tool(params, &service).await.unwrap();
+
+    match call_result.content.first().unwrap() {
+        rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) => {
+            let lines: Vec<_> = text_content.text.lines().collect();
+            assert_eq!(lines.len(), 2);
+            assert!(lines[0].starts_with("[FILE] data.txt (2.00 KB, modified "));
+            assert!(lines[0].contains(":")); // Timestamp includes the time of day
+            assert!(lines[1].starts_with("[DIR] nested ("));
+        }
+        _ => panic!("Expected TextContent result"),
+    }
+}
+
+#[tokio::test]
+async fn test_read_multiple_files_bounded_concurrency_keeps_order() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    let paths: Vec<String> = (0..200)
+        .map(|index| {
+            let path = dir_path.join(format!("file{}.txt", index));
+            fs::write(&path, format!("content {}", index)).unwrap();
+            path.to_str().unwrap().to_string()
+        })
+        .collect();
+
+    let params = ReadMultipleFilesTool {
+        paths: paths.clone(),
+        max_concurrency: Some(8),
+    };
+    let call_result = ReadMultipleFilesTool::run_tool(params, &service)
+        .await
+        .unwrap();
+
+    match call_result.content.first().unwrap() {
+        rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) => {
+            let sections: Vec<_> = text_content.text.split("\n---\n").collect();
+            assert_eq!(sections.len(), 200);
+            for (index, section) in sections.iter().enumerate() {
+                assert_eq!(*section, format!("{}:\ncontent {}\n", paths[index], index));
+            }
+        }
+        _ => panic!("Expected TextContent result"),
+    }
+}
+
+#[tokio::test]
+async fn test_get_multiple_file_info_reports_missing_path() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::write(dir_path.join("a.txt"), "alpha").unwrap();
+    fs::create_dir(dir_path.join("sub")).unwrap();
+    let paths = vec![
+        dir_path.join("a.txt").to_str().unwrap().to_string(),
+        dir_path.join("missing.txt").to_str().unwrap().to_string(),
+        dir_path.join("sub").to_str().unwrap().to_string(),
+    ];
+    let params = GetMultipleFileInfoTool {
+        paths: paths.clone(),
+        detect_mime: None,
+    };
+
+    let result = GetMultipleFileInfoTool::run_tool(params, &service)
+        .await
+        .unwrap();
+    let rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) =
+        result.content.first().unwrap()
+    else {
+        panic!("Expected TextContent result");
+    };
+    let infos: Vec<serde_json::Value> = serde_json::from_str(&text_content.text).unwrap();
+
+    assert_eq!(infos.len(), 3);
+    assert_eq!(infos[0]["path"], paths[0]);
+    assert_eq!(infos[0]["size"], 5);
+    assert_eq!(infos[0]["isFile"], true);
+    assert_eq!(infos[1]["path"], paths[1]);
+    assert!(infos[1]["error"].is_string());
+    assert!(infos[1].get("size").is_none());
+    assert_eq!(infos[2]["isDirectory"], true);
+}
+
+#[tokio::test]
+async fn test_get_file_info_json_format() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("info.txt");
+    fs::write(&file_path, "hello").unwrap();
+    let params = GetFileInfoTool {
+        path: file_path.to_str().unwrap().to_string(),
+        detect_mime: None,
+        detect_text_format: None,
+        format: Some("json".to_string()),
+        follow_symlinks: None,
+    };
+
+    let result = GetFileInfoTool::run_tool(params, &service).await.unwrap();
+    let rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) =
+        result.content.first().unwrap()
+    else {
+        panic!("Expected TextContent result");
+    };
+    let info: serde_json::Value = serde_json::from_str(&text_content.text).unwrap();
+
+    assert_eq!(info["size"], 5);
+    assert_eq!(info["isFile"], true);
+    assert_eq!(info["isDirectory"], false);
+    assert_eq!(info["isSymlink"], false);
+    assert!(info["symlinkTarget"].is_null());
+    assert!(info["permissions"].is_string());
+    let modified = info["modified"].as_str().unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(modified).is_ok());
+    assert!(info.get("mimeType").is_none());
+    assert!(info.get("lineEnding").is_none());
+}
+
+#[tokio::test]
+async fn test_get_file_info_invalid_format() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let params = GetFileInfoTool {
+        path: temp_dir.join("dir1").to_str().unwrap().to_string(),
+        detect_mime: None,
+        detect_text_format: None,
+        format: Some("yaml".to_string()),
+        follow_symlinks: None,
+    };
+    let result = GetFileInfoTool::run_tool(params, &service).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_read_multiple_files_skips_binary() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let dir_path = temp_dir.join("dir1");
+    fs::write(dir_path.join("a.txt"), "alpha").unwrap();
+    fs::write(dir_path.join("b.bin"), b"\0\x01\x02").unwrap();
+    let params = ReadMultipleFilesTool {
+        paths: vec![
+            dir_path.join("a.txt").to_str().unwrap().to_string(),
+            dir_path.join("b.bin").to_str().unwrap().to_string(),
+        ],
+        max_concurrency: None,
+    };
+
+    let result = ReadMultipleFilesTool::run_tool(params, &service)
+        .await
+        .unwrap();
+    let rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) =
+        result.content.first().unwrap()
+    else {
+        panic!("Expected TextContent result");
+    };
+    assert!(text_content.text.contains("alpha"));
+    assert!(text_content.text.contains("b.bin: Skipped - binary file"));
+}
+
+#[tokio::test]
+async fn test_read_file_in_chunks() {
+    use base64::{prelude::BASE64_STANDARD, Engine};
+
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("large.bin");
+    let bytes: Vec<u8> = (0..=255u8).cycle().take(1000).collect();
+    fs::write(&file_path, &bytes).unwrap();
+
+    let mut reassembled = vec![];
+    let mut offset = 0;
+    let mut calls = 0;
+    loop {
+        let params = ReadFileTool {
+            path: file_path.to_str().unwrap().to_string(),
+            encoding: None,
+            include_hash: None,
+            offset: Some(offset),
+            chunk_size: Some(400),
+            start_line: None,
+            end_line: None,
+        };
+        let call_result = ReadFileTool::run_tool(params, &service).await.unwrap();
+        let text = match call_result.content.first().unwrap() {
+            rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) => {
+                text_content.text.clone()
+            }
+            _ => panic!("Expected TextContent result"),
+        };
+        let chunk: serde_json::Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(chunk["totalSize"], 1000);
+        assert_eq!(chunk["offset"], offset);
+        let data = BASE64_STANDARD
+            .decode(chunk["content"].as_str().unwrap())
+            .unwrap();
+        offset += data.len() as u64;
+        reassembled.extend(data);
+        calls += 1;
+        if chunk["eof"].as_bool().unwrap() {
+            break;
+        }
+    }
+
+    assert_eq!(calls, 3);
+    assert_eq!(reassembled, bytes);
+}
+
+#[tokio::test]
+async fn test_read_file_rejects_conflicting_options() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = create_temp_file(&temp_dir.join("dir1"), "lines.txt", "one\ntwo\n");
+    let params = ReadFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        encoding: None,
+        include_hash: None,
+        offset: None,
+        chunk_size: None,
+        start_line: None,
+        end_line: None,
+    };
+
+    let conflicting = [
+        ReadFileTool {
+            offset: Some(0),
+            encoding: Some("lossy".to_string()),
+            ..params.clone()
+        },
+        ReadFileTool {
+            chunk_size: Some(4),
+            include_hash: Some("sha256".to_string()),
+            ..params.clone()
+        },
+        ReadFileTool {
+            offset: Some(0),
+            start_line: Some(1),
+            ..params.clone()
+        },
+        ReadFileTool {
+            end_line: Some(1),
+            include_hash: Some("sha256".to_string()),
+            ..params.clone()
+        },
+        ReadFileTool {
+            start_line: Some(1),
+            encoding: Some("base64".to_string()),
+            ..params.clone()
+        },
+    ];
+    for params in conflicting {
+        let err = ReadFileTool::run_tool(params, &service).await.unwrap_err();
+        assert_eq!(error_code(&err), "INVALID_ARGUMENT");
+    }
+
+    // Options that work together are still accepted
+    let params = ReadFileTool {
+        start_line: Some(2),
+        encoding: Some("lossy".to_string()),
+        ..params
+    };
+    assert!(ReadFileTool::run_tool(params, &service).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_server_info_reports_read_only_server() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let service = service.with_default_access(AccessLevel::ReadOnly);
+
+    let call_result = ServerInfoTool::run_tool(ServerInfoTool {}, &service, "2025-03-26")
+        .await
+        .unwrap();
+    let text = match call_result.content.first().unwrap() {
+        rust_mcp_schema::CallToolResultContentItem::TextContent(text_content) => {
+            text_content.text.clone()
+        }
+        _ => panic!("Expected TextContent result"),
+    };
+    let info: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(info["protocolVersion"], "2025-03-26");
+    assert_eq!(info["mode"], "read-only");
+    assert_eq!(
+        info["allowedDirectories"],
+        serde_json::json!([{
+            "path": temp_dir.join("dir1").display().to_string(),
+            "access": "read-only",
+        }])
+    );
+    assert_eq!(info["deny"], serde_json::json!([]));
+    assert!(info["limits"]["maxExtractSize"].is_null());
+}
+
+#[tokio::test]
+async fn test_write_file_utf16le_reads_back_with_auto_encoding() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("legacy.txt");
+    let params = WriteFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        content: "Grüße, 世界".to_string(),
+        backup: None,
+        expected_sha256: None,
+        encoding: Some("utf-16le".to_string()),
+    };
+    WriteFileTool::run_tool(params, &service).await.unwrap();
+    assert_eq!(&fs::read(&file_path).unwrap()[..4], &[0xFF, 0xFE, b'G', 0]);
+
+    let decoded = service
+        .read_file_decoded(&file_path, Some("auto"), None)
+        .await
+        .unwrap();
+    assert_eq!(decoded.content, "Grüße, 世界");
+    assert_eq!(decoded.detected_encoding, Some("UTF-16LE"));
+}
+
+#[tokio::test]
+async fn test_write_file_latin1_rejects_unmappable_characters() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("latin1.txt");
+    let params = WriteFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        content: "café".to_string(),
+        backup: None,
+        expected_sha256: None,
+        encoding: Some("latin1".to_string()),
+    };
+    WriteFileTool::run_tool(params.clone(), &service)
+        .await
+        .unwrap();
+    assert_eq!(fs::read(&file_path).unwrap(), b"caf\xe9");
+
+    let params = WriteFileTool {
+        content: "世界".to_string(),
+        ..params
+    };
+    let result = WriteFileTool::run_tool(params, &service).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn test_edit_file_line_operations_use_original_line_numbers() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("lines.txt");
+    fs::write(&file_path, "1\n2\n3\n4\n5\n").unwrap();
+    let params = EditFileTool {
+        path: file_path.to_str().unwrap().to_string(),
+        edits: vec![],
+        inserts: Some(vec![
+            InsertOperation {
+                line: 1,
+                text: "header".to_string(),
+            },
+            InsertOperation {
+                line: 4,
+                text: "replacement".to_string(),
+            },
+        ]),
+        delete_lines: Some(vec![DeleteLinesOperation { start: 4, end: 5 }]),
+        dry_run: None,
+        save_to: None,
+        skip_unmatched: None,
+        backup: None,
+        context_lines: None,
+        fuzzy_threshold: None,
+        strict_span: None,
+    };
+    EditFileTool::run_tool(params.clone(), &service)
+        .await
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&file_path).unwrap(),
+        "header\n1\n2\n3\nreplacement\n"
+    );
+
+    let overlapping = EditFileTool {
+        inserts: Some(vec![InsertOperation {
+            line: 5,
+            text: "inside".to_string(),
+        }]),
+        ..params
+    };
+    assert!(EditFileTool::run_tool(overlapping, &service).await.is_err());
+}
+
+#[tokio::test]
+async fn test_edit_file_line_operations_from_json() {
+    let (temp_dir, service) = setup_service(vec!["dir1".to_string()]);
+    let file_path = temp_dir.join("dir1").join("lines.txt");
+    fs::write(&file_path, "1\n2\n3\n").unwrap();
+    let params: EditFileTool = serde_json::from_value(serde_json::json!({
+        "path": file_path.to_str().unwrap(),
+        "inserts": [{ "line": 2, "text": "inserted" }],
+        "deleteLines": [{ "start": 3, "end": 3 }],
+        "dryRun": false,
+    }))
+    .unwrap();
+    EditFileTool::run_tool(params, &service).await.unwrap();
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "1\ninserted\n2\n");
+
+    // `saveTo` writes the result elsewhere and leaves the file alone
+    let copy_path = temp_dir.join("dir1").join("copy.txt");
+    let params: EditFileTool = serde_json::from_value(serde_json::json!({
+        "path": file_path.to_str().unwrap(),
+        "deleteLines": [{ "start": 1, "end": 1 }],
+        "saveTo": copy_path.to_str().unwrap(),
+    }))
+    .unwrap();
+    EditFileTool::run_tool(params, &service).await.unwrap();
+    assert_eq!(fs::read_to_string(&copy_path).unwrap(), "inserted\n2\n");
+    assert_eq!(fs::read_to_string(&file_path).unwrap(), "1\ninserted\n2\n");
+}